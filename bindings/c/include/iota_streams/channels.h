@@ -13,6 +13,9 @@ typedef struct ChannelAddress channel_address_t;
 typedef struct MsgId msgid_t;
 typedef struct PublicKey public_key_t;
 typedef struct PskIds psk_ids_t;
+extern void drop_psk_ids(psk_ids_t const *);
+extern size_t psk_ids_len(psk_ids_t const *);
+extern uint8_t const *get_indexed_psk_id(psk_ids_t const *, size_t index);
 typedef struct KePks ke_pks_t;
 
 typedef struct NextMsgIds next_msg_ids_t;
@@ -23,6 +26,14 @@ extern void drop_unwrapped_message(unwrapped_message_t const *);
 
 typedef struct UnwrappedMessages unwrapped_messages_t;
 extern void drop_unwrapped_messages(unwrapped_messages_t const *);
+extern size_t unwrapped_messages_len(unwrapped_messages_t const *);
+
+typedef struct FetchReport fetch_report_t;
+extern void drop_fetch_report(fetch_report_t const *);
+extern unwrapped_messages_t const *fetch_report_msgs(fetch_report_t const *);
+extern size_t fetch_report_errors_len(fetch_report_t const *);
+extern address_t const *get_indexed_error_link(fetch_report_t const *, size_t index);
+extern char const *get_indexed_error_str(fetch_report_t const *, size_t index);
 
 typedef struct MessageLinks {
   address_t const *msg_link;
@@ -90,8 +101,12 @@ extern next_msg_ids_t const *auth_gen_next_msg_ids(author_t *author);
 // Generic Processing
 extern unwrapped_message_t const *auth_receive_msg(author_t *author, address_t const *address);
 // Fetching/Syncing
-extern unwrapped_messages_t const *auth_fetch_next_msgs(author_t *author);
-extern unwrapped_messages_t const *auth_sync_state(author_t *author);
+extern fetch_report_t const *auth_fetch_next_msgs(author_t *author);
+extern fetch_report_t const *auth_sync_state(author_t *author);
+// Pre-shared keys
+extern void auth_store_psk(author_t *author, uint8_t const *pskid, uint8_t const *psk);
+extern uint8_t auth_remove_psk(author_t *author, uint8_t const *pskid);
+extern psk_ids_t const *auth_psk_ids(author_t const *author);
 
 /////////////
 // Subscriber
@@ -128,8 +143,12 @@ extern next_msg_ids_t const *sub_gen_next_msg_ids(subscriber_t *subscriber);
 // Generic Message Processing
 extern unwrapped_message_t const *sub_receive_msg(subscriber_t *subscriber, address_t const *address);
 // Fetching/Syncing
-extern unwrapped_messages_t const *sub_fetch_next_msgs(subscriber_t *subscriber);
-extern unwrapped_messages_t const *sub_sync_state(subscriber_t *subscriber);
+extern fetch_report_t const *sub_fetch_next_msgs(subscriber_t *subscriber);
+extern fetch_report_t const *sub_sync_state(subscriber_t *subscriber);
+// Pre-shared keys
+extern void sub_store_psk(subscriber_t *subscriber, uint8_t const *pskid, uint8_t const *psk);
+extern uint8_t sub_remove_psk(subscriber_t *subscriber, uint8_t const *pskid);
+extern psk_ids_t const *sub_psk_ids(subscriber_t const *subscriber);
 
 /////////////
 /// Utility
@@ -200,11 +200,18 @@ int main()
   printf("Sent\n");
 
   printf("Subscriber fetching messages...\n");
-  unwrapped_messages_t const *message_returns = sub_sync_state(subA);
+  fetch_report_t const *sync_report = sub_sync_state(subA);
+  unwrapped_messages_t const *message_returns = fetch_report_msgs(sync_report);
   printf("Found messages\n");
 
   size_t x;
-  for(x = 0; x < 3; x++)
+  for(x = 0; x < fetch_report_errors_len(sync_report); x++)
+  {
+    char const *error = get_indexed_error_str(sync_report, x);
+    printf("Failed to handle message: %s\n", error);
+    drop_str(error);
+  }
+  for(x = 0; x < unwrapped_messages_len(message_returns); x++)
   {
     packet_payloads_t response = get_indexed_payload(message_returns, x);
     printf("Unpacking message...\npublic: '%s' \tmasked: '%s'\n", response.public_payload.ptr, response.masked_payload.ptr);
@@ -225,7 +232,7 @@ int main()
   drop_links(tagged_packet_1_links);
   drop_links(tagged_packet_2_links);
   drop_links(tagged_packet_3_links);
-  drop_unwrapped_messages(message_returns);
+  drop_fetch_report(sync_report);
 
   auth_drop(auth);
   sub_drop(subA);
@@ -38,6 +38,47 @@ pub extern "C" fn drop_address(addr: *const Address) {
 pub type PskIds = psk::PskIds;
 pub type KePks = Vec<PublicKey>;
 
+#[no_mangle]
+pub extern "C" fn drop_psk_ids(ids: *const PskIds) {
+    unsafe { Box::from_raw(ids as *mut PskIds); }
+}
+
+#[no_mangle]
+pub extern "C" fn psk_ids_len(ids: *const PskIds) -> size_t {
+    unsafe { ids.as_ref().map_or(0, |ids| ids.len()) }
+}
+
+/// Pointer to `PSKID_SIZE` bytes of the pre-shared key identifier at `index`.
+#[no_mangle]
+pub extern "C" fn get_indexed_psk_id(ids: *const PskIds, index: size_t) -> *const uint8_t {
+    unsafe {
+        ids.as_ref().map_or(null(), |ids| {
+            ids.get(index).map_or(null(), |id| id.as_ptr())
+        })
+    }
+}
+
+/// Read pre-shared key identifier and pre-shared key from raw pointers to `PSKID_SIZE` and `PSK_SIZE` bytes.
+fn read_psk(pskid: *const uint8_t, psk: *const uint8_t) -> Option<(psk::PskId, psk::Psk)> {
+    if pskid.is_null() || psk.is_null() {
+        return None;
+    }
+    unsafe {
+        Some((
+            psk::PskId::clone_from_slice(core::slice::from_raw_parts(pskid, psk::PSKID_SIZE)),
+            psk::Psk::clone_from_slice(core::slice::from_raw_parts(psk, psk::PSK_SIZE)),
+        ))
+    }
+}
+
+/// Read pre-shared key identifier from raw pointer to `PSKID_SIZE` bytes.
+fn read_pskid(pskid: *const uint8_t) -> Option<psk::PskId> {
+    if pskid.is_null() {
+        return None;
+    }
+    unsafe { Some(psk::PskId::clone_from_slice(core::slice::from_raw_parts(pskid, psk::PSKID_SIZE))) }
+}
+
 pub type NextMsgIds = Vec<(PublicKey, Cursor<Address>)>;
 
 #[no_mangle]
@@ -56,6 +97,49 @@ pub extern "C" fn drop_unwrapped_messages(ms: *const UnwrappedMessages) {
     unsafe { Box::from_raw(ms as *mut UnwrappedMessages); }
 }
 
+#[no_mangle]
+pub extern "C" fn unwrapped_messages_len(ms: *const UnwrappedMessages) -> size_t {
+    unsafe { ms.as_ref().map_or(0, |ms| ms.len()) }
+}
+
+#[no_mangle]
+pub extern "C" fn drop_fetch_report(report: *const FetchReport) {
+    unsafe { Box::from_raw(report as *mut FetchReport); }
+}
+
+/// Messages handled successfully, the pointer is valid as long as the report is not dropped.
+#[no_mangle]
+pub extern "C" fn fetch_report_msgs(report: *const FetchReport) -> *const UnwrappedMessages {
+    unsafe { report.as_ref().map_or(null(), |report| &report.msgs as *const UnwrappedMessages) }
+}
+
+#[no_mangle]
+pub extern "C" fn fetch_report_errors_len(report: *const FetchReport) -> size_t {
+    unsafe { report.as_ref().map_or(0, |report| report.errors.len()) }
+}
+
+/// Link of the message failed at `index`, the pointer is valid as long as the report is not dropped.
+#[no_mangle]
+pub extern "C" fn get_indexed_error_link(report: *const FetchReport, index: size_t) -> *const Address {
+    unsafe {
+        report.as_ref().map_or(null(), |report| {
+            report.errors.get(index).map_or(null(), |(link, _e)| link as *const Address)
+        })
+    }
+}
+
+/// Description of the failure at `index`, the string must be released with `drop_str`.
+#[no_mangle]
+pub extern "C" fn get_indexed_error_str(report: *const FetchReport, index: size_t) -> *const c_char {
+    unsafe {
+        report.as_ref().map_or(null(), |report| {
+            report.errors.get(index).map_or(null(), |(_link, e)| {
+                CString::new(e.to_string()).map_or(null(), |e| e.into_raw())
+            })
+        })
+    }
+}
+
 #[cfg(feature = "sync-client")]
 pub type TransportWrap = iota_streams::app::transport::tangle::client::Client;
 
@@ -317,9 +401,9 @@ pub extern "C" fn get_indexed_payload(msgs: *const UnwrappedMessages, index: siz
 
 fn handle_message_contents(m: &UnwrappedMessage) -> PacketPayloads {
     match &m.body {
-        MessageContent::TaggedPacket { public_payload: p, masked_payload: m, } => (p, m).into(),
+        MessageContent::TaggedPacket { public_payload: p, masked_payload: m, .. } => (p, m).into(),
 
-        MessageContent::SignedPacket { pk: _, public_payload: p, masked_payload: m } => (p, m).into(),
+        MessageContent::SignedPacket { pk: _, public_payload: p, masked_payload: m, .. } => (p, m).into(),
 
         _ => PacketPayloads::default(),
     }
@@ -261,29 +261,52 @@ pub extern "C" fn sub_receive_msg(user: *mut Subscriber, link: *const Address) -
     }
 }
 
+/// Fetch next messages, the report holds the handled messages and the failures.
 #[no_mangle]
-pub extern "C" fn sub_fetch_next_msgs(user: *mut Subscriber) -> *const UnwrappedMessages {
+pub extern "C" fn sub_fetch_next_msgs(user: *mut Subscriber) -> *const FetchReport {
     unsafe {
         user.as_mut().map_or(null(), |user| {
-            let m = user.fetch_next_msgs();
-            Box::into_raw(Box::new(m))
+            let report = user.fetch_next_msgs();
+            Box::into_raw(Box::new(report))
         })
     }
 }
 
+/// Fetch messages up to the latest one, the report holds the handled messages and the failures.
 #[no_mangle]
-pub extern "C" fn sub_sync_state(user: *mut Subscriber) -> *const UnwrappedMessages {
+pub extern "C" fn sub_sync_state(user: *mut Subscriber) -> *const FetchReport {
     unsafe {
         user.as_mut().map_or(null(), |user| {
-            let mut ms = Vec::new();
-            loop {
-                let m = user.fetch_next_msgs();
-                if m.is_empty() {
-                    break;
-                }
-                ms.extend(m);
-            }
-            Box::into_raw(Box::new(ms))
+            let report = user.sync_state();
+            Box::into_raw(Box::new(report))
+        })
+    }
+}
+
+/// Store a pre-shared key, `pskid` and `psk` point to `PSKID_SIZE` and `PSK_SIZE` bytes respectively.
+#[no_mangle]
+pub extern "C" fn sub_store_psk(user: *mut Subscriber, pskid: *const uint8_t, psk: *const uint8_t) {
+    unsafe {
+        user.as_mut().map_or((), |user| {
+            read_psk(pskid, psk).map_or((), |(pskid, psk)| user.store_psk(pskid, psk))
         })
     }
 }
+
+/// Remove a pre-shared key, returns 1 if the key was stored and 0 otherwise.
+#[no_mangle]
+pub extern "C" fn sub_remove_psk(user: *mut Subscriber, pskid: *const uint8_t) -> uint8_t {
+    unsafe {
+        user.as_mut().map_or(0, |user| {
+            read_pskid(pskid).map_or(0, |pskid| if user.remove_psk(&pskid).is_ok() { 1 } else { 0 })
+        })
+    }
+}
+
+/// Identifiers of the stored pre-shared keys.
+#[no_mangle]
+pub extern "C" fn sub_psk_ids(user: *const Subscriber) -> *const PskIds {
+    unsafe {
+        user.as_ref().map_or(null(), |user| Box::into_raw(Box::new(user.psk_ids())))
+    }
+}
@@ -25,7 +25,7 @@ use iota_streams::{
     },
     core::{
         prelude::{Rc, String, ToString},
-        psk::{PskIds, PSKID_SIZE}
+        psk::PskIds,
     },
     ddml::types::*,
 };
@@ -62,6 +62,29 @@ impl Author {
         )
     }
 
+    #[wasm_bindgen(catch)]
+    pub fn store_psk(&self, pskid: String, psk: Vec<u8>) -> Result<()> {
+        let pskid = pskid_from_str(&pskid)?;
+        let psk = psk_from_bytes(&psk)?;
+        self.author.borrow_mut().store_psk(pskid, psk);
+        Ok(())
+    }
+
+    #[wasm_bindgen(catch)]
+    pub fn remove_psk(&self, pskid: String) -> Result<()> {
+        let pskid = pskid_from_str(&pskid)?;
+        to_result(self.author.borrow_mut().remove_psk(&pskid))
+    }
+
+    #[wasm_bindgen(catch)]
+    pub fn psk_ids(&self) -> Result<PskIdsW> {
+        let mut ids = PskIdsW::new();
+        for pskid in self.author.borrow().psk_ids() {
+            ids.add(pskid_to_str(&pskid));
+        }
+        Ok(ids)
+    }
+
     #[wasm_bindgen(catch)]
     pub fn is_multi_branching(&self) -> Result<bool> {
         Ok(self.author.borrow_mut().is_multi_branching())
@@ -123,11 +146,7 @@ impl Author {
 
         for id in ids {
             if let Some(id_str) = id.unwrap().as_string() {
-                if id_str.as_bytes().len() != PSKID_SIZE {
-                    return Err(JsValue::from_str("PskId is wrong size"))
-                }
-                let gen_arr = GenericArray::clone_from_slice(id_str.as_bytes());
-                preshared.push(gen_arr);
+                preshared.push(pskid_from_str(&id_str)?);
             }
         }
 
@@ -309,21 +328,15 @@ impl Author {
     }
 
     #[wasm_bindgen(catch)]
-    pub async fn sync_state(self) -> Result<()> {
-        loop {
-            let msgs = self.author.borrow_mut().fetch_next_msgs().await;
-            if msgs.is_empty() {
-                break;
-            }
-        }
-        Ok(())
+    pub async fn sync_state(self) -> Result<FetchReport> {
+        let report = self.author.borrow_mut().sync_state().await;
+        Ok(report.into())
     }
 
     #[wasm_bindgen(catch)]
-    pub async fn fetch_next_msgs(self) -> Result<Array> {
-        let msgs = self.author.borrow_mut().fetch_next_msgs().await;
-        let payloads = get_message_contents(msgs);
-        Ok(payloads.into_iter().map(JsValue::from).collect())
+    pub async fn fetch_next_msgs(self) -> Result<FetchReport> {
+        let report = self.author.borrow_mut().fetch_next_msgs().await;
+        Ok(report.into())
     }
 
     #[wasm_bindgen(catch)]
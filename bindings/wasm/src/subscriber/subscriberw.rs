@@ -1,8 +1,8 @@
 use core::convert::TryInto as _;
 use wasm_bindgen::prelude::*;
-use js_sys::Array;
 
 use crate::types::*;
+use crate::types::PskIds as PskIdsW;
 
 use iota_streams::{
   app::transport::{
@@ -51,6 +51,29 @@ impl Subscriber {
         )
     }
 
+    #[wasm_bindgen(catch)]
+    pub fn store_psk(&self, pskid: String, psk: Vec<u8>) -> Result<()> {
+        let pskid = pskid_from_str(&pskid)?;
+        let psk = psk_from_bytes(&psk)?;
+        self.subscriber.borrow_mut().store_psk(pskid, psk);
+        Ok(())
+    }
+
+    #[wasm_bindgen(catch)]
+    pub fn remove_psk(&self, pskid: String) -> Result<()> {
+        let pskid = pskid_from_str(&pskid)?;
+        to_result(self.subscriber.borrow_mut().remove_psk(&pskid))
+    }
+
+    #[wasm_bindgen(catch)]
+    pub fn psk_ids(&self) -> Result<PskIdsW> {
+        let mut ids = PskIdsW::new();
+        for pskid in self.subscriber.borrow().psk_ids() {
+            ids.add(pskid_to_str(&pskid));
+        }
+        Ok(ids)
+    }
+
     #[wasm_bindgen(catch)]
     pub fn is_multi_branching(&self) -> Result<bool> {
         Ok(self.subscriber.borrow_mut().is_multi_branching())
@@ -244,21 +267,15 @@ impl Subscriber {
     }
 
     #[wasm_bindgen(catch)]
-    pub async fn sync_state(self) -> Result<()> {
-        loop {
-            let msgs = self.subscriber.borrow_mut().fetch_next_msgs().await;
-            if msgs.is_empty() {
-                break;
-            }
-        }
-        Ok(())
+    pub async fn sync_state(self) -> Result<FetchReport> {
+        let report = self.subscriber.borrow_mut().sync_state().await;
+        Ok(report.into())
     }
 
     #[wasm_bindgen(catch)]
-    pub async fn fetch_next_msgs(self) -> Result<Array> {
-        let msgs = self.subscriber.borrow_mut().fetch_next_msgs().await;
-        let payloads = get_message_contents(msgs);
-        Ok(payloads.into_iter().map(JsValue::from).collect())
+    pub async fn fetch_next_msgs(self) -> Result<FetchReport> {
+        let report = self.subscriber.borrow_mut().fetch_next_msgs().await;
+        Ok(report.into())
     }
 
 
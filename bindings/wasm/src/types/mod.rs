@@ -7,11 +7,15 @@ use iota_streams::{
     app_channels::{
         api::tangle::{
             Address as ApiAddress,
+            FetchReport as ApiFetchReport,
             UnwrappedMessage,
             MessageContent,
         },
     },
-    core::prelude::{ String, ToString, Rc, },
+    core::{
+        prelude::{ String, ToString, Rc, },
+        psk,
+    },
     ddml::types::hex,
 };
 
@@ -22,6 +26,26 @@ pub fn to_result<T, E: ToString>(r: core::result::Result<T, E>) -> Result<T> {
     r.map_err(|e| JsValue::from_str(&e.to_string()))
 }
 
+/// PskIds are passed to and from JS as hex strings.
+pub fn pskid_from_str(id: &str) -> Result<psk::PskId> {
+    let id = hex::decode(id).map_err(|_| JsValue::from_str("PskId is not a hex string"))?;
+    if id.len() != psk::PSKID_SIZE {
+        return Err(JsValue::from_str("PskId is wrong size"))
+    }
+    Ok(psk::PskId::clone_from_slice(&id))
+}
+
+pub fn pskid_to_str(id: &psk::PskId) -> String {
+    hex::encode(id)
+}
+
+pub fn psk_from_bytes(key: &[u8]) -> Result<psk::Psk> {
+    if key.len() != psk::PSK_SIZE {
+        return Err(JsValue::from_str("Psk is wrong size"))
+    }
+    Ok(psk::Psk::clone_from_slice(key))
+}
+
 #[wasm_bindgen]
 pub struct SendOptions {
     pub depth: u8,
@@ -60,6 +84,7 @@ impl SendOptions {
 }
 
 #[wasm_bindgen]
+#[derive(Clone)]
 pub struct Address {
     addr_id: String,
     msg_id: String,
@@ -134,7 +159,7 @@ pub fn get_message_contents(msgs: Vec<UnwrappedMessage>) -> Vec<UserResponse> {
     let mut payloads = Vec::new();
     for msg in msgs {
         match msg.body {
-            MessageContent::SignedPacket { pk, public_payload: p, masked_payload: m } => {
+            MessageContent::SignedPacket { pk, public_payload: p, masked_payload: m, .. } => {
                 payloads.push(UserResponse::new(
                     Address::from_string(msg.link.to_string()),
                     None,
@@ -146,7 +171,7 @@ pub fn get_message_contents(msgs: Vec<UnwrappedMessage>) -> Vec<UserResponse> {
                     )
                 ))
             },
-            MessageContent::TaggedPacket { public_payload: p, masked_payload: m } => {
+            MessageContent::TaggedPacket { public_payload: p, masked_payload: m, .. } => {
                 payloads.push(UserResponse::new(
                     Address::from_string(msg.link.to_string()),
                     None,
@@ -164,6 +189,7 @@ pub fn get_message_contents(msgs: Vec<UnwrappedMessage>) -> Vec<UserResponse> {
 
 
 #[wasm_bindgen]
+#[derive(Clone)]
 pub struct UserResponse {
     link: Address,
     seq_link: Option<Address>,
@@ -178,12 +204,27 @@ pub struct NextMsgId {
 
 
 #[wasm_bindgen]
+#[derive(Clone)]
 pub struct Message {
     pk: Option<String>,
     public_payload: Vec<u8>,
     masked_payload: Vec<u8>,
 }
 
+/// Message which has been published but could not be fetched or handled.
+#[wasm_bindgen]
+pub struct FetchError {
+    link: Address,
+    error: String,
+}
+
+/// Outcome of fetching next messages: handled messages and failures.
+#[wasm_bindgen]
+pub struct FetchReport {
+    msgs: Vec<UserResponse>,
+    errors: Vec<FetchError>,
+}
+
 #[wasm_bindgen]
 pub struct PskIds {
     ids: Vec<String>
@@ -250,6 +291,48 @@ impl Message {
 
 }
 
+#[wasm_bindgen]
+impl FetchError {
+    pub fn get_link(&self) -> Address {
+        self.link.copy()
+    }
+
+    pub fn get_error(&self) -> String {
+        self.error.clone()
+    }
+}
+
+impl From<ApiFetchReport> for FetchReport {
+    fn from(report: ApiFetchReport) -> Self {
+        let errors = report
+            .errors
+            .into_iter()
+            .map(|(link, e)| FetchError {
+                link: Address::from_string(link.to_string()),
+                error: e.to_string(),
+            })
+            .collect();
+        FetchReport {
+            msgs: get_message_contents(report.msgs),
+            errors,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl FetchReport {
+    pub fn get_msgs(&self) -> Array {
+        self.msgs.iter().cloned().map(JsValue::from).collect()
+    }
+
+    pub fn get_errors(&self) -> Array {
+        self.errors
+            .iter()
+            .map(|e| JsValue::from(FetchError { link: e.link.copy(), error: e.error.clone() }))
+            .collect()
+    }
+}
+
 #[wasm_bindgen]
 impl NextMsgId {
     pub fn new(pk: String, msgid: Address) -> Self {
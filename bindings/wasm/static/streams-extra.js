@@ -136,7 +136,12 @@ async function fetch_messages() {
   console.log("Fetching...");
   busy = true;
 
-  let next_msgs = await auth.clone().fetch_next_msgs()
+  let report = await auth.clone().fetch_next_msgs()
+  let errors = report.get_errors();
+  for(var i = 0; i < errors.length; i++) {
+      console.log("Failed to fetch message at ", errors[i].get_link().to_string(), ": ", errors[i].get_error());
+  }
+  let next_msgs = report.get_msgs();
   if(next_msgs.length === 0) {
       exists = false;
       busy = false;
@@ -127,6 +127,23 @@ pub fn example<T: Transport>(
         print!("  SubscriberA: {}", subscriberA);
     }
 
+    println!("\nGrant writer role [SubscriberA]");
+    let permission_link = {
+        let (msg, seq) = author.send_permission(&keyload_link, subscriberA.get_pk(), true, None)?;
+        let seq = seq.unwrap();
+        println!("  msg => <{}> {:?}", msg.msgid, msg);
+        println!("  seq => <{}> {:?}", seq.msgid, seq);
+        print!("  Author     : {}", author);
+        seq
+    };
+
+    println!("\nHandle Permission [SubscriberA]");
+    {
+        let msg_tag = subscriberA.receive_sequence(&permission_link)?;
+        subscriberA.receive_permission(&msg_tag)?;
+        print!("  SubscriberA: {}", subscriberA);
+    }
+
     println!("\nSubscriber A fetching transactions...");
     utils::s_fetch_next_messages(&mut subscriberA);
 
@@ -230,6 +247,25 @@ pub fn example<T: Transport>(
         print!("  SubscriberB: {}", subscriberB);
     }
 
+    println!("\nGrant writer role [SubscriberB]");
+    let permission_link = {
+        let (msg, seq) = author.send_permission(&keyload_link, subscriberB.get_pk(), true, None)?;
+        let seq = seq.unwrap();
+        println!("  msg => <{}> {:?}", msg.msgid, msg);
+        println!("  seq => <{}> {:?}", seq.msgid, seq);
+        print!("  Author     : {}", author);
+        seq
+    };
+
+    println!("\nHandle Permission [SubscriberB]");
+    {
+        let msg_tag = subscriberA.receive_sequence(&permission_link)?;
+        subscriberA.receive_permission(&msg_tag)?;
+        print!("  SubscriberA: {}", subscriberA);
+        subscriberB.receive_permission(&msg_tag)?;
+        print!("  SubscriberB: {}", subscriberB);
+    }
+
     println!("\nSubscriber A fetching transactions...");
     utils::s_fetch_next_messages(&mut subscriberA);
 
@@ -256,11 +292,48 @@ pub fn example<T: Transport>(
         try_or!(masked_payload == unwrapped_masked,
                              MaskedPayloadMismatch(masked_payload.to_string(), unwrapped_masked.to_string())
         )?;
+        let (unwrapped_public, unwrapped_masked) = subscriberB.receive_tagged_packet(&msg_tag)?;
+        print!("  SubscriberB: {}", subscriberB);
+        try_or!(public_payload == unwrapped_public,
+                             PublicPayloadMismatch(public_payload.to_string(), unwrapped_public.to_string())
+        )?;
+        try_or!(masked_payload == unwrapped_masked,
+                             MaskedPayloadMismatch(masked_payload.to_string(), unwrapped_masked.to_string())
+        )?;
         let resultC = subscriberC.receive_tagged_packet(&msg_tag);
         print!("  SubscriberC: {}", subscriberC);
         try_or!(resultC.is_err(), SubscriberAccessMismatch(String::from("C")))?;
     }
 
+    println!("\nSigned packet - SubscriberA");
+    let signed_packet_link = {
+        let (msg, seq) = subscriberA.send_signed_packet(&keyload_link, &public_payload, &masked_payload)?;
+        let seq = seq.unwrap();
+        println!("  msg => <{}> {:?}", msg.msgid, msg);
+        println!("  seq => <{}> {:?}", seq.msgid, seq);
+        print!("  SubscriberA: {}", subscriberA);
+        seq
+    };
+
+    println!("\nHandle Signed packet - SubscriberA");
+    {
+        // SubscriberB learns writer role of SubscriberA from the keyload, the Permission message
+        // granting it was published before SubscriberB subscribed.
+        try_or!(subscriberB.is_writer(subscriberA.get_pk(), &keyload_link),
+                             SubscriberAccessMismatch(String::from("A"))
+        )?;
+        let msg_tag = subscriberB.receive_sequence(&signed_packet_link)?;
+        let (signer_pk, unwrapped_public, unwrapped_masked) = subscriberB.receive_signed_packet(&msg_tag)?;
+        print!("  SubscriberB: {}", subscriberB);
+        try_or!(&signer_pk == subscriberA.get_pk(), SubscriberAccessMismatch(String::from("A")))?;
+        try_or!(public_payload == unwrapped_public,
+                             PublicPayloadMismatch(public_payload.to_string(), unwrapped_public.to_string())
+        )?;
+        try_or!(masked_payload == unwrapped_masked,
+                             MaskedPayloadMismatch(masked_payload.to_string(), unwrapped_masked.to_string())
+        )?;
+    }
+
     println!("\nSubscriber B fetching transactions...");
     utils::s_fetch_next_messages(&mut subscriberB);
 
@@ -136,6 +136,29 @@ pub fn example<T: Transport>(
         print!("  SubscriberB: {}", subscriberB);
     }
 
+    println!("\nGrant writer role [SubscriberA, SubscriberB]");
+    let permission_links = {
+        let mut links = Vec::new();
+        for pk in &[subscriberA.get_pk().clone(), subscriberB.get_pk().clone()] {
+            let (msg, seq) = author.send_permission(&previous_msg_link, pk, true, None)?;
+            println!("  msg => <{}> {:?}", msg.msgid, msg);
+            panic_if_not(seq.is_none());
+            links.push(msg);
+        }
+        print!("  Author     : {}", author);
+        links
+    };
+
+    println!("\nHandle Permission");
+    {
+        for link in &permission_links {
+            subscriberA.receive_permission(link)?;
+            subscriberB.receive_permission(link)?;
+        }
+        print!("  SubscriberA: {}", subscriberA);
+        print!("  SubscriberB: {}", subscriberB);
+    }
+
     println!("\nSigned packet");
     let previous_msg_link = {
         print!("  Author     : {}", author);
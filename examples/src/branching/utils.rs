@@ -16,10 +16,14 @@ pub fn s_fetch_next_messages<T: Transport>(subscriber: &mut Subscriber<T>)
     let mut exists = true;
 
     while exists {
-        let msgs = subscriber.fetch_next_msgs();
+        let report = subscriber.fetch_next_msgs();
         exists = false;
 
-        for msg in msgs {
+        for (link, e) in report.errors.iter() {
+            println!("Failed to handle message at {}: {}", link.rel(), e);
+        }
+
+        for msg in report.msgs {
             println!("Message exists at {}... ", &msg.link.rel());
             exists = true;
         }
@@ -35,10 +39,14 @@ pub fn a_fetch_next_messages<T: Transport>(author: &mut Author<T>)
     let mut exists = true;
 
     while exists {
-        let msgs = author.fetch_next_msgs();
+        let report = author.fetch_next_msgs();
         exists = false;
 
-        for msg in msgs {
+        for (link, e) in report.errors.iter() {
+            println!("Failed to handle message at {}: {}", link.rel(), e);
+        }
+
+        for msg in report.msgs {
             println!("Message exists at {}... ", &msg.link.rel());
             exists = true;
         }
@@ -5,11 +5,105 @@ use iota_streams_core::prelude::{
     HashMap,
     Vec,
 };
+use iota_streams_app::message::Cursor;
 use iota_streams_core_edsig::{
     key_exchange::x25519,
     signature::ed25519,
 };
 
+/// Publishing rights (role) of a channel participant. Channel owner is always allowed to publish.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Permission<Link> {
+    /// Participant can only read packets.
+    Read,
+    /// Participant can publish packets linked to any message.
+    Write,
+    /// Participant can publish packets linked to the listed messages (branches) only.
+    WriteBranches(Vec<Link>),
+}
+
+impl<Link> Default for Permission<Link> {
+    fn default() -> Self {
+        Permission::Read
+    }
+}
+
+impl<Link: Eq> Permission<Link> {
+    /// Check whether a packet linked to `link_to` can be published.
+    pub fn can_write(&self, link_to: &Link) -> bool {
+        match self {
+            Permission::Read => false,
+            Permission::Write => true,
+            Permission::WriteBranches(branches) => branches.contains(link_to),
+        }
+    }
+
+    /// Grant write permission for all branches or for the given branch only.
+    pub fn grant(&mut self, branch: Option<Link>) {
+        match branch {
+            None => *self = Permission::Write,
+            Some(branch) => match self {
+                Permission::Read => *self = Permission::WriteBranches(vec![branch]),
+                Permission::Write => {},
+                Permission::WriteBranches(branches) => {
+                    if !branches.contains(&branch) {
+                        branches.push(branch);
+                    }
+                },
+            },
+        }
+    }
+
+    /// Revoke write permission for all branches or for the given branch only.
+    /// Revoking a single branch has no effect on permission granted for all branches.
+    pub fn revoke(&mut self, branch: Option<&Link>) {
+        match branch {
+            None => *self = Permission::Read,
+            Some(branch) => {
+                if let Permission::WriteBranches(branches) = self {
+                    branches.retain(|b| b != branch);
+                    if branches.is_empty() {
+                        *self = Permission::Read;
+                    }
+                }
+            },
+        }
+    }
+}
+
+impl<Link> fmt::Display for Permission<Link> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Permission::Read => write!(f, "r"),
+            Permission::Write => write!(f, "rw"),
+            Permission::WriteBranches(branches) => write!(f, "rw[{}]", branches.len()),
+        }
+    }
+}
+
+/// Info stored together with public key: sequencing state and publishing rights.
+#[derive(Clone, Default)]
+pub struct PkInfo<Link> {
+    pub cursor: Cursor<Link>,
+    pub permission: Permission<Link>,
+}
+
+impl<Link> PkInfo<Link> {
+    /// Info for a participant with read-only permission.
+    pub fn new(cursor: Cursor<Link>) -> Self {
+        Self {
+            cursor,
+            permission: Permission::Read,
+        }
+    }
+}
+
+impl<Link: fmt::Display> fmt::Display for PkInfo<Link> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.cursor, self.permission)
+    }
+}
+
 pub trait PublicKeyStore<Info>: Default {
     fn filter<'a>(&'a self, pks: &'a Vec<ed25519::PublicKey>) -> Vec<(&'a ed25519::PublicKey, &'a x25519::PublicKey)>;
 
@@ -18,6 +112,8 @@ pub trait PublicKeyStore<Info>: Default {
     fn get_mut(&mut self, pk: &ed25519::PublicKey) -> Option<&mut Info>;
     fn get_ke_pk(&self, pk: &ed25519::PublicKey) -> Option<&x25519::PublicKey>;
     fn insert(&mut self, pk: ed25519::PublicKey, info: Info) -> Result<()>;
+    /// Remove the public key together with its info, return the info if the key was present
+    fn remove(&mut self, pk: &ed25519::PublicKey) -> Option<Info>;
     fn keys(&self) -> Vec<(&ed25519::PublicKey, &x25519::PublicKey)>;
     fn iter(&self) -> Vec<(&ed25519::PublicKey, &Info)>;
     fn iter_mut(&mut self) -> Vec<(&ed25519::PublicKey, &mut Info)>;
@@ -62,6 +158,9 @@ impl<Info> PublicKeyStore<Info> for PublicKeyMap<Info> {
         self.pks.insert(pk.into(), (xpk, info));
         Ok(())
     }
+    fn remove(&mut self, pk: &ed25519::PublicKey) -> Option<Info> {
+        self.pks.remove(pk.into()).map(|(_x, i)| i)
+    }
     fn keys(&self) -> Vec<(&ed25519::PublicKey, &x25519::PublicKey)> {
         self.pks.iter().map(|(k, (x, _i))| (&k.0, x)).collect()
     }
@@ -10,6 +10,8 @@ pub trait PresharedKeyStore: Default {
     fn insert(&mut self, pskid: psk::PskId, psk: psk::Psk);
     fn filter<'a>(&'a self, psk_ids: &'_ psk::PskIds) -> Vec<psk::IPsk<'a>>;
     fn get<'a>(&'a self, pskid: &'_ psk::PskId) -> Option<&'a psk::Psk>;
+    /// Remove the pre-shared key, return it if it was present
+    fn remove(&mut self, pskid: &'_ psk::PskId) -> Option<psk::Psk>;
     fn iter(&self) -> Vec<(&psk::PskId, &psk::Psk)>;
 }
 
@@ -31,6 +33,9 @@ impl PresharedKeyStore for PresharedKeyMap {
     fn get<'a>(&'a self, pskid: &'_ psk::PskId) -> Option<&'a psk::Psk> {
         self.psks.get(pskid)
     }
+    fn remove(&mut self, pskid: &'_ psk::PskId) -> Option<psk::Psk> {
+        self.psks.remove(pskid)
+    }
     fn iter(&self) -> Vec<(&psk::PskId, &psk::Psk)> {
         self.psks.iter().collect()
     }
@@ -5,12 +5,29 @@ use core::fmt;
 
 use super::*;
 use crate::api::tangle::{
+    FetchReport,
     UnwrappedMessage,
     User,
 };
 
-use iota_streams_core::prelude::Vec;
+#[cfg(not(feature = "async"))]
+use crate::api::tangle::MessagesIter;
+#[cfg(feature = "async")]
+use core::time::Duration;
+#[cfg(feature = "async")]
+use futures::Stream;
+
+use iota_streams_core::{
+    prelude::{
+        hex,
+        Vec,
+    },
+    err,
+    Errors::UserNotRegistered,
+    LOCATION_LOG,
+};
 use iota_streams_core_edsig::signature::ed25519;
+use iota_streams_ddml::link_store::LinkStore as _;
 
 /// Author Object. Contains User API.
 pub struct Author<Trans> {
@@ -35,11 +52,79 @@ impl<Trans> Author<Trans>
         Self { user }
     }
 
+    /// Create a new Author instance with the same identity for another channel. The new channel
+    /// has its own link generator, sequencing state and subscribers, transport object is shared.
+    ///
+    /// # Arguments
+    /// * `channel_idx` - Index of the channel application instance
+    ///
+    pub fn new_channel(&self, channel_idx: u64) -> Result<Self>
+    where
+        Trans: Clone,
+    {
+        let user = self.user.new_channel(channel_idx, self.user.transport.clone())?;
+        Ok(Self { user })
+    }
+
     /// Return boolean representing the sequencing nature of the channel
     pub fn is_multi_branching(&self) -> bool {
         self.user.is_multi_branching()
     }
 
+    /// Return boolean representing whether packets in the channel are ratcheted
+    pub fn is_ratcheted(&self) -> bool {
+        self.user.is_ratcheted()
+    }
+
+    /// Enable forward-secret ratchet for packets in the channel. Spongos state of the message
+    /// packets are linked to is advanced in a one-way fashion as many times as the packet sequence
+    /// number, the state of the message itself is left intact. Must be set
+    /// before the channel is announced.
+    ///
+    ///   # Arguments
+    ///   * `ratchet` - Boolean representing whether packets should be ratcheted
+    ///
+    pub fn set_ratchet(&mut self, ratchet: bool) -> Result<()> {
+        self.user.set_ratchet(ratchet)
+    }
+
+    /// Return boolean representing whether masked payloads in the channel are padded
+    pub fn is_padded(&self) -> bool {
+        self.user.is_padded()
+    }
+
+    /// Enable padding of masked payloads in the channel. Masked payload of each packet is padded
+    /// so that the packet fills a whole number of `payload_length` sized frames, the padding is
+    /// removed on unwrap. Participants should use the same `payload_length`. Must be set before
+    /// the channel is announced.
+    ///
+    ///   # Arguments
+    ///   * `padding` - Boolean representing whether masked payloads should be padded
+    ///
+    pub fn set_padding(&mut self, padding: bool) -> Result<()> {
+        self.user.set_padding(padding)
+    }
+
+    /// Include authenticated timestamp into signed and tagged packets. Timestamp is taken
+    /// from the local clock in milliseconds since Unix epoch.
+    ///
+    ///   # Arguments
+    ///   * `timestamping` - Boolean representing whether packets should be timestamped
+    ///
+    pub fn set_timestamping(&mut self, timestamping: bool) {
+        self.user.set_timestamping(timestamping)
+    }
+
+    /// Set the maximum allowed difference between the timestamp of a received packet and the
+    /// local clock. Packets outside of the window or without a timestamp are rejected.
+    ///
+    ///   # Arguments
+    ///   * `clock_skew` - Window size in milliseconds, `None` accepts packets regardless of timestamp
+    ///
+    pub fn set_clock_skew(&mut self, clock_skew: Option<u64>) {
+        self.user.set_clock_skew(clock_skew)
+    }
+
     /// Fetch the Address (application instance) of the channel.
     pub fn channel_address(&self) -> Option<&ChannelAddress> {
         self.user.channel_address()
@@ -82,6 +167,114 @@ impl<Trans> Author<Trans>
         Ok(self.user.store_state_for_all(link, seq_num)?)
     }
 
+    /// Fetch the Address of the latest keyload sent by the Author.
+    pub fn latest_keyload(&self) -> Option<Address> {
+        match (&self.user.user.appinst, &self.user.user.latest_keyload) {
+            (Some(appinst), Some(msgid)) => Some(Address::new(appinst.appinst.clone(), msgid.clone())),
+            _ => None,
+        }
+    }
+
+    /// Address packets linked to `link_to` are attached to: the latest revocation keyload if
+    /// `link_to` is a keyload sent before it, `link_to` otherwise.
+    fn keyload_link(&self, link_to: &Address) -> Address {
+        Address::new(link_to.appinst.clone(), self.user.user.keyload_link(&link_to.msgid).clone())
+    }
+
+    /// Record keyload `link` as the latest one.
+    fn keyload_sent(&mut self, link: &Address) {
+        self.user.user.latest_keyload = Some(link.msgid.clone());
+    }
+
+    /// Supersede keyloads sent before the revocation keyload `link` by it.
+    fn revocation_sent(&mut self, link: &Address) {
+        self.keyload_sent(link);
+        let keyloads = self
+            .user
+            .user
+            .link_store
+            .borrow()
+            .iter()
+            .into_iter()
+            .filter_map(|(msgid, (_spongos, info))| {
+                if matches!(info, MsgInfo::Keyload) {
+                    Some(msgid.clone())
+                } else {
+                    None
+                }
+            })
+            .collect();
+        self.user.user.supersede_keyloads(keyloads, &link.msgid);
+    }
+
+    /// Fetch the ed25519 public keys of the channel subscribers
+    pub fn subscribers(&self) -> Vec<&ed25519::PublicKey> {
+        self.user.subscribers()
+    }
+
+    /// Store a subscriber public key enrolled out-of-band, without a Subscribe message.
+    /// The subscriber will be included in subsequent keyloads for everyone.
+    ///
+    ///   # Arguments
+    ///   * `pk` - ed25519 Public Key of the subscriber
+    ///
+    pub fn store_new_subscriber(&mut self, pk: ed25519::PublicKey) -> Result<()> {
+        self.user.store_new_subscriber(pk)
+    }
+
+    /// Remove a subscriber public key from the channel.
+    /// The subscriber will be excluded from subsequent keyloads for everyone.
+    ///
+    ///   # Arguments
+    ///   * `pk` - ed25519 Public Key of the subscriber
+    ///
+    pub fn remove_subscriber(&mut self, pk: &ed25519::PublicKey) -> Result<()> {
+        self.user.remove_subscriber(pk)
+    }
+
+    /// Store a pre-shared key, it can be used in keyloads afterwards.
+    ///
+    ///   # Arguments
+    ///   * `pskid` - Identifier of the pre-shared key
+    ///   * `psk` - Pre-shared key
+    ///
+    pub fn store_psk(&mut self, pskid: PskId, psk: Psk) {
+        self.user.store_psk(pskid, psk)
+    }
+
+    /// Remove a pre-shared key.
+    ///
+    ///   # Arguments
+    ///   * `pskid` - Identifier of the pre-shared key
+    ///
+    pub fn remove_psk(&mut self, pskid: &PskId) -> Result<()> {
+        self.user.remove_psk(pskid)
+    }
+
+    /// Fetch the identifiers of the stored pre-shared keys
+    pub fn psk_ids(&self) -> PskIds {
+        self.user.psk_ids()
+    }
+
+    /// Fetch publishing rights of a participant, None if the participant is not known.
+    ///
+    ///   # Arguments
+    ///   * `pk` - ed25519 Public Key of the participant
+    ///
+    pub fn permission(&self, pk: &ed25519::PublicKey) -> Option<&Permission> {
+        self.user.permission(pk)
+    }
+
+    /// Check whether a participant has writer role for packets linked to the given message.
+    ///
+    ///   # Arguments
+    ///   * `pk` - ed25519 Public Key of the participant
+    ///   * `link_to` - Address of the message packets are linked to
+    ///
+    pub fn is_writer(&self, pk: &ed25519::PublicKey, link_to: &Address) -> bool {
+        self.user.is_writer(pk, link_to)
+    }
+
     /// Serialize user state and encrypt it with password.
     ///
     ///   # Arguments
@@ -111,6 +304,16 @@ impl<Trans: Transport> Author<Trans>
         self.user.send_announce()
     }
 
+    /// Replace the signature key pair with a new one derived from seed. A change key message
+    /// linked to the announcement and signed with the current key is sent.
+    ///
+    ///  # Arguments
+    ///  * `seed` - A string slice representing the seed of the new key pair
+    ///
+    pub fn send_change_key(&mut self, seed: &str) -> Result<Address> {
+        self.user.send_change_key(seed)
+    }
+
     /// Create and send a new keyload for a list of subscribers.
     ///
     ///  # Arguments
@@ -124,7 +327,28 @@ impl<Trans: Transport> Author<Trans>
         psk_ids: &PskIds,
         ke_pks: &Vec<ed25519::PublicKey>,
     ) -> Result<(Address, Option<Address>)> {
-        self.user.send_keyload(link_to, psk_ids, ke_pks)
+        let links = self.user.send_keyload(link_to, psk_ids, ke_pks)?;
+        self.keyload_sent(&links.0);
+        Ok(links)
+    }
+
+    /// Create and send a new keyload for a list of subscribers, recipients identifiers
+    /// are replaced with blinded tags so that observers can't tell who is authorized.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload will be attached to
+    ///  * `psk_ids` - Vector of Pre-shared key ids to be included in message
+    ///  * `ke_pks`  - Vector of Public Keys to be included in message
+    ///
+    pub fn send_anonymous_keyload(
+        &mut self,
+        link_to: &Address,
+        psk_ids: &PskIds,
+        ke_pks: &Vec<ed25519::PublicKey>,
+    ) -> Result<(Address, Option<Address>)> {
+        let links = self.user.send_anonymous_keyload(link_to, psk_ids, ke_pks)?;
+        self.keyload_sent(&links.0);
+        Ok(links)
     }
 
     /// Create and send keyload for all subscribed subscribers.
@@ -133,10 +357,61 @@ impl<Trans: Transport> Author<Trans>
     ///  * `link_to` - Address of the message the keyload will be attached to
     ///
     pub fn send_keyload_for_everyone(&mut self, link_to: &Address) -> Result<(Address, Option<Address>)> {
-        self.user.send_keyload_for_everyone(link_to)
+        let links = self.user.send_keyload_for_everyone(link_to)?;
+        self.keyload_sent(&links.0);
+        Ok(links)
+    }
+
+    /// Revoke read access of a subscriber: its public key (and optionally a pre-shared key) is
+    /// removed and a new keyload for the remaining subscribers is sent. Keyloads sent before are
+    /// superseded by the new one: packets later sent linked to any of them are attached to the new
+    /// keyload instead and can be read by the remaining subscribers only. The subscriber and
+    /// pre-shared key are kept if the keyload can't be sent.
+    ///
+    ///  # Arguments
+    ///  * `pk` - ed25519 Public Key of the subscriber to be revoked
+    ///  * `pskid` - Optional identifier of the pre-shared key to be revoked
+    ///
+    pub fn revoke(&mut self, pk: &ed25519::PublicKey, pskid: Option<&PskId>) -> Result<(Address, Option<Address>)> {
+        let announcement_link = match self.user.user.appinst {
+            Some(ref appinst) => appinst.clone(),
+            None => return err!(UserNotRegistered),
+        };
+        let revoked = self.user.user.remove_revoked(pk, pskid)?;
+        match self.user.send_keyload_for_everyone(&announcement_link) {
+            Ok(links) => {
+                self.revocation_sent(&links.0);
+                Ok(links)
+            }
+            Err(err) => {
+                self.user.user.restore_revoked(pk, pskid, revoked)?;
+                Err(err)
+            }
+        }
+    }
+
+    /// Assign writer (grant write permission) or reader (revoke it) role to a subscriber. Signed
+    /// packets of the subscriber are accepted by other users only while it is a writer for the
+    /// branch. Roles are also carried in subsequent keyloads.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the permission will be attached to
+    ///  * `pk` - ed25519 Public Key of the subscriber
+    ///  * `write` - Boolean representing whether write permission is granted or revoked
+    ///  * `branch` - Optional address of the message packets must be linked to, all branches if None
+    ///
+    pub fn send_permission(
+        &mut self,
+        link_to: &Address,
+        pk: &ed25519::PublicKey,
+        write: bool,
+        branch: Option<&Address>,
+    ) -> Result<(Address, Option<Address>)> {
+        self.user.send_permission(link_to, pk, write, branch)
     }
 
-    /// Create and send a signed packet.
+    /// Create and send a signed packet. Packets linked to a keyload superseded by a revocation are
+    /// attached to the revocation keyload.
     ///
     ///  # Arguments
     ///  * `link_to` - Address of the message the keyload will be attached to
@@ -149,10 +424,12 @@ impl<Trans: Transport> Author<Trans>
         public_payload: &Bytes,
         masked_payload: &Bytes,
     ) -> Result<(Address, Option<Address>)> {
-        self.user.send_signed_packet(link_to, public_payload, masked_payload)
+        let link_to = self.keyload_link(link_to);
+        self.user.send_signed_packet(&link_to, public_payload, masked_payload)
     }
 
-    /// Create and send a tagged packet.
+    /// Create and send a tagged packet. Packets linked to a keyload superseded by a revocation are
+    /// attached to the revocation keyload.
     ///
     ///  # Arguments
     ///  * `link_to` - Address of the message the keyload will be attached to
@@ -165,7 +442,8 @@ impl<Trans: Transport> Author<Trans>
         public_payload: &Bytes,
         masked_payload: &Bytes,
     ) -> Result<(Address, Option<Address>)> {
-        self.user.send_tagged_packet(link_to, public_payload, masked_payload)
+        let link_to = self.keyload_link(link_to);
+        self.user.send_tagged_packet(&link_to, public_payload, masked_payload)
     }
 
 
@@ -206,10 +484,40 @@ impl<Trans: Transport> Author<Trans>
     }
 
     /// Retrieves the next message for each user (if present in transport layer) and returns them
-    pub fn fetch_next_msgs(&mut self) -> Vec<UnwrappedMessage> {
+    /// together with the failures to fetch or handle published messages
+    pub fn fetch_next_msgs(&mut self) -> FetchReport {
         self.user.fetch_next_msgs()
     }
 
+    /// Iterator over messages pending in the transport, messages are fetched and handled lazily
+    pub fn messages(&mut self) -> MessagesIter<'_, Trans> {
+        self.user.messages()
+    }
+
+    /// Retrieves all messages published in the channel up to the latest one and returns them
+    pub fn sync_state(&mut self) -> FetchReport {
+        self.user.sync_state()
+    }
+
+    /// Retrieves the message the message at `link` has been linked to, own state is not updated
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the message whose predecessor is fetched
+    ///
+    pub fn fetch_prev_msg(&mut self, link: &Address) -> Result<UnwrappedMessage> {
+        self.user.fetch_prev_msg(link)
+    }
+
+    /// Retrieves up to `max` messages preceding the message at `link`, the closest message comes first
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the message to start from
+    ///   * `max` - Maximum number of messages to retrieve
+    ///
+    pub fn fetch_prev_msgs(&mut self, link: &Address, max: usize) -> Result<Vec<UnwrappedMessage>> {
+        self.user.fetch_prev_msgs(link, max)
+    }
+
     /// Receive and process a message of unknown type. Message will be handled appropriately and
     /// the unwrapped contents returned
     ///
@@ -220,10 +528,14 @@ impl<Trans: Transport> Author<Trans>
         self.user.receive_message(link)
     }
 
-    // Unsubscribe a subscriber
-    // pub pub fn receive_unsubscribe(&mut self, link: Address) -> Result<()> {
-    // self.user.handle_unsubscribe(link, MsgInfo::Unsubscribe)
-    // }
+    /// Receive and process an Unsubscribe message, removing the subscriber from the channel.
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the Unsubscribe message
+    ///
+    pub fn receive_unsubscribe(&mut self, link: &Address) -> Result<()> {
+        self.user.receive_unsubscribe(link)
+    }
 }
 
 #[cfg(feature = "async")]
@@ -234,6 +546,16 @@ impl<Trans: Transport> Author<Trans>
         self.user.send_announce().await
     }
 
+    /// Replace the signature key pair with a new one derived from seed. A change key message
+    /// linked to the announcement and signed with the current key is sent.
+    ///
+    ///  # Arguments
+    ///  * `seed` - A string slice representing the seed of the new key pair
+    ///
+    pub async fn send_change_key(&mut self, seed: &str) -> Result<Address> {
+        self.user.send_change_key(seed).await
+    }
+
     /// Create and send a new keyload for a list of subscribers.
     ///
     ///  # Arguments
@@ -247,7 +569,28 @@ impl<Trans: Transport> Author<Trans>
         psk_ids: &PskIds,
         ke_pks: &Vec<ed25519::PublicKey>,
     ) -> Result<(Address, Option<Address>)> {
-        self.user.send_keyload(link_to, psk_ids, ke_pks).await
+        let links = self.user.send_keyload(link_to, psk_ids, ke_pks).await?;
+        self.keyload_sent(&links.0);
+        Ok(links)
+    }
+
+    /// Create and send a new keyload for a list of subscribers, recipients identifiers
+    /// are replaced with blinded tags so that observers can't tell who is authorized.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload will be attached to
+    ///  * `psk_ids` - Vector of Pre-shared key ids to be included in message
+    ///  * `ke_pks`  - Vector of Public Keys to be included in message
+    ///
+    pub async fn send_anonymous_keyload(
+        &mut self,
+        link_to: &Address,
+        psk_ids: &PskIds,
+        ke_pks: &Vec<ed25519::PublicKey>,
+    ) -> Result<(Address, Option<Address>)> {
+        let links = self.user.send_anonymous_keyload(link_to, psk_ids, ke_pks).await?;
+        self.keyload_sent(&links.0);
+        Ok(links)
     }
 
     /// Create and send keyload for all subscribed subscribers.
@@ -256,10 +599,61 @@ impl<Trans: Transport> Author<Trans>
     ///  * `link_to` - Address of the message the keyload will be attached to
     ///
     pub async fn send_keyload_for_everyone(&mut self, link_to: &Address) -> Result<(Address, Option<Address>)> {
-        self.user.send_keyload_for_everyone(link_to).await
+        let links = self.user.send_keyload_for_everyone(link_to).await?;
+        self.keyload_sent(&links.0);
+        Ok(links)
+    }
+
+    /// Revoke read access of a subscriber: its public key (and optionally a pre-shared key) is
+    /// removed and a new keyload for the remaining subscribers is sent. Keyloads sent before are
+    /// superseded by the new one: packets later sent linked to any of them are attached to the new
+    /// keyload instead and can be read by the remaining subscribers only. The subscriber and
+    /// pre-shared key are kept if the keyload can't be sent.
+    ///
+    ///  # Arguments
+    ///  * `pk` - ed25519 Public Key of the subscriber to be revoked
+    ///  * `pskid` - Optional identifier of the pre-shared key to be revoked
+    ///
+    pub async fn revoke(&mut self, pk: &ed25519::PublicKey, pskid: Option<&PskId>) -> Result<(Address, Option<Address>)> {
+        let announcement_link = match self.user.user.appinst {
+            Some(ref appinst) => appinst.clone(),
+            None => return err!(UserNotRegistered),
+        };
+        let revoked = self.user.user.remove_revoked(pk, pskid)?;
+        match self.user.send_keyload_for_everyone(&announcement_link).await {
+            Ok(links) => {
+                self.revocation_sent(&links.0);
+                Ok(links)
+            }
+            Err(err) => {
+                self.user.user.restore_revoked(pk, pskid, revoked)?;
+                Err(err)
+            }
+        }
+    }
+
+    /// Assign writer (grant write permission) or reader (revoke it) role to a subscriber. Signed
+    /// packets of the subscriber are accepted by other users only while it is a writer for the
+    /// branch. Roles are also carried in subsequent keyloads.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the permission will be attached to
+    ///  * `pk` - ed25519 Public Key of the subscriber
+    ///  * `write` - Boolean representing whether write permission is granted or revoked
+    ///  * `branch` - Optional address of the message packets must be linked to, all branches if None
+    ///
+    pub async fn send_permission(
+        &mut self,
+        link_to: &Address,
+        pk: &ed25519::PublicKey,
+        write: bool,
+        branch: Option<&Address>,
+    ) -> Result<(Address, Option<Address>)> {
+        self.user.send_permission(link_to, pk, write, branch).await
     }
 
-    /// Create and send a signed packet.
+    /// Create and send a signed packet. Packets linked to a keyload superseded by a revocation are
+    /// attached to the revocation keyload.
     ///
     ///  # Arguments
     ///  * `link_to` - Address of the message the keyload will be attached to
@@ -272,10 +666,12 @@ impl<Trans: Transport> Author<Trans>
         public_payload: &Bytes,
         masked_payload: &Bytes,
     ) -> Result<(Address, Option<Address>)> {
-        self.user.send_signed_packet(link_to, public_payload, masked_payload).await
+        let link_to = self.keyload_link(link_to);
+        self.user.send_signed_packet(&link_to, public_payload, masked_payload).await
     }
 
-    /// Create and send a tagged packet.
+    /// Create and send a tagged packet. Packets linked to a keyload superseded by a revocation are
+    /// attached to the revocation keyload.
     ///
     ///  # Arguments
     ///  * `link_to` - Address of the message the keyload will be attached to
@@ -288,7 +684,8 @@ impl<Trans: Transport> Author<Trans>
         public_payload: &Bytes,
         masked_payload: &Bytes,
     ) -> Result<(Address, Option<Address>)> {
-        self.user.send_tagged_packet(link_to, public_payload, masked_payload).await
+        let link_to = self.keyload_link(link_to);
+        self.user.send_tagged_packet(&link_to, public_payload, masked_payload).await
     }
 
     /// Receive and process a subscribe message.
@@ -328,10 +725,45 @@ impl<Trans: Transport> Author<Trans>
     }
 
     /// Retrieves the next message for each user (if present in transport layer) and returns them
-    pub async fn fetch_next_msgs(&mut self) -> Vec<UnwrappedMessage> {
+    /// together with the failures to fetch or handle published messages
+    pub async fn fetch_next_msgs(&mut self) -> FetchReport {
         self.user.fetch_next_msgs().await
     }
 
+    /// Stream of incoming messages, the transport is polled every `interval` while there are no
+    /// new messages. Dropping the stream stops polling.
+    ///
+    ///   # Arguments
+    ///   * `interval` - Delay between polls of the transport when there are no new messages
+    ///
+    pub fn messages<'a>(&'a mut self, interval: Duration) -> impl Stream<Item = Result<UnwrappedMessage>> + 'a {
+        self.user.messages(interval)
+    }
+
+    /// Retrieves all messages published in the channel up to the latest one and returns them
+    pub async fn sync_state(&mut self) -> FetchReport {
+        self.user.sync_state().await
+    }
+
+    /// Retrieves the message the message at `link` has been linked to, own state is not updated
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the message whose predecessor is fetched
+    ///
+    pub async fn fetch_prev_msg(&mut self, link: &Address) -> Result<UnwrappedMessage> {
+        self.user.fetch_prev_msg(link).await
+    }
+
+    /// Retrieves up to `max` messages preceding the message at `link`, the closest message comes first
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the message to start from
+    ///   * `max` - Maximum number of messages to retrieve
+    ///
+    pub async fn fetch_prev_msgs(&mut self, link: &Address, max: usize) -> Result<Vec<UnwrappedMessage>> {
+        self.user.fetch_prev_msgs(link, max).await
+    }
+
     /// Receive and process a message of unknown type. Message will be handled appropriately and
     /// the unwrapped contents returned
     ///
@@ -343,10 +775,14 @@ impl<Trans: Transport> Author<Trans>
         self.user.receive_message(link).await
     }
 
-    // Unsubscribe a subscriber
-    // pub async fn receive_unsubscribe(&mut self, link: Address) -> Result<()> {
-    // self.user.handle_unsubscribe(link, MsgInfo::Unsubscribe).await
-    // }
+    /// Receive and process an Unsubscribe message, removing the subscriber from the channel.
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the Unsubscribe message
+    ///
+    pub async fn receive_unsubscribe(&mut self, link: &Address) -> Result<()> {
+        self.user.receive_unsubscribe(link).await
+    }
 }
 
 impl<Trans> fmt::Display for Author<Trans> {
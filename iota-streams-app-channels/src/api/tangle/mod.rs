@@ -1,7 +1,10 @@
 //! Default parameters for Author and Subscriber types.
 
 use super::{
-    pk_store::PublicKeyMap,
+    pk_store::{
+        self,
+        PublicKeyMap,
+    },
     psk_store::PresharedKeyMap,
 };
 use iota_streams_app::{
@@ -26,7 +29,11 @@ pub use transport::{
     TransportOptions as _,
 };
 
-use iota_streams_core::psk;
+use iota_streams_core::{
+    prelude::Vec,
+    psk,
+    Error,
+};
 use iota_streams_core_keccak::sponge::prp::keccak::KeccakF1600;
 use iota_streams_ddml::link_store::DefaultLinkStore;
 pub use iota_streams_ddml::types::Bytes;
@@ -36,6 +43,10 @@ use iota_streams_core_edsig::signature::ed25519;
 /// Default spongos PRP.
 pub type DefaultF = KeccakF1600;
 
+/// Identifier for Pre-Shared Key
+pub type PskId = psk::PskId;
+/// Pre-Shared Key
+pub type Psk = psk::Psk;
 /// Identifiers for Pre-Shared Keys
 pub type PskIds = psk::PskIds;
 
@@ -63,8 +74,12 @@ pub type Preparsed<'a> = message::PreparsedMessage<'a, DefaultF, Address>;
 
 /// Sequence State information
 pub type SeqState = Cursor<MsgId>;
-/// Public Key Mapping for sequence states
-pub type PkStore = PublicKeyMap<SeqState>;
+/// Publishing rights of a participant
+pub type Permission = pk_store::Permission<MsgId>;
+/// Sequence State information together with publishing rights
+pub type PkInfo = pk_store::PkInfo<MsgId>;
+/// Public Key Mapping for sequence states and publishing rights
+pub type PkStore = PublicKeyMap<PkInfo>;
 /// Pre-Shared Key Mapping
 pub type PskStore = PresharedKeyMap;
 
@@ -77,6 +92,10 @@ pub type LinkStore = DefaultLinkStore<DefaultF, MsgId, MsgInfo>;
 /// Test Transport.
 pub type BucketTransport = transport::BucketTransport<Address, Message>;
 
+/// Filesystem-backed Transport.
+#[cfg(feature = "std")]
+pub use transport::FileTransport;
+
 /// Transportation trait for Tangle Client implementation
 // TODO: Use trait synonyms `pub Transport = transport::Transport<DefaultF, Address>;`.
 pub trait Transport: transport::Transport<Address, Message> {}
@@ -86,6 +105,7 @@ mod msginfo;
 pub use msginfo::MsgInfo;
 
 /// Message body returned as part of handle message routine.
+#[derive(Clone, PartialEq)]
 pub enum MessageContent {
     Announce,
     Keyload,
@@ -93,14 +113,23 @@ pub enum MessageContent {
         pk: PublicKey,
         public_payload: Bytes,
         masked_payload: Bytes,
+        timestamp: Option<u64>,
     },
     TaggedPacket {
         public_payload: Bytes,
         masked_payload: Bytes,
+        timestamp: Option<u64>,
     },
     Sequence,
     Subscribe,
     Unsubscribe,
+    ChangeKey {
+        pk: PublicKey,
+    },
+    Permission {
+        pk: PublicKey,
+        write: bool,
+    },
 }
 
 impl MessageContent {
@@ -112,33 +141,93 @@ impl MessageContent {
         Self::Keyload
     }
 
-    pub fn new_signed_packet(pk: PublicKey, public_payload: Bytes, masked_payload: Bytes) -> Self {
+    pub fn new_signed_packet(pk: PublicKey, public_payload: Bytes, masked_payload: Bytes, timestamp: Option<u64>) -> Self {
         Self::SignedPacket {
             pk,
             public_payload,
             masked_payload,
+            timestamp,
         }
     }
 
-    pub fn new_tagged_packet(public_payload: Bytes, masked_payload: Bytes) -> Self {
+    pub fn new_tagged_packet(public_payload: Bytes, masked_payload: Bytes, timestamp: Option<u64>) -> Self {
         Self::TaggedPacket {
             public_payload,
             masked_payload,
+            timestamp,
         }
     }
+
+    pub fn new_unsubscribe() -> Self {
+        Self::Unsubscribe
+    }
+
+    pub fn new_change_key(pk: PublicKey) -> Self {
+        Self::ChangeKey { pk }
+    }
+
+    pub fn new_permission(pk: PublicKey, write: bool) -> Self {
+        Self::Permission { pk, write }
+    }
+}
+
+/// Unwrapped message type containing possible message contents together with the message metadata
+#[derive(Clone, PartialEq)]
+pub struct UnwrappedMessage {
+    /// Address of the message.
+    pub link: Address,
+    /// Unwrapped message contents.
+    pub body: MessageContent,
+    /// Content type of the message, eg. `crate::message::SIGNED_PACKET`.
+    pub content_type: u8,
+    /// Sequence number of the message taken from its header.
+    pub seq_num: u64,
+    /// Public key of the message sender if it's known.
+    pub pk: Option<PublicKey>,
+    /// Address of the message this message has been linked to.
+    pub link_to: Option<Address>,
+    /// Timestamp of the transport message in milliseconds since Unix epoch.
+    pub timestamp: u64,
+}
+
+/// Outcome of fetching next messages in the channel.
+#[derive(Default)]
+pub struct FetchReport {
+    /// Messages which have been fetched and handled successfully.
+    pub msgs: Vec<UnwrappedMessage>,
+    /// Links of messages which have been published but could not be fetched or handled,
+    /// together with the cause of the failure.
+    pub errors: Vec<(Address, Error)>,
 }
 
-/// Generic unwrapped message type containing possible message contents
-pub type UnwrappedMessage = message::GenericMessage<Address, MessageContent>;
+impl FetchReport {
+    /// No new messages have been found.
+    pub fn is_empty(&self) -> bool {
+        self.msgs.is_empty() && self.errors.is_empty()
+    }
+
+    /// Record failure of the message at `link` unless a failure has been recorded for it already.
+    pub(crate) fn push_error(&mut self, link: Address, e: Error) {
+        if self.errors.iter().all(|(failed, _)| *failed != link) {
+            self.errors.push((link, e));
+        }
+    }
+}
 
 mod user;
 /// User object storing the Auth/Sub implementation as well as the transport instance
 pub use user::User;
+#[cfg(not(feature = "async"))]
+pub use user::MessagesIter;
 
 mod author;
 /// Tangle-specific Channel Author type.
 pub use author::Author;
 
+mod multi_channel_author;
+/// Tangle-specific Author type managing several channels.
+pub use multi_channel_author::MultiChannelAuthor;
+
 mod subscriber;
 /// Tangle-specific Channel Subscriber type.
 pub use subscriber::Subscriber;
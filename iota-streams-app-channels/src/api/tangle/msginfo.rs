@@ -18,6 +18,8 @@ pub enum MsgInfo {
     Subscribe,
     Unsubscribe,
     Sequence,
+    ChangeKey,
+    Permission,
 }
 
 // Default instance is required by ddml unwrap logic as unwrap modifies/updates an existing object rather producing a new one.
@@ -37,6 +39,8 @@ impl From<MsgInfo> for u8 {
             MsgInfo::Subscribe => 4,
             MsgInfo::Unsubscribe => 5,
             MsgInfo::Sequence => 6,
+            MsgInfo::ChangeKey => 7,
+            MsgInfo::Permission => 8,
         }
     }
 }
@@ -58,6 +62,8 @@ impl TryFrom<u8> for MsgInfo {
             4 => Ok(MsgInfo::Subscribe),
             5 => Ok(MsgInfo::Unsubscribe),
             6 => Ok(MsgInfo::Sequence),
+            7 => Ok(MsgInfo::ChangeKey),
+            8 => Ok(MsgInfo::Permission),
             _ => Err(()),
         }
     }
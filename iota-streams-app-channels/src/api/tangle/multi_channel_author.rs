@@ -0,0 +1,147 @@
+//! Author managing several channels (application instances) with a single identity.
+
+use iota_streams_core::{
+    prelude::{
+        string::ToString,
+        Vec,
+    },
+    err,
+    try_or,
+    Errors::{
+        ChannelCreationFailure,
+        InputStreamNotFullyConsumed,
+        OutputStreamNotFullyConsumed,
+        UserNotRegistered,
+        UserVersionRecoveryFailure,
+    },
+    Result,
+    LOCATION_LOG,
+};
+use iota_streams_ddml::{
+    command::*,
+    types::*,
+};
+
+use super::*;
+use crate::api::tangle::Author;
+
+/// Multi-channel Author Object. All channels share the same Ed25519 identity, each channel
+/// has its own link generator, sequencing state and subscribers.
+pub struct MultiChannelAuthor<Trans> {
+    channels: Vec<(u64, Author<Trans>)>,
+}
+
+impl<Trans: Clone> MultiChannelAuthor<Trans> {
+    /// Create a new multi-channel Author instance with channel 0.
+    ///
+    /// # Arguments
+    /// * `seed` - A string slice representing the seed of the user [Characters: A-Z, 9]
+    /// * `encoding` - A string slice representing the encoding type for the message [supported: utf-8]
+    /// * `payload_length` - Maximum size in bytes of payload per message chunk [1-1024],
+    /// * `multi_branching` - Boolean representing use of multi-branch or single-branch sequencing
+    /// * `transport` - Transport object used for sending and receiving, it is shared by all channels
+    ///
+    pub fn new(seed: &str, encoding: &str, payload_length: usize, multi_branching: bool, transport: Trans) -> Self {
+        let author = Author::new(seed, encoding, payload_length, multi_branching, transport);
+        Self {
+            channels: vec![(0, author)],
+        }
+    }
+
+    /// Create a new channel with the same identity and settings.
+    ///
+    /// # Arguments
+    /// * `channel_idx` - Index of the channel application instance
+    ///
+    pub fn new_channel(&mut self, channel_idx: u64) -> Result<&mut Author<Trans>> {
+        if let Some(author) = self.channel(channel_idx) {
+            let appinst = author.channel_address().map_or(Default::default(), |appinst| appinst.to_string());
+            return err!(ChannelCreationFailure(appinst));
+        }
+        let author = match self.channels.first() {
+            Some((_, author)) => author.new_channel(channel_idx)?,
+            None => return err!(UserNotRegistered),
+        };
+        self.channels.push((channel_idx, author));
+        let last = self.channels.len() - 1;
+        Ok(&mut self.channels[last].1)
+    }
+
+    /// Fetch the Author of the channel `channel_idx`.
+    pub fn channel(&self, channel_idx: u64) -> Option<&Author<Trans>> {
+        self.channels.iter().find(|(idx, _)| *idx == channel_idx).map(|(_, author)| author)
+    }
+
+    /// Fetch the mutable Author of the channel `channel_idx`.
+    pub fn channel_mut(&mut self, channel_idx: u64) -> Option<&mut Author<Trans>> {
+        self.channels.iter_mut().find(|(idx, _)| *idx == channel_idx).map(|(_, author)| author)
+    }
+
+    /// Fetch the indices of the managed channels in creation order.
+    pub fn channel_indices(&self) -> Vec<u64> {
+        self.channels.iter().map(|(idx, _)| *idx).collect()
+    }
+
+    /// Serialize state of all channels and encrypt it with password.
+    ///
+    ///   # Arguments
+    ///   * `pwd` - Encryption password
+    ///
+    pub fn export(&self, pwd: &str) -> Result<Vec<u8>> {
+        const VERSION: u8 = 0;
+        let mut exports = Vec::with_capacity(self.channels.len());
+        for (idx, author) in self.channels.iter() {
+            exports.push((*idx, author.export(pwd)?));
+        }
+        let repeated_channels = Size(exports.len());
+
+        let buf_size = {
+            let mut ctx = sizeof::Context::<DefaultF>::new();
+            ctx.absorb(Uint8(VERSION))?
+                .absorb(repeated_channels)?
+                .repeated(exports.iter(), |ctx, (idx, bytes)| {
+                    ctx.absorb(Uint64(*idx))?.absorb(<&Bytes>::from(bytes))
+                })?;
+            ctx.get_size()
+        };
+
+        let mut buf = vec![0; buf_size];
+        {
+            let mut ctx = wrap::Context::<DefaultF, _>::new(&mut buf[..]);
+            ctx.absorb(Uint8(VERSION))?
+                .absorb(repeated_channels)?
+                .repeated(exports.iter(), |ctx, (idx, bytes)| {
+                    ctx.absorb(Uint64(*idx))?.absorb(<&Bytes>::from(bytes))
+                })?;
+            try_or!(ctx.stream.is_empty(), OutputStreamNotFullyConsumed(ctx.stream.len()))?;
+        }
+        Ok(buf)
+    }
+
+    /// Deserialize state of all channels and decrypt it with password.
+    ///
+    ///   # Arguments
+    ///   * `bytes` - Encrypted serialized user states
+    ///   * `pwd` - Encryption password
+    ///   * `tsp` - Transport object used for sending and receiving, it is shared by all channels
+    ///
+    pub fn import(bytes: &[u8], pwd: &str, tsp: Trans) -> Result<Self> {
+        const VERSION: u8 = 0;
+        let mut ctx = unwrap::Context::<DefaultF, _>::new(bytes);
+        let mut version = Uint8(0);
+        let mut repeated_channels = Size(0);
+        let mut channels = Vec::new();
+        ctx.absorb(&mut version)?
+            .guard(version.0 == VERSION, UserVersionRecoveryFailure(VERSION, version.0))?
+            .absorb(&mut repeated_channels)?
+            .repeated(repeated_channels, |ctx| {
+                let mut idx = Uint64(0);
+                let mut bytes = Bytes::new();
+                ctx.absorb(&mut idx)?.absorb(&mut bytes)?;
+                channels.push((idx.0, Author::import(&bytes.0, pwd, tsp.clone())?));
+                Ok(ctx)
+            })?;
+        try_or!(ctx.stream.is_empty(), InputStreamNotFullyConsumed(ctx.stream.len()))?;
+        Ok(Self { channels })
+    }
+}
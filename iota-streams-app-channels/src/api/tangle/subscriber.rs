@@ -5,10 +5,18 @@ use core::fmt;
 
 use super::*;
 use crate::api::tangle::{
+    FetchReport,
     UnwrappedMessage,
     User,
 };
 
+#[cfg(not(feature = "async"))]
+use crate::api::tangle::MessagesIter;
+#[cfg(feature = "async")]
+use core::time::Duration;
+#[cfg(feature = "async")]
+use futures::Stream;
+
 use iota_streams_core::prelude::Vec;
 use iota_streams_core_edsig::signature::ed25519;
 
@@ -57,6 +65,36 @@ impl<Trans> Subscriber<Trans>
         self.user.is_multi_branching()
     }
 
+    /// Return boolean representing whether packets in the channel are ratcheted
+    pub fn is_ratcheted(&self) -> bool {
+        self.user.is_ratcheted()
+    }
+
+    /// Return boolean representing whether masked payloads in the channel are padded
+    pub fn is_padded(&self) -> bool {
+        self.user.is_padded()
+    }
+
+    /// Include authenticated timestamp into signed and tagged packets. Timestamp is taken
+    /// from the local clock in milliseconds since Unix epoch.
+    ///
+    ///   # Arguments
+    ///   * `timestamping` - Boolean representing whether packets should be timestamped
+    ///
+    pub fn set_timestamping(&mut self, timestamping: bool) {
+        self.user.set_timestamping(timestamping)
+    }
+
+    /// Set the maximum allowed difference between the timestamp of a received packet and the
+    /// local clock. Packets outside of the window or without a timestamp are rejected.
+    ///
+    ///   # Arguments
+    ///   * `clock_skew` - Window size in milliseconds, `None` accepts packets regardless of timestamp
+    ///
+    pub fn set_clock_skew(&mut self, clock_skew: Option<u64>) {
+        self.user.set_clock_skew(clock_skew)
+    }
+
     /// Stores the provided link to the internal sequencing state for the provided participant
     /// [Used for multi-branching sequence state updates]
     ///
@@ -91,6 +129,49 @@ impl<Trans> Subscriber<Trans>
         self.user.gen_next_msg_ids(branching)
     }
 
+    /// Store a pre-shared key, it can be used in keyloads afterwards.
+    ///
+    ///   # Arguments
+    ///   * `pskid` - Identifier of the pre-shared key
+    ///   * `psk` - Pre-shared key
+    ///
+    pub fn store_psk(&mut self, pskid: PskId, psk: Psk) {
+        self.user.store_psk(pskid, psk)
+    }
+
+    /// Remove a pre-shared key.
+    ///
+    ///   # Arguments
+    ///   * `pskid` - Identifier of the pre-shared key
+    ///
+    pub fn remove_psk(&mut self, pskid: &PskId) -> Result<()> {
+        self.user.remove_psk(pskid)
+    }
+
+    /// Fetch the identifiers of the stored pre-shared keys
+    pub fn psk_ids(&self) -> PskIds {
+        self.user.psk_ids()
+    }
+
+    /// Fetch publishing rights of a participant, None if the participant is not known.
+    ///
+    ///   # Arguments
+    ///   * `pk` - ed25519 Public Key of the participant
+    ///
+    pub fn permission(&self, pk: &ed25519::PublicKey) -> Option<&Permission> {
+        self.user.permission(pk)
+    }
+
+    /// Check whether a participant has writer role for packets linked to the given message.
+    ///
+    ///   # Arguments
+    ///   * `pk` - ed25519 Public Key of the participant
+    ///   * `link_to` - Address of the message packets are linked to
+    ///
+    pub fn is_writer(&self, pk: &ed25519::PublicKey, link_to: &Address) -> bool {
+        self.user.is_writer(pk, link_to)
+    }
+
     /// Serialize user state and encrypt it with password.
     ///
     ///   # Arguments
@@ -156,11 +237,14 @@ impl<Trans: Transport> Subscriber<Trans>
         self.user.send_signed_packet(link_to, public_payload, masked_payload)
     }
 
-    // Unsubscribe from the Channel app instance.
-    // pub pub fn unsubscribe(&mut self, link_to: &Address) -> Result<Message> {
-    // TODO: lookup link_to Subscribe message.
-    // self.user.unsubscribe(link_to.rel(), MsgInfo::Unsubscribe)
-    // }
+    /// Create and send an Unsubscribe message, leaving the Channel app instance.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the Subscribe message previously sent by this subscriber
+    ///
+    pub fn send_unsubscribe(&mut self, link_to: &Address) -> Result<Address> {
+        self.user.send_unsubscribe(link_to)
+    }
 
     /// Receive and Process an announcement message.
     ///
@@ -171,6 +255,25 @@ impl<Trans: Transport> Subscriber<Trans>
         self.user.receive_announcement(link)
     }
 
+    /// Receive and process a change key message, Author's public key is replaced with the new one.
+    ///
+    /// # Arguments
+    /// * `link` - Address of the ChangeKey message
+    ///
+    pub fn receive_change_key(&mut self, link: &Address) -> Result<ed25519::PublicKey> {
+        self.user.receive_change_key(link)
+    }
+
+    /// Receive and process a permission message, returns the participant's public key and
+    /// whether write permission has been granted.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the Permission message
+    ///
+    pub fn receive_permission(&mut self, link: &Address) -> Result<(ed25519::PublicKey, bool)> {
+        self.user.receive_permission(link)
+    }
+
     /// Receive and process a keyload message.
     ///
     ///  # Arguments
@@ -208,10 +311,40 @@ impl<Trans: Transport> Subscriber<Trans>
     }
 
     /// Retrieves the next message for each user (if present in transport layer) and returns them
-    pub fn fetch_next_msgs(&mut self) -> Vec<UnwrappedMessage> {
+    /// together with the failures to fetch or handle published messages
+    pub fn fetch_next_msgs(&mut self) -> FetchReport {
         self.user.fetch_next_msgs()
     }
 
+    /// Iterator over messages pending in the transport, messages are fetched and handled lazily
+    pub fn messages(&mut self) -> MessagesIter<'_, Trans> {
+        self.user.messages()
+    }
+
+    /// Retrieves all messages published in the channel up to the latest one and returns them
+    pub fn sync_state(&mut self) -> FetchReport {
+        self.user.sync_state()
+    }
+
+    /// Retrieves the message the message at `link` has been linked to, own state is not updated
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the message whose predecessor is fetched
+    ///
+    pub fn fetch_prev_msg(&mut self, link: &Address) -> Result<UnwrappedMessage> {
+        self.user.fetch_prev_msg(link)
+    }
+
+    /// Retrieves up to `max` messages preceding the message at `link`, the closest message comes first
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the message to start from
+    ///   * `max` - Maximum number of messages to retrieve
+    ///
+    pub fn fetch_prev_msgs(&mut self, link: &Address, max: usize) -> Result<Vec<UnwrappedMessage>> {
+        self.user.fetch_prev_msgs(link, max)
+    }
+
     /// Receive and process a message of unknown type. Message will be handled appropriately and
     /// the unwrapped contents returned
     ///
@@ -269,11 +402,14 @@ impl<Trans: Transport> Subscriber<Trans>
         self.user.send_signed_packet(link_to, public_payload, masked_payload).await
     }
 
-    // Unsubscribe from the Channel app instance.
-    // pub pub async fn unsubscribe(&mut self, link_to: &Address) -> Result<Message> {
-    // TODO: lookup link_to Subscribe message.
-    // self.user.unsubscribe(link_to.rel(), MsgInfo::Unsubscribe).await
-    // }
+    /// Create and send an Unsubscribe message, leaving the Channel app instance.
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the Subscribe message previously sent by this subscriber
+    ///
+    pub async fn send_unsubscribe(&mut self, link_to: &Address) -> Result<Address> {
+        self.user.send_unsubscribe(link_to).await
+    }
 
     /// Receive and Process an announcement message.
     ///
@@ -284,6 +420,25 @@ impl<Trans: Transport> Subscriber<Trans>
         self.user.receive_announcement(link).await
     }
 
+    /// Receive and process a change key message, Author's public key is replaced with the new one.
+    ///
+    /// # Arguments
+    /// * `link` - Address of the ChangeKey message
+    ///
+    pub async fn receive_change_key(&mut self, link: &Address) -> Result<ed25519::PublicKey> {
+        self.user.receive_change_key(link).await
+    }
+
+    /// Receive and process a permission message, returns the participant's public key and
+    /// whether write permission has been granted.
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the Permission message
+    ///
+    pub async fn receive_permission(&mut self, link: &Address) -> Result<(ed25519::PublicKey, bool)> {
+        self.user.receive_permission(link).await
+    }
+
     /// Receive and process a keyload message.
     ///
     ///  # Arguments
@@ -321,10 +476,45 @@ impl<Trans: Transport> Subscriber<Trans>
     }
 
     /// Retrieves the next message for each user (if present in transport layer) and returns them
-    pub async fn fetch_next_msgs(&mut self) -> Vec<UnwrappedMessage> {
+    /// together with the failures to fetch or handle published messages
+    pub async fn fetch_next_msgs(&mut self) -> FetchReport {
         self.user.fetch_next_msgs().await
     }
 
+    /// Stream of incoming messages, the transport is polled every `interval` while there are no
+    /// new messages. Dropping the stream stops polling.
+    ///
+    ///   # Arguments
+    ///   * `interval` - Delay between polls of the transport when there are no new messages
+    ///
+    pub fn messages<'a>(&'a mut self, interval: Duration) -> impl Stream<Item = Result<UnwrappedMessage>> + 'a {
+        self.user.messages(interval)
+    }
+
+    /// Retrieves all messages published in the channel up to the latest one and returns them
+    pub async fn sync_state(&mut self) -> FetchReport {
+        self.user.sync_state().await
+    }
+
+    /// Retrieves the message the message at `link` has been linked to, own state is not updated
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the message whose predecessor is fetched
+    ///
+    pub async fn fetch_prev_msg(&mut self, link: &Address) -> Result<UnwrappedMessage> {
+        self.user.fetch_prev_msg(link).await
+    }
+
+    /// Retrieves up to `max` messages preceding the message at `link`, the closest message comes first
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the message to start from
+    ///   * `max` - Maximum number of messages to retrieve
+    ///
+    pub async fn fetch_prev_msgs(&mut self, link: &Address, max: usize) -> Result<Vec<UnwrappedMessage>> {
+        self.user.fetch_prev_msgs(link, max).await
+    }
+
     /// Receive and process a message of unknown type. Message will be handled appropriately and
     /// the unwrapped contents returned
     ///
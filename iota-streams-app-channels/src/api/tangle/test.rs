@@ -2,20 +2,33 @@
 use crate::api::tangle::{
     Address,
     Author,
+    MultiChannelAuthor,
     Subscriber,
 };
 use iota_streams_app::{
     message::HasLink,
-    transport::tangle::PAYLOAD_BYTES,
+    transport::{
+        new_shared_transport,
+        tangle::PAYLOAD_BYTES,
+        RetryOptions,
+        SharedTransport,
+    },
 };
 use iota_streams_core::{
-    prelude::string::ToString,
+    prelude::{
+        string::ToString,
+        Vec,
+    },
     println,
+    err,
     try_or,
     Result,
     ensure,
     LOCATION_LOG,
-    Errors::*,
+    Errors::{
+        self,
+        *,
+    },
 };
 
 use super::*;
@@ -133,6 +146,104 @@ pub fn example<T: Transport + Clone>(transport: T) -> Result<()>
         subscriberB.receive_keyload(&keyload_link)?;
     }
 
+    println!("\nunsubscribe");
+    let unsubscribeB_link = {
+        let msg = subscriberB.send_unsubscribe(&subscribeB_link)?;
+        println!("  {}", msg);
+        msg
+    };
+
+    {
+        author.receive_unsubscribe(&unsubscribeB_link)?;
+        ensure!(author.subscribers().is_empty(), "subscriberB was not removed");
+        author.store_new_subscriber(subscriberA.get_pk().clone())?;
+    }
+
+    println!("\nshare keyload for remaining subscribers");
+    let keyload_link = {
+        let (msg, _) = author.send_keyload_for_everyone(&announcement_link)?;
+        println!("  {}", msg);
+        msg
+    };
+
+    {
+        let resultA = subscriberA.receive_keyload(&keyload_link)?;
+        try_or!(resultA, MessageUnwrapFailure("A".to_string()))?;
+        let resultB = subscriberB.receive_keyload(&keyload_link);
+        let unwrapped = resultB.is_ok() && !resultB.unwrap();
+        try_or!(unwrapped, SubscriberAccessMismatch("B".to_string()))?;
+    }
+
+    println!("\nchange key");
+    let change_key_link = {
+        let msg = author.send_change_key("AUTHOR9NEW9SEED")?;
+        println!("  {}", msg);
+        msg
+    };
+
+    {
+        let new_pk = subscriberA.receive_change_key(&change_key_link)?;
+        ensure!(&new_pk == author.get_pk(), "author public key was not changed");
+    }
+
+    println!("\nsign packet with new key");
+    let signed_packet_link = {
+        let (msg, _) = author.send_signed_packet(&keyload_link, &public_payload, &masked_payload)?;
+        println!("  {}", msg);
+        msg
+    };
+
+    {
+        let (pk, _unwrapped_public, unwrapped_masked) = subscriberA.receive_signed_packet(&signed_packet_link)?;
+        ensure!(&pk == author.get_pk(), "signed packet is not signed with the new key");
+        try_or!(
+            masked_payload == unwrapped_masked,
+            MaskedPayloadMismatch(masked_payload.to_string(), unwrapped_masked.to_string())
+        )?;
+    }
+
+    println!("\nshare anonymous keyload for subscriberA");
+    let keyload_link = {
+        let (msg, _) = author.send_anonymous_keyload(&announcement_link, &Vec::new(), &vec![subscriberA.get_pk().clone()])?;
+        println!("  {}", msg);
+        msg
+    };
+
+    {
+        let resultA = subscriberA.receive_keyload(&keyload_link)?;
+        try_or!(resultA, MessageUnwrapFailure("A".to_string()))?;
+        let resultB = subscriberB.receive_keyload(&keyload_link);
+        let unwrapped = resultB.is_ok() && !resultB.unwrap();
+        try_or!(unwrapped, SubscriberAccessMismatch("B".to_string()))?;
+    }
+
+    println!("\nrevoke subscriberA");
+    let revoke_link = {
+        let (msg, _) = author.revoke(&subscriberA.get_pk().clone(), None)?;
+        println!("  {}", msg);
+        msg
+    };
+
+    {
+        ensure!(author.subscribers().is_empty(), "subscriberA was not revoked");
+        ensure!(author.latest_keyload() == Some(revoke_link.clone()), "revoke keyload is not the latest one");
+        let resultA = subscriberA.receive_keyload(&revoke_link);
+        let unwrapped = resultA.is_ok() && !resultA.unwrap();
+        try_or!(unwrapped, SubscriberAccessMismatch("A".to_string()))?;
+    }
+
+    println!("\nsign packet linked to revoked keyload");
+    let signed_packet_link = {
+        let (msg, _) = author.send_signed_packet(&keyload_link, &public_payload, &masked_payload)?;
+        println!("  {}", msg);
+        msg
+    };
+
+    {
+        let resultA = subscriberA.receive_signed_packet(&signed_packet_link);
+        try_or!(resultA.is_err(), SubscriberAccessMismatch("A".to_string()))?;
+    }
+
     let subAdump = subscriberA.export("pwdSubA").unwrap();
     let _subscriberA2 = Subscriber::import(subAdump.as_ref(), "pwdSubA", transport.clone()).unwrap();
 
@@ -140,7 +251,8 @@ pub fn example<T: Transport + Clone>(transport: T) -> Result<()>
     let _subscriberB2 = Subscriber::import(subBdump.as_ref(), "pwdSubB", transport.clone()).unwrap();
 
     let authordump = author.export("pwdAuthor").unwrap();
-    let _author2 = Author::import(authordump.as_ref(), "pwdAuthor", transport.clone()).unwrap();
+    let author2 = Author::import(authordump.as_ref(), "pwdAuthor", transport.clone()).unwrap();
+    ensure!(author2.latest_keyload() == Some(revoke_link), "latest keyload is not preserved by export");
 
     Ok(())
 }
@@ -251,6 +363,104 @@ pub async fn example<T: Transport + Clone>(transport: T) -> Result<()> where
         subscriberB.receive_keyload(&keyload_link).await?;
     }
 
+    println!("\nunsubscribe");
+    let unsubscribeB_link = {
+        let msg = subscriberB.send_unsubscribe(&subscribeB_link).await?;
+        println!("  {}", msg);
+        msg
+    };
+
+    {
+        author.receive_unsubscribe(&unsubscribeB_link).await?;
+        ensure!(author.subscribers().is_empty(), "subscriberB was not removed");
+        author.store_new_subscriber(subscriberA.get_pk().clone())?;
+    }
+
+    println!("\nshare keyload for remaining subscribers");
+    let keyload_link = {
+        let (msg, _) = author.send_keyload_for_everyone(&announcement_link).await?;
+        println!("  {}", msg);
+        msg
+    };
+
+    {
+        let resultA = subscriberA.receive_keyload(&keyload_link).await?;
+        try_or!(resultA, MessageUnwrapFailure("A".to_string()))?;
+        let resultB = subscriberB.receive_keyload(&keyload_link).await;
+        let unwrapped = resultB.is_ok() && !resultB.unwrap();
+        try_or!(unwrapped, SubscriberAccessMismatch("B".to_string()))?;
+    }
+
+    println!("\nchange key");
+    let change_key_link = {
+        let msg = author.send_change_key("AUTHOR9NEW9SEED").await?;
+        println!("  {}", msg);
+        msg
+    };
+
+    {
+        let new_pk = subscriberA.receive_change_key(&change_key_link).await?;
+        ensure!(&new_pk == author.get_pk(), "author public key was not changed");
+    }
+
+    println!("\nsign packet with new key");
+    let signed_packet_link = {
+        let (msg, _) = author.send_signed_packet(&keyload_link, &public_payload, &masked_payload).await?;
+        println!("  {}", msg);
+        msg
+    };
+
+    {
+        let (pk, _unwrapped_public, unwrapped_masked) = subscriberA.receive_signed_packet(&signed_packet_link).await?;
+        ensure!(&pk == author.get_pk(), "signed packet is not signed with the new key");
+        try_or!(
+            masked_payload == unwrapped_masked,
+            MaskedPayloadMismatch(masked_payload.to_string(), unwrapped_masked.to_string())
+        )?;
+    }
+
+    println!("\nshare anonymous keyload for subscriberA");
+    let keyload_link = {
+        let (msg, _) = author.send_anonymous_keyload(&announcement_link, &Vec::new(), &vec![subscriberA.get_pk().clone()]).await?;
+        println!("  {}", msg);
+        msg
+    };
+
+    {
+        let resultA = subscriberA.receive_keyload(&keyload_link).await?;
+        try_or!(resultA, MessageUnwrapFailure("A".to_string()))?;
+        let resultB = subscriberB.receive_keyload(&keyload_link).await;
+        let unwrapped = resultB.is_ok() && !resultB.unwrap();
+        try_or!(unwrapped, SubscriberAccessMismatch("B".to_string()))?;
+    }
+
+    println!("\nrevoke subscriberA");
+    let revoke_link = {
+        let (msg, _) = author.revoke(&subscriberA.get_pk().clone(), None).await?;
+        println!("  {}", msg);
+        msg
+    };
+
+    {
+        ensure!(author.subscribers().is_empty(), "subscriberA was not revoked");
+        ensure!(author.latest_keyload() == Some(revoke_link.clone()), "revoke keyload is not the latest one");
+        let resultA = subscriberA.receive_keyload(&revoke_link).await;
+        let unwrapped = resultA.is_ok() && !resultA.unwrap();
+        try_or!(unwrapped, SubscriberAccessMismatch("A".to_string()))?;
+    }
+
+    println!("\nsign packet linked to revoked keyload");
+    let signed_packet_link = {
+        let (msg, _) = author.send_signed_packet(&keyload_link, &public_payload, &masked_payload).await?;
+        println!("  {}", msg);
+        msg
+    };
+
+    {
+        let resultA = subscriberA.receive_signed_packet(&signed_packet_link).await;
+        try_or!(resultA.is_err(), SubscriberAccessMismatch("A".to_string()))?;
+    }
+
     let subAdump = subscriberA.export("pwdSubA").unwrap();
     let _subscriberA2 = Subscriber::import(subAdump.as_ref(), "pwdSubA", transport.clone()).unwrap();
 
@@ -258,23 +468,1407 @@ pub async fn example<T: Transport + Clone>(transport: T) -> Result<()> where
     let _subscriberB2 = Subscriber::import(subBdump.as_ref(), "pwdSubB", transport.clone()).unwrap();
 
     let authordump = author.export("pwdAuthor").unwrap();
-    let _author2 = Author::import(authordump.as_ref(), "pwdAuthor", transport.clone()).unwrap();
+    let author2 = Author::import(authordump.as_ref(), "pwdAuthor", transport.clone()).unwrap();
+    ensure!(author2.latest_keyload() == Some(revoke_link), "latest keyload is not preserved by export");
 
     Ok(())
 }
 
-#[test]
 #[cfg(not(feature = "async"))]
-fn run_basic_scenario() {
-    let transport = iota_streams_app::transport::new_shared_transport(
-        crate::api::tangle::BucketTransport::new());
-    assert!(dbg!(example(transport)).is_ok());
+pub fn ratchet_example<T: Transport + Clone>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let mut author = Author::new("AUTHOR9RATCHET9SEED", encoding, PAYLOAD_BYTES, false, transport.clone());
+    author.set_ratchet(true)?;
+    let mut subscriber = Subscriber::new("SUBSCRIBER9RATCHET9SEED", encoding, PAYLOAD_BYTES, transport.clone());
+
+    println!("announce ratcheted channel");
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+    ensure!(subscriber.is_ratcheted(), "ratchet flag was not announced");
+    ensure!(author.set_ratchet(false).is_err(), "ratchet flag changed after announcement");
+
+    println!("share keyload");
+    author.store_new_subscriber(subscriber.get_pk().clone())?;
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link)?;
+    try_or!(subscriber.receive_keyload(&keyload_link)?, MessageUnwrapFailure("subscriber".to_string()))?;
+
+    println!("tag ratcheted packets");
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let mut packets = Vec::new();
+    for i in 0..3_u8 {
+        let masked_payload = Bytes(vec![b'A' + i; 16]);
+        let (packet_link, _) = author.send_tagged_packet(&keyload_link, &public_payload, &masked_payload)?;
+        packets.push((packet_link, masked_payload));
+    }
+
+    println!("receive ratcheted packets");
+    for (packet_link, masked_payload) in packets.iter().skip(1) {
+        let (_unwrapped_public, unwrapped_masked) = subscriber.receive_tagged_packet(packet_link)?;
+        try_or!(
+            *masked_payload == unwrapped_masked,
+            MaskedPayloadMismatch(masked_payload.to_string(), unwrapped_masked.to_string())
+        )?;
+    }
+
+    // Keyload state has been advanced past the handled packets, earlier states are gone.
+    println!("reject skipped and handled packets");
+    ensure!(
+        subscriber.receive_tagged_packet(&packets[0].0).is_err(),
+        "skipped ratcheted packet was unwrapped"
+    );
+    ensure!(
+        subscriber.receive_tagged_packet(&packets[1].0).is_err(),
+        "handled ratcheted packet was unwrapped again"
+    );
+
+    println!("export and import ratcheted subscriber");
+    let subdump = subscriber.export("pwdSub")?;
+    let mut subscriber = Subscriber::import(subdump.as_ref(), "pwdSub", transport.clone())?;
+    ensure!(
+        subscriber.receive_tagged_packet(&packets[2].0).is_err(),
+        "handled ratcheted packet was unwrapped after import"
+    );
+    // Large packet is split into frames chained to the ratcheted keyload state.
+    let masked_payload = Bytes(vec![b'M'; 3 * PAYLOAD_BYTES]);
+    let (packet_link, _) = author.send_tagged_packet(&keyload_link, &public_payload, &masked_payload)?;
+    let (_unwrapped_public, unwrapped_masked) = subscriber.receive_tagged_packet(&packet_link)?;
+    try_or!(
+        masked_payload == unwrapped_masked,
+        MaskedPayloadMismatch(masked_payload.to_string(), unwrapped_masked.to_string())
+    )?;
+
+    Ok(())
 }
 
-#[test]
 #[cfg(feature = "async")]
-fn run_basic_scenario() {
-    let transport = iota_streams_app::transport::new_shared_transport(
-        crate::api::tangle::BucketTransport::new());
-    assert!(dbg!(smol::block_on(example(transport))).is_ok());
+pub async fn ratchet_example<T: Transport + Clone>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let mut author = Author::new("AUTHOR9RATCHET9SEED", encoding, PAYLOAD_BYTES, false, transport.clone());
+    author.set_ratchet(true)?;
+    let mut subscriber = Subscriber::new("SUBSCRIBER9RATCHET9SEED", encoding, PAYLOAD_BYTES, transport.clone());
+
+    println!("announce ratcheted channel");
+    let announcement_link = author.send_announce().await?;
+    subscriber.receive_announcement(&announcement_link).await?;
+    ensure!(subscriber.is_ratcheted(), "ratchet flag was not announced");
+    ensure!(author.set_ratchet(false).is_err(), "ratchet flag changed after announcement");
+
+    println!("share keyload");
+    author.store_new_subscriber(subscriber.get_pk().clone())?;
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link).await?;
+    try_or!(subscriber.receive_keyload(&keyload_link).await?, MessageUnwrapFailure("subscriber".to_string()))?;
+
+    println!("tag ratcheted packets");
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let mut packets = Vec::new();
+    for i in 0..3_u8 {
+        let masked_payload = Bytes(vec![b'A' + i; 16]);
+        let (packet_link, _) = author.send_tagged_packet(&keyload_link, &public_payload, &masked_payload).await?;
+        packets.push((packet_link, masked_payload));
+    }
+
+    println!("receive ratcheted packets");
+    for (packet_link, masked_payload) in packets.iter().skip(1) {
+        let (_unwrapped_public, unwrapped_masked) = subscriber.receive_tagged_packet(packet_link).await?;
+        try_or!(
+            *masked_payload == unwrapped_masked,
+            MaskedPayloadMismatch(masked_payload.to_string(), unwrapped_masked.to_string())
+        )?;
+    }
+
+    // Keyload state has been advanced past the handled packets, earlier states are gone.
+    println!("reject skipped and handled packets");
+    ensure!(
+        subscriber.receive_tagged_packet(&packets[0].0).await.is_err(),
+        "skipped ratcheted packet was unwrapped"
+    );
+    ensure!(
+        subscriber.receive_tagged_packet(&packets[1].0).await.is_err(),
+        "handled ratcheted packet was unwrapped again"
+    );
+
+    println!("export and import ratcheted subscriber");
+    let subdump = subscriber.export("pwdSub")?;
+    let mut subscriber = Subscriber::import(subdump.as_ref(), "pwdSub", transport.clone())?;
+    ensure!(
+        subscriber.receive_tagged_packet(&packets[2].0).await.is_err(),
+        "handled ratcheted packet was unwrapped after import"
+    );
+    // Large packet is split into frames chained to the ratcheted keyload state.
+    let masked_payload = Bytes(vec![b'M'; 3 * PAYLOAD_BYTES]);
+    let (packet_link, _) = author.send_tagged_packet(&keyload_link, &public_payload, &masked_payload).await?;
+    let (_unwrapped_public, unwrapped_masked) = subscriber.receive_tagged_packet(&packet_link).await?;
+    try_or!(
+        masked_payload == unwrapped_masked,
+        MaskedPayloadMismatch(masked_payload.to_string(), unwrapped_masked.to_string())
+    )?;
+
+    Ok(())
+}
+
+#[cfg(not(feature = "async"))]
+pub fn delegation_example<T: Transport + Clone>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let mut author = Author::new("AUTHOR9DELEGATION9SEED", encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriberA = Subscriber::new("SUBSCRIBERA9DELEGATION9SEED", encoding, PAYLOAD_BYTES, transport.clone());
+    let mut subscriberB = Subscriber::new("SUBSCRIBERB9DELEGATION9SEED", encoding, PAYLOAD_BYTES, transport.clone());
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+
+    println!("announce");
+    let announcement_link = author.send_announce()?;
+    subscriberA.receive_announcement(&announcement_link)?;
+    subscriberB.receive_announcement(&announcement_link)?;
+
+    println!("share keyload");
+    author.store_new_subscriber(subscriberA.get_pk().clone())?;
+    author.store_new_subscriber(subscriberB.get_pk().clone())?;
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link)?;
+    try_or!(subscriberA.receive_keyload(&keyload_link)?, MessageUnwrapFailure("A".to_string()))?;
+    try_or!(subscriberB.receive_keyload(&keyload_link)?, MessageUnwrapFailure("B".to_string()))?;
+
+    println!("sign packet without permission");
+    let (packet_link, _) = subscriberB.send_signed_packet(&keyload_link, &public_payload, &masked_payload)?;
+    ensure!(
+        subscriberA.receive_signed_packet(&packet_link).is_err(),
+        "signed packet of subscriber without write permission was accepted"
+    );
+
+    println!("grant write permission to subscriberA");
+    let (permission_link, _) = author.send_permission(&keyload_link, subscriberA.get_pk(), true, Some(&keyload_link))?;
+    let (pk, write) = subscriberB.receive_permission(&permission_link)?;
+    ensure!(&pk == subscriberA.get_pk() && write, "bad permission");
+    let expected = Permission::WriteBranches(vec![keyload_link.msgid.clone()]);
+    ensure!(subscriberB.permission(subscriberA.get_pk()) == Some(&expected), "permission was not stored");
+    ensure!(author.permission(subscriberA.get_pk()) == Some(&expected), "permission was not stored by author");
+
+    let subBdump = subscriberB.export("pwdSubB")?;
+    let subscriberB2 = Subscriber::import(subBdump.as_ref(), "pwdSubB", transport.clone())?;
+    ensure!(subscriberB2.permission(subscriberA.get_pk()) == Some(&expected), "permission was not imported");
+
+    println!("sign packet with permission");
+    let (packet_link, _) = subscriberA.send_signed_packet(&keyload_link, &public_payload, &masked_payload)?;
+    let (pk, _unwrapped_public, unwrapped_masked) = subscriberB.receive_signed_packet(&packet_link)?;
+    ensure!(&pk == subscriberA.get_pk(), "bad signer");
+    try_or!(
+        masked_payload == unwrapped_masked,
+        MaskedPayloadMismatch(masked_payload.to_string(), unwrapped_masked.to_string())
+    )?;
+    author.receive_signed_packet(&packet_link)?;
+
+    println!("tag packets by writer and reader");
+    ensure!(subscriberB.is_writer(subscriberA.get_pk(), &keyload_link), "subscriberA is not a writer");
+    ensure!(!subscriberA.is_writer(subscriberB.get_pk(), &keyload_link), "subscriberB is not a reader");
+    let (packet_link, _) = subscriberA.send_tagged_packet(&keyload_link, &public_payload, &masked_payload)?;
+    let (_unwrapped_public, unwrapped_masked) = subscriberB.receive_tagged_packet(&packet_link)?;
+    try_or!(
+        masked_payload == unwrapped_masked,
+        MaskedPayloadMismatch(masked_payload.to_string(), unwrapped_masked.to_string())
+    )?;
+    let (packet_link, _) = subscriberB.send_tagged_packet(&keyload_link, &public_payload, &masked_payload)?;
+    ensure!(
+        subscriberA.receive_tagged_packet(&packet_link).is_err(),
+        "tagged packet of subscriber without write permission was accepted"
+    );
+
+    println!("sign packet in another branch");
+    let (packet_link, _) = subscriberA.send_signed_packet(&announcement_link, &public_payload, &masked_payload)?;
+    ensure!(
+        subscriberB.receive_signed_packet(&packet_link).is_err(),
+        "signed packet outside of the delegated branch was accepted"
+    );
+
+    println!("share roles with a new subscriber in keyload");
+    let mut subscriberC = Subscriber::new("SUBSCRIBERC9DELEGATION9SEED", encoding, PAYLOAD_BYTES, transport.clone());
+    subscriberC.receive_announcement(&announcement_link)?;
+    let (permission_link, _) = author.send_permission(&keyload_link, subscriberA.get_pk(), true, None)?;
+    subscriberB.receive_permission(&permission_link)?;
+    author.store_new_subscriber(subscriberC.get_pk().clone())?;
+    let (new_keyload_link, _) = author.send_keyload_for_everyone(&announcement_link)?;
+    try_or!(subscriberC.receive_keyload(&new_keyload_link)?, MessageUnwrapFailure("C".to_string()))?;
+    try_or!(subscriberA.receive_keyload(&new_keyload_link)?, MessageUnwrapFailure("A".to_string()))?;
+    try_or!(subscriberB.receive_keyload(&new_keyload_link)?, MessageUnwrapFailure("B".to_string()))?;
+    ensure!(
+        subscriberC.permission(subscriberA.get_pk()) == Some(&Permission::Write),
+        "writer role was not shared in keyload"
+    );
+    let (packet_link, _) = subscriberA.send_signed_packet(&new_keyload_link, &public_payload, &masked_payload)?;
+    let (pk, _unwrapped_public, _unwrapped_masked) = subscriberC.receive_signed_packet(&packet_link)?;
+    ensure!(&pk == subscriberA.get_pk(), "bad signer");
+    let (packet_link, _) = subscriberB.send_signed_packet(&new_keyload_link, &public_payload, &masked_payload)?;
+    ensure!(
+        subscriberC.receive_signed_packet(&packet_link).is_err(),
+        "signed packet of a reader was accepted by a new subscriber"
+    );
+
+    println!("revoke write permission of subscriberA");
+    let (permission_link, _) = author.send_permission(&keyload_link, subscriberA.get_pk(), false, None)?;
+    let (_pk, write) = subscriberB.receive_permission(&permission_link)?;
+    ensure!(!write, "bad permission");
+    let (packet_link, _) = subscriberA.send_signed_packet(&keyload_link, &public_payload, &masked_payload)?;
+    ensure!(
+        subscriberB.receive_signed_packet(&packet_link).is_err(),
+        "signed packet of subscriber with revoked permission was accepted"
+    );
+
+    Ok(())
+}
+
+#[cfg(feature = "async")]
+pub async fn delegation_example<T: Transport + Clone>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let mut author = Author::new("AUTHOR9DELEGATION9SEED", encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriberA = Subscriber::new("SUBSCRIBERA9DELEGATION9SEED", encoding, PAYLOAD_BYTES, transport.clone());
+    let mut subscriberB = Subscriber::new("SUBSCRIBERB9DELEGATION9SEED", encoding, PAYLOAD_BYTES, transport.clone());
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+
+    println!("announce");
+    let announcement_link = author.send_announce().await?;
+    subscriberA.receive_announcement(&announcement_link).await?;
+    subscriberB.receive_announcement(&announcement_link).await?;
+
+    println!("share keyload");
+    author.store_new_subscriber(subscriberA.get_pk().clone())?;
+    author.store_new_subscriber(subscriberB.get_pk().clone())?;
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link).await?;
+    try_or!(subscriberA.receive_keyload(&keyload_link).await?, MessageUnwrapFailure("A".to_string()))?;
+    try_or!(subscriberB.receive_keyload(&keyload_link).await?, MessageUnwrapFailure("B".to_string()))?;
+
+    println!("sign packet without permission");
+    let (packet_link, _) = subscriberB.send_signed_packet(&keyload_link, &public_payload, &masked_payload).await?;
+    ensure!(
+        subscriberA.receive_signed_packet(&packet_link).await.is_err(),
+        "signed packet of subscriber without write permission was accepted"
+    );
+
+    println!("grant write permission to subscriberA");
+    let (permission_link, _) = author.send_permission(&keyload_link, subscriberA.get_pk(), true, Some(&keyload_link)).await?;
+    let (pk, write) = subscriberB.receive_permission(&permission_link).await?;
+    ensure!(&pk == subscriberA.get_pk() && write, "bad permission");
+    let expected = Permission::WriteBranches(vec![keyload_link.msgid.clone()]);
+    ensure!(subscriberB.permission(subscriberA.get_pk()) == Some(&expected), "permission was not stored");
+    ensure!(author.permission(subscriberA.get_pk()) == Some(&expected), "permission was not stored by author");
+
+    let subBdump = subscriberB.export("pwdSubB")?;
+    let subscriberB2 = Subscriber::import(subBdump.as_ref(), "pwdSubB", transport.clone())?;
+    ensure!(subscriberB2.permission(subscriberA.get_pk()) == Some(&expected), "permission was not imported");
+
+    println!("sign packet with permission");
+    let (packet_link, _) = subscriberA.send_signed_packet(&keyload_link, &public_payload, &masked_payload).await?;
+    let (pk, _unwrapped_public, unwrapped_masked) = subscriberB.receive_signed_packet(&packet_link).await?;
+    ensure!(&pk == subscriberA.get_pk(), "bad signer");
+    try_or!(
+        masked_payload == unwrapped_masked,
+        MaskedPayloadMismatch(masked_payload.to_string(), unwrapped_masked.to_string())
+    )?;
+    author.receive_signed_packet(&packet_link).await?;
+
+    println!("tag packets by writer and reader");
+    ensure!(subscriberB.is_writer(subscriberA.get_pk(), &keyload_link), "subscriberA is not a writer");
+    ensure!(!subscriberA.is_writer(subscriberB.get_pk(), &keyload_link), "subscriberB is not a reader");
+    let (packet_link, _) = subscriberA.send_tagged_packet(&keyload_link, &public_payload, &masked_payload).await?;
+    let (_unwrapped_public, unwrapped_masked) = subscriberB.receive_tagged_packet(&packet_link).await?;
+    try_or!(
+        masked_payload == unwrapped_masked,
+        MaskedPayloadMismatch(masked_payload.to_string(), unwrapped_masked.to_string())
+    )?;
+    let (packet_link, _) = subscriberB.send_tagged_packet(&keyload_link, &public_payload, &masked_payload).await?;
+    ensure!(
+        subscriberA.receive_tagged_packet(&packet_link).await.is_err(),
+        "tagged packet of subscriber without write permission was accepted"
+    );
+
+    println!("sign packet in another branch");
+    let (packet_link, _) = subscriberA.send_signed_packet(&announcement_link, &public_payload, &masked_payload).await?;
+    ensure!(
+        subscriberB.receive_signed_packet(&packet_link).await.is_err(),
+        "signed packet outside of the delegated branch was accepted"
+    );
+
+    println!("share roles with a new subscriber in keyload");
+    let mut subscriberC = Subscriber::new("SUBSCRIBERC9DELEGATION9SEED", encoding, PAYLOAD_BYTES, transport.clone());
+    subscriberC.receive_announcement(&announcement_link).await?;
+    let (permission_link, _) = author.send_permission(&keyload_link, subscriberA.get_pk(), true, None).await?;
+    subscriberB.receive_permission(&permission_link).await?;
+    author.store_new_subscriber(subscriberC.get_pk().clone())?;
+    let (new_keyload_link, _) = author.send_keyload_for_everyone(&announcement_link).await?;
+    try_or!(subscriberC.receive_keyload(&new_keyload_link).await?, MessageUnwrapFailure("C".to_string()))?;
+    try_or!(subscriberA.receive_keyload(&new_keyload_link).await?, MessageUnwrapFailure("A".to_string()))?;
+    try_or!(subscriberB.receive_keyload(&new_keyload_link).await?, MessageUnwrapFailure("B".to_string()))?;
+    ensure!(
+        subscriberC.permission(subscriberA.get_pk()) == Some(&Permission::Write),
+        "writer role was not shared in keyload"
+    );
+    let (packet_link, _) = subscriberA.send_signed_packet(&new_keyload_link, &public_payload, &masked_payload).await?;
+    let (pk, _unwrapped_public, _unwrapped_masked) = subscriberC.receive_signed_packet(&packet_link).await?;
+    ensure!(&pk == subscriberA.get_pk(), "bad signer");
+    let (packet_link, _) = subscriberB.send_signed_packet(&new_keyload_link, &public_payload, &masked_payload).await?;
+    ensure!(
+        subscriberC.receive_signed_packet(&packet_link).await.is_err(),
+        "signed packet of a reader was accepted by a new subscriber"
+    );
+
+    println!("revoke write permission of subscriberA");
+    let (permission_link, _) = author.send_permission(&keyload_link, subscriberA.get_pk(), false, None).await?;
+    let (_pk, write) = subscriberB.receive_permission(&permission_link).await?;
+    ensure!(!write, "bad permission");
+    let (packet_link, _) = subscriberA.send_signed_packet(&keyload_link, &public_payload, &masked_payload).await?;
+    ensure!(
+        subscriberB.receive_signed_packet(&packet_link).await.is_err(),
+        "signed packet of subscriber with revoked permission was accepted"
+    );
+
+    Ok(())
+}
+
+#[cfg(not(feature = "async"))]
+pub fn large_payload_example<T: Transport + Clone>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let mut author = Author::new("AUTHOR9LARGE9SEED", encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new("SUBSCRIBER9LARGE9SEED", encoding, PAYLOAD_BYTES, transport.clone());
+
+    println!("announce channel");
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+
+    println!("sign packet spanning multiple frames");
+    let public_payload = Bytes((0..3 * PAYLOAD_BYTES).map(|i| i as u8).collect());
+    let masked_payload = Bytes(vec![b'M'; 2 * PAYLOAD_BYTES]);
+    let (packet_link, _) = author.send_signed_packet(&announcement_link, &public_payload, &masked_payload)?;
+    let (_signer_pk, unwrapped_public, unwrapped_masked) = subscriber.receive_signed_packet(&packet_link)?;
+    try_or!(
+        public_payload == unwrapped_public,
+        PublicPayloadMismatch(public_payload.to_string(), unwrapped_public.to_string())
+    )?;
+    try_or!(
+        masked_payload == unwrapped_masked,
+        MaskedPayloadMismatch(masked_payload.to_string(), unwrapped_masked.to_string())
+    )?;
+
+    println!("tag packet spanning multiple frames");
+    author.store_new_subscriber(subscriber.get_pk().clone())?;
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link)?;
+    try_or!(subscriber.receive_keyload(&keyload_link)?, MessageUnwrapFailure("subscriber".to_string()))?;
+    let (packet_link, _) = author.send_tagged_packet(&keyload_link, &public_payload, &masked_payload)?;
+    let msg = subscriber.receive_msg(&packet_link)?;
+    match msg.body {
+        MessageContent::TaggedPacket { public_payload: p, masked_payload: m, .. } => {
+            ensure!(p == public_payload && m == masked_payload, "reassembled tagged packet does not match");
+        },
+        _ => return err!(MessageUnwrapFailure("tagged packet".to_string())),
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "async")]
+pub async fn large_payload_example<T: Transport + Clone>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let mut author = Author::new("AUTHOR9LARGE9SEED", encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new("SUBSCRIBER9LARGE9SEED", encoding, PAYLOAD_BYTES, transport.clone());
+
+    println!("announce channel");
+    let announcement_link = author.send_announce().await?;
+    subscriber.receive_announcement(&announcement_link).await?;
+
+    println!("sign packet spanning multiple frames");
+    let public_payload = Bytes((0..3 * PAYLOAD_BYTES).map(|i| i as u8).collect());
+    let masked_payload = Bytes(vec![b'M'; 2 * PAYLOAD_BYTES]);
+    let (packet_link, _) = author.send_signed_packet(&announcement_link, &public_payload, &masked_payload).await?;
+    let (_signer_pk, unwrapped_public, unwrapped_masked) = subscriber.receive_signed_packet(&packet_link).await?;
+    try_or!(
+        public_payload == unwrapped_public,
+        PublicPayloadMismatch(public_payload.to_string(), unwrapped_public.to_string())
+    )?;
+    try_or!(
+        masked_payload == unwrapped_masked,
+        MaskedPayloadMismatch(masked_payload.to_string(), unwrapped_masked.to_string())
+    )?;
+
+    println!("tag packet spanning multiple frames");
+    author.store_new_subscriber(subscriber.get_pk().clone())?;
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link).await?;
+    try_or!(subscriber.receive_keyload(&keyload_link).await?, MessageUnwrapFailure("subscriber".to_string()))?;
+    let (packet_link, _) = author.send_tagged_packet(&keyload_link, &public_payload, &masked_payload).await?;
+    let msg = subscriber.receive_msg(&packet_link).await?;
+    match msg.body {
+        MessageContent::TaggedPacket { public_payload: p, masked_payload: m, .. } => {
+            ensure!(p == public_payload && m == masked_payload, "reassembled tagged packet does not match");
+        },
+        _ => return err!(MessageUnwrapFailure("tagged packet".to_string())),
+    }
+
+    Ok(())
+}
+
+#[cfg(not(feature = "async"))]
+pub fn padding_example<T: Transport + Clone>(mut transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let payload_length = 256;
+    let mut author = Author::new("AUTHOR9PADDING9SEED", encoding, payload_length, false, transport.clone());
+    author.set_padding(true)?;
+    let mut subscriber = Subscriber::new("SUBSCRIBER9PADDING9SEED", encoding, payload_length, transport.clone());
+
+    println!("announce padded channel");
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+    ensure!(subscriber.is_padded(), "padding flag was not announced");
+    ensure!(author.set_padding(false).is_err(), "padding flag changed after announcement");
+
+    println!("sign padded packets");
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    for masked_len in &[0, 1, 100, 300] {
+        let masked_payload = Bytes(vec![b'M'; *masked_len]);
+        let (packet_link, _) = author.send_signed_packet(&announcement_link, &public_payload, &masked_payload)?;
+        let frame = transport.recv_message(&packet_link)?;
+        ensure!(
+            frame.binary.body.bytes.len() == payload_length,
+            "padded packet does not fill the frame"
+        );
+        let (_signer_pk, _unwrapped_public, unwrapped_masked) = subscriber.receive_signed_packet(&packet_link)?;
+        try_or!(
+            masked_payload == unwrapped_masked,
+            MaskedPayloadMismatch(masked_payload.to_string(), unwrapped_masked.to_string())
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "async")]
+pub async fn padding_example<T: Transport + Clone>(mut transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let payload_length = 256;
+    let mut author = Author::new("AUTHOR9PADDING9SEED", encoding, payload_length, false, transport.clone());
+    author.set_padding(true)?;
+    let mut subscriber = Subscriber::new("SUBSCRIBER9PADDING9SEED", encoding, payload_length, transport.clone());
+
+    println!("announce padded channel");
+    let announcement_link = author.send_announce().await?;
+    subscriber.receive_announcement(&announcement_link).await?;
+    ensure!(subscriber.is_padded(), "padding flag was not announced");
+    ensure!(author.set_padding(false).is_err(), "padding flag changed after announcement");
+
+    println!("sign padded packets");
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    for masked_len in &[0, 1, 100, 300] {
+        let masked_payload = Bytes(vec![b'M'; *masked_len]);
+        let (packet_link, _) = author.send_signed_packet(&announcement_link, &public_payload, &masked_payload).await?;
+        let frame = transport.recv_message(&packet_link).await?;
+        ensure!(
+            frame.binary.body.bytes.len() == payload_length,
+            "padded packet does not fill the frame"
+        );
+        let (_signer_pk, _unwrapped_public, unwrapped_masked) = subscriber.receive_signed_packet(&packet_link).await?;
+        try_or!(
+            masked_payload == unwrapped_masked,
+            MaskedPayloadMismatch(masked_payload.to_string(), unwrapped_masked.to_string())
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(not(feature = "async"))]
+pub fn timestamp_example<T: Transport + Clone>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let mut author = Author::new("AUTHOR9TIMESTAMP9SEED", encoding, PAYLOAD_BYTES, false, transport.clone());
+    author.set_timestamping(true);
+    let mut subscriber = Subscriber::new("SUBSCRIBER9TIMESTAMP9SEED", encoding, PAYLOAD_BYTES, transport.clone());
+    subscriber.set_clock_skew(Some(60_000));
+
+    println!("announce channel");
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+
+    println!("sign timestamped packet");
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+    let (packet_link, _) = author.send_signed_packet(&announcement_link, &public_payload, &masked_payload)?;
+    let msg = subscriber.receive_msg(&packet_link)?;
+    match msg.body {
+        MessageContent::SignedPacket { timestamp, .. } => {
+            ensure!(timestamp.is_some(), "signed packet is not timestamped");
+        },
+        _ => return err!(MessageUnwrapFailure("signed packet".to_string())),
+    }
+
+    println!("reject packet without timestamp");
+    author.set_timestamping(false);
+    let (packet_link, _) = author.send_signed_packet(&announcement_link, &public_payload, &masked_payload)?;
+    let msg = transport.clone().recv_message(&packet_link)?;
+    ensure!(
+        msg.binary.parse_header()?.header.get_content_version() == 0,
+        "packet without timestamp changes the content version"
+    );
+    ensure!(
+        subscriber.receive_signed_packet(&packet_link).is_err(),
+        "packet without timestamp accepted"
+    );
+
+    println!("export clock skew policy");
+    let subscriber_dump = subscriber.export("pwdSub")?;
+    let mut subscriber2 = Subscriber::import(subscriber_dump.as_ref(), "pwdSub", transport.clone())?;
+    ensure!(
+        subscriber2.receive_signed_packet(&packet_link).is_err(),
+        "clock skew is not preserved by export"
+    );
+    subscriber.set_clock_skew(None);
+    let (_signer_pk, _unwrapped_public, unwrapped_masked) = subscriber.receive_signed_packet(&packet_link)?;
+    try_or!(
+        masked_payload == unwrapped_masked,
+        MaskedPayloadMismatch(masked_payload.to_string(), unwrapped_masked.to_string())
+    )?;
+
+    Ok(())
+}
+
+#[cfg(feature = "async")]
+pub async fn timestamp_example<T: Transport + Clone>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let mut author = Author::new("AUTHOR9TIMESTAMP9SEED", encoding, PAYLOAD_BYTES, false, transport.clone());
+    author.set_timestamping(true);
+    let mut subscriber = Subscriber::new("SUBSCRIBER9TIMESTAMP9SEED", encoding, PAYLOAD_BYTES, transport.clone());
+    subscriber.set_clock_skew(Some(60_000));
+
+    println!("announce channel");
+    let announcement_link = author.send_announce().await?;
+    subscriber.receive_announcement(&announcement_link).await?;
+
+    println!("sign timestamped packet");
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+    let (packet_link, _) = author.send_signed_packet(&announcement_link, &public_payload, &masked_payload).await?;
+    let msg = subscriber.receive_msg(&packet_link).await?;
+    match msg.body {
+        MessageContent::SignedPacket { timestamp, .. } => {
+            ensure!(timestamp.is_some(), "signed packet is not timestamped");
+        },
+        _ => return err!(MessageUnwrapFailure("signed packet".to_string())),
+    }
+
+    println!("reject packet without timestamp");
+    author.set_timestamping(false);
+    let (packet_link, _) = author.send_signed_packet(&announcement_link, &public_payload, &masked_payload).await?;
+    let msg = transport.clone().recv_message(&packet_link).await?;
+    ensure!(
+        msg.binary.parse_header()?.header.get_content_version() == 0,
+        "packet without timestamp changes the content version"
+    );
+    ensure!(
+        subscriber.receive_signed_packet(&packet_link).await.is_err(),
+        "packet without timestamp accepted"
+    );
+
+    println!("export clock skew policy");
+    let subscriber_dump = subscriber.export("pwdSub")?;
+    let mut subscriber2 = Subscriber::import(subscriber_dump.as_ref(), "pwdSub", transport.clone())?;
+    ensure!(
+        subscriber2.receive_signed_packet(&packet_link).await.is_err(),
+        "clock skew is not preserved by export"
+    );
+    subscriber.set_clock_skew(None);
+    let (_signer_pk, _unwrapped_public, unwrapped_masked) = subscriber.receive_signed_packet(&packet_link).await?;
+    try_or!(
+        masked_payload == unwrapped_masked,
+        MaskedPayloadMismatch(masked_payload.to_string(), unwrapped_masked.to_string())
+    )?;
+
+    Ok(())
+}
+
+#[cfg(not(feature = "async"))]
+pub fn metadata_example<T: Transport + Clone>(mut transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let mut author = Author::new("AUTHOR9METADATA9SEED", encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new("SUBSCRIBER9METADATA9SEED", encoding, PAYLOAD_BYTES, transport.clone());
+
+    println!("announce channel");
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+
+    println!("receive keyload with metadata");
+    author.store_new_subscriber(subscriber.get_pk().clone())?;
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link)?;
+    let msg = subscriber.receive_msg(&keyload_link)?;
+    ensure!(msg.content_type == crate::message::KEYLOAD, "bad keyload content type");
+    ensure!(msg.pk.as_ref() == Some(author.get_pk()), "bad keyload sender");
+    ensure!(msg.link_to.as_ref() == Some(&announcement_link), "bad keyload link_to");
+
+    println!("receive tagged packet with metadata");
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+    let (packet_link, _) = author.send_tagged_packet(&keyload_link, &public_payload, &masked_payload)?;
+    let keyload_seq_num = msg.seq_num;
+    let msg = subscriber.receive_msg(&packet_link)?;
+    ensure!(msg.content_type == crate::message::TAGGED_PACKET, "bad packet content type");
+    ensure!(msg.pk.as_ref() == Some(author.get_pk()), "bad packet sender");
+    ensure!(msg.link_to.as_ref() == Some(&keyload_link), "bad packet link_to");
+    ensure!(msg.seq_num > keyload_seq_num, "bad packet sequence number");
+    let sent = transport.recv_message(&packet_link)?;
+    ensure!(msg.timestamp == sent.timestamp, "bad packet timestamp");
+
+    Ok(())
+}
+
+#[cfg(feature = "async")]
+pub async fn metadata_example<T: Transport + Clone>(mut transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let mut author = Author::new("AUTHOR9METADATA9SEED", encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new("SUBSCRIBER9METADATA9SEED", encoding, PAYLOAD_BYTES, transport.clone());
+
+    println!("announce channel");
+    let announcement_link = author.send_announce().await?;
+    subscriber.receive_announcement(&announcement_link).await?;
+
+    println!("receive keyload with metadata");
+    author.store_new_subscriber(subscriber.get_pk().clone())?;
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link).await?;
+    let msg = subscriber.receive_msg(&keyload_link).await?;
+    ensure!(msg.content_type == crate::message::KEYLOAD, "bad keyload content type");
+    ensure!(msg.pk.as_ref() == Some(author.get_pk()), "bad keyload sender");
+    ensure!(msg.link_to.as_ref() == Some(&announcement_link), "bad keyload link_to");
+
+    println!("receive tagged packet with metadata");
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+    let (packet_link, _) = author.send_tagged_packet(&keyload_link, &public_payload, &masked_payload).await?;
+    let keyload_seq_num = msg.seq_num;
+    let msg = subscriber.receive_msg(&packet_link).await?;
+    ensure!(msg.content_type == crate::message::TAGGED_PACKET, "bad packet content type");
+    ensure!(msg.pk.as_ref() == Some(author.get_pk()), "bad packet sender");
+    ensure!(msg.link_to.as_ref() == Some(&keyload_link), "bad packet link_to");
+    ensure!(msg.seq_num > keyload_seq_num, "bad packet sequence number");
+    let sent = transport.recv_message(&packet_link).await?;
+    ensure!(msg.timestamp == sent.timestamp, "bad packet timestamp");
+
+    Ok(())
+}
+
+#[cfg(not(feature = "async"))]
+pub fn history_example<T: Transport + Clone>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let mut author = Author::new("AUTHOR9HISTORY9SEED", encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new("SUBSCRIBER9HISTORY9SEED", encoding, PAYLOAD_BYTES, transport.clone());
+
+    println!("publish channel history");
+    let announcement_link = author.send_announce()?;
+    author.store_new_subscriber(subscriber.get_pk().clone())?;
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link)?;
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+    let mut links = vec![announcement_link.clone(), keyload_link];
+    for _ in 0..3 {
+        let (packet_link, _) = author.send_tagged_packet(links.last().unwrap(), &public_payload, &masked_payload)?;
+        links.push(packet_link);
+    }
+
+    println!("sync subscriber state");
+    subscriber.receive_announcement(&announcement_link)?;
+    let msgs = subscriber.sync_state().msgs;
+    try_or!(msgs.len() == 4, ValueMismatch(4, msgs.len()))?;
+    ensure!(msgs.last().unwrap().link == *links.last().unwrap(), "sync_state stopped before the head");
+
+    println!("traverse history backwards");
+    let head = links.last().unwrap().clone();
+    let prev_msgs = subscriber.fetch_prev_msgs(&head, 10)?;
+    try_or!(prev_msgs.len() == 4, ValueMismatch(4, prev_msgs.len()))?;
+    for (msg, link) in prev_msgs.iter().zip(links.iter().rev().skip(1)) {
+        ensure!(msg.link == *link, "unexpected message in history");
+    }
+    let prev_msg = subscriber.fetch_prev_msg(&links[1])?;
+    ensure!(prev_msg.content_type == crate::message::ANNOUNCE, "keyload is not linked to announcement");
+    ensure!(
+        subscriber.fetch_prev_msg(&announcement_link).is_err(),
+        "announcement is linked to a previous message"
+    );
+
+    println!("history traversal does not affect state");
+    let (packet_link, _) = author.send_tagged_packet(&head, &public_payload, &masked_payload)?;
+    let msgs = subscriber.fetch_next_msgs().msgs;
+    try_or!(msgs.len() == 1, ValueMismatch(1, msgs.len()))?;
+    ensure!(msgs[0].link == packet_link, "next message was not fetched");
+
+    Ok(())
+}
+
+#[cfg(feature = "async")]
+pub async fn history_example<T: Transport + Clone>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let mut author = Author::new("AUTHOR9HISTORY9SEED", encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new("SUBSCRIBER9HISTORY9SEED", encoding, PAYLOAD_BYTES, transport.clone());
+
+    println!("publish channel history");
+    let announcement_link = author.send_announce().await?;
+    author.store_new_subscriber(subscriber.get_pk().clone())?;
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link).await?;
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+    let mut links = vec![announcement_link.clone(), keyload_link];
+    for _ in 0..3 {
+        let (packet_link, _) = author.send_tagged_packet(links.last().unwrap(), &public_payload, &masked_payload).await?;
+        links.push(packet_link);
+    }
+
+    println!("sync subscriber state");
+    subscriber.receive_announcement(&announcement_link).await?;
+    let msgs = subscriber.sync_state().await.msgs;
+    try_or!(msgs.len() == 4, ValueMismatch(4, msgs.len()))?;
+    ensure!(msgs.last().unwrap().link == *links.last().unwrap(), "sync_state stopped before the head");
+
+    println!("traverse history backwards");
+    let head = links.last().unwrap().clone();
+    let prev_msgs = subscriber.fetch_prev_msgs(&head, 10).await?;
+    try_or!(prev_msgs.len() == 4, ValueMismatch(4, prev_msgs.len()))?;
+    for (msg, link) in prev_msgs.iter().zip(links.iter().rev().skip(1)) {
+        ensure!(msg.link == *link, "unexpected message in history");
+    }
+    let prev_msg = subscriber.fetch_prev_msg(&links[1]).await?;
+    ensure!(prev_msg.content_type == crate::message::ANNOUNCE, "keyload is not linked to announcement");
+    ensure!(
+        subscriber.fetch_prev_msg(&announcement_link).await.is_err(),
+        "announcement is linked to a previous message"
+    );
+
+    println!("history traversal does not affect state");
+    let (packet_link, _) = author.send_tagged_packet(&head, &public_payload, &masked_payload).await?;
+    let msgs = subscriber.fetch_next_msgs().await.msgs;
+    try_or!(msgs.len() == 1, ValueMismatch(1, msgs.len()))?;
+    ensure!(msgs[0].link == packet_link, "next message was not fetched");
+
+    Ok(())
+}
+
+#[cfg(feature = "async")]
+pub async fn stream_example<T: Transport + Clone>(transport: T) -> Result<()> {
+    use futures::StreamExt as _;
+
+    let encoding = "utf-8";
+    let mut author = Author::new("AUTHOR9STREAM9SEED", encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new("SUBSCRIBER9STREAM9SEED", encoding, PAYLOAD_BYTES, transport.clone());
+
+    println!("publish messages");
+    let announcement_link = author.send_announce().await?;
+    subscriber.receive_announcement(&announcement_link).await?;
+    author.store_new_subscriber(subscriber.get_pk().clone())?;
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link).await?;
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+    let mut links = vec![keyload_link];
+    for _ in 0..2 {
+        let (packet_link, _) = author.send_tagged_packet(links.last().unwrap(), &public_payload, &masked_payload).await?;
+        links.push(packet_link);
+    }
+
+    println!("consume message stream");
+    {
+        let mut messages = iota_streams_core::prelude::Box::pin(subscriber.messages(core::time::Duration::from_millis(10)));
+        for link in links[..2].iter() {
+            match messages.next().await {
+                Some(msg) => ensure!(msg?.link == *link, "unexpected message in stream"),
+                None => return err!(MessageUnwrapFailure("stream".to_string())),
+            }
+        }
+    }
+    // Messages not yielded by the dropped stream are still pending
+    let report = subscriber.fetch_next_msgs().await;
+    try_or!(report.msgs.len() == 1, ValueMismatch(1, report.msgs.len()))?;
+    ensure!(report.msgs[0].link == links[2], "stream advanced sequencing state past yielded messages");
+    ensure!(subscriber.fetch_next_msgs().await.is_empty(), "stream did not advance sequencing state");
+
+    Ok(())
+}
+
+#[cfg(feature = "async")]
+pub async fn shared_transport_example<T: Transport + Clone>(transport: T) -> Result<()> {
+    use futures::future::join_all;
+
+    let encoding = "utf-8";
+    let mut author = Author::new("AUTHOR9SHARED9SEED", encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscribers: Vec<Subscriber<T>> = ["SUBSCRIBERA9SHARED9SEED", "SUBSCRIBERB9SHARED9SEED", "SUBSCRIBERC9SHARED9SEED"]
+        .iter()
+        .map(|seed| Subscriber::new(seed, encoding, PAYLOAD_BYTES, transport.clone()))
+        .collect();
+
+    println!("subscribe concurrently");
+    let announcement_link = author.send_announce().await?;
+    for result in join_all(subscribers.iter_mut().map(|s| s.receive_announcement(&announcement_link))).await {
+        result?;
+    }
+    for result in join_all(subscribers.iter_mut().map(|s| s.send_subscribe(&announcement_link))).await {
+        author.receive_subscribe(&result?).await?;
+    }
+
+    println!("fetch keyload concurrently");
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link).await?;
+    for report in join_all(subscribers.iter_mut().map(|s| s.fetch_next_msgs())).await {
+        try_or!(report.msgs.len() == 1, ValueMismatch(1, report.msgs.len()))?;
+        ensure!(report.msgs[0].link == keyload_link, "unexpected message fetched");
+    }
+
+    println!("fetch packet concurrently");
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+    let (packet_link, _) = author.send_tagged_packet(&keyload_link, &public_payload, &masked_payload).await?;
+    for report in join_all(subscribers.iter_mut().map(|s| s.fetch_next_msgs())).await {
+        try_or!(report.msgs.len() == 1, ValueMismatch(1, report.msgs.len()))?;
+        ensure!(report.msgs[0].link == packet_link, "unexpected message fetched");
+    }
+
+    Ok(())
+}
+
+#[cfg(not(feature = "async"))]
+pub fn iter_example<T: Transport + Clone>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let mut author = Author::new("AUTHOR9ITER9SEED", encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new("SUBSCRIBER9ITER9SEED", encoding, PAYLOAD_BYTES, transport.clone());
+
+    println!("publish messages");
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+    author.store_new_subscriber(subscriber.get_pk().clone())?;
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link)?;
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+    let mut links = vec![keyload_link];
+    for _ in 0..2 {
+        let (packet_link, _) = author.send_tagged_packet(links.last().unwrap(), &public_payload, &masked_payload)?;
+        links.push(packet_link);
+    }
+    // Subscriber is not a recipient of the keyload and can't unwrap the following packet
+    let (private_keyload_link, _) = author.send_keyload(links.last().unwrap(), &Vec::new(), &Vec::new())?;
+    links.push(private_keyload_link);
+    let (private_packet_link, _) = author.send_tagged_packet(links.last().unwrap(), &public_payload, &masked_payload)?;
+
+    println!("stop iteration early");
+    let first = subscriber.messages().next();
+    match first {
+        Some(msg) => ensure!(msg?.link == links[0], "unexpected first message"),
+        None => return err!(MessageUnwrapFailure("iterator".to_string())),
+    }
+
+    println!("iterate over the rest of messages");
+    let mut msgs = subscriber.messages();
+    for link in links.iter().skip(1) {
+        match msgs.next() {
+            Some(msg) => ensure!(msg?.link == *link, "unexpected message in iterator"),
+            None => return err!(MessageUnwrapFailure("iterator".to_string())),
+        }
+    }
+    ensure!(matches!(msgs.next(), Some(Err(_))), "unreadable packet was not reported");
+    ensure!(msgs.next().is_none(), "iterator did not stop");
+    drop(msgs);
+    ensure!(
+        subscriber.receive_tagged_packet(&private_packet_link).is_err(),
+        "unreadable packet was handled"
+    );
+
+    Ok(())
+}
+
+#[cfg(not(feature = "async"))]
+pub fn report_example<T: Transport + Clone>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let mut author = Author::new("AUTHOR9REPORT9SEED", encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new("SUBSCRIBER9REPORT9SEED", encoding, PAYLOAD_BYTES, transport.clone());
+
+    println!("publish packet subscriber can't unwrap");
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+    let (keyload_link, _) = author.send_keyload(&announcement_link, &Vec::new(), &Vec::new())?;
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+    let (packet_link, _) = author.send_tagged_packet(&keyload_link, &public_payload, &masked_payload)?;
+
+    println!("report failures");
+    let report = subscriber.sync_state();
+    try_or!(report.msgs.len() == 1, ValueMismatch(1, report.msgs.len()))?;
+    ensure!(report.msgs[0].link == keyload_link, "keyload was not fetched");
+    try_or!(report.errors.len() == 1, ValueMismatch(1, report.errors.len()))?;
+    ensure!(report.errors[0].0 == packet_link, "failed packet was not reported");
+
+    let report = subscriber.fetch_next_msgs();
+    ensure!(report.msgs.is_empty(), "failed packet advanced sequencing state");
+    try_or!(report.errors.len() == 1, ValueMismatch(1, report.errors.len()))?;
+
+    Ok(())
+}
+
+#[cfg(feature = "async")]
+pub async fn report_example<T: Transport + Clone>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let mut author = Author::new("AUTHOR9REPORT9SEED", encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new("SUBSCRIBER9REPORT9SEED", encoding, PAYLOAD_BYTES, transport.clone());
+
+    println!("publish packet subscriber can't unwrap");
+    let announcement_link = author.send_announce().await?;
+    subscriber.receive_announcement(&announcement_link).await?;
+    let (keyload_link, _) = author.send_keyload(&announcement_link, &Vec::new(), &Vec::new()).await?;
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+    let (packet_link, _) = author.send_tagged_packet(&keyload_link, &public_payload, &masked_payload).await?;
+
+    println!("report failures");
+    let report = subscriber.sync_state().await;
+    try_or!(report.msgs.len() == 1, ValueMismatch(1, report.msgs.len()))?;
+    ensure!(report.msgs[0].link == keyload_link, "keyload was not fetched");
+    try_or!(report.errors.len() == 1, ValueMismatch(1, report.errors.len()))?;
+    ensure!(report.errors[0].0 == packet_link, "failed packet was not reported");
+
+    let report = subscriber.fetch_next_msgs().await;
+    ensure!(report.msgs.is_empty(), "failed packet advanced sequencing state");
+    try_or!(report.errors.len() == 1, ValueMismatch(1, report.errors.len()))?;
+
+    Ok(())
+}
+
+#[cfg(not(feature = "async"))]
+pub fn file_example<T: Transport + Clone>(author_transport: T, subscriber_transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let mut author = Author::new("AUTHOR9FILE9SEED", encoding, PAYLOAD_BYTES, false, author_transport.clone());
+    let mut subscriber = Subscriber::new("SUBSCRIBER9FILE9SEED", encoding, PAYLOAD_BYTES, subscriber_transport.clone());
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+
+    println!("exchange messages through separate transports");
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+    let (signed_packet_link, _) = author.send_signed_packet(&announcement_link, &public_payload, &masked_payload)?;
+    let (_pk, unwrapped_public, unwrapped_masked) = subscriber.receive_signed_packet(&signed_packet_link)?;
+    try_or!(
+        public_payload == unwrapped_public,
+        PublicPayloadMismatch(public_payload.to_string(), unwrapped_public.to_string())
+    )?;
+    try_or!(
+        masked_payload == unwrapped_masked,
+        MaskedPayloadMismatch(masked_payload.to_string(), unwrapped_masked.to_string())
+    )?;
+
+    let subscribe_link = subscriber.send_subscribe(&announcement_link)?;
+    author.receive_subscribe(&subscribe_link)?;
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link)?;
+    try_or!(subscriber.receive_keyload(&keyload_link)?, MessageUnwrapFailure("subscriber".to_string()))?;
+
+    println!("read stored messages with a new user");
+    let mut reader = Subscriber::new("READER9FILE9SEED", encoding, PAYLOAD_BYTES, subscriber_transport.clone());
+    reader.receive_announcement(&announcement_link)?;
+    let (_pk, unwrapped_public, _) = reader.receive_signed_packet(&signed_packet_link)?;
+    try_or!(
+        public_payload == unwrapped_public,
+        PublicPayloadMismatch(public_payload.to_string(), unwrapped_public.to_string())
+    )?;
+
+    Ok(())
+}
+
+#[cfg(feature = "async")]
+pub async fn file_example<T: Transport + Clone>(author_transport: T, subscriber_transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let mut author = Author::new("AUTHOR9FILE9SEED", encoding, PAYLOAD_BYTES, false, author_transport.clone());
+    let mut subscriber = Subscriber::new("SUBSCRIBER9FILE9SEED", encoding, PAYLOAD_BYTES, subscriber_transport.clone());
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+
+    println!("exchange messages through separate transports");
+    let announcement_link = author.send_announce().await?;
+    subscriber.receive_announcement(&announcement_link).await?;
+    let (signed_packet_link, _) = author.send_signed_packet(&announcement_link, &public_payload, &masked_payload).await?;
+    let (_pk, unwrapped_public, unwrapped_masked) = subscriber.receive_signed_packet(&signed_packet_link).await?;
+    try_or!(
+        public_payload == unwrapped_public,
+        PublicPayloadMismatch(public_payload.to_string(), unwrapped_public.to_string())
+    )?;
+    try_or!(
+        masked_payload == unwrapped_masked,
+        MaskedPayloadMismatch(masked_payload.to_string(), unwrapped_masked.to_string())
+    )?;
+
+    let subscribe_link = subscriber.send_subscribe(&announcement_link).await?;
+    author.receive_subscribe(&subscribe_link).await?;
+    let (keyload_link, _) = author.send_keyload_for_everyone(&announcement_link).await?;
+    try_or!(subscriber.receive_keyload(&keyload_link).await?, MessageUnwrapFailure("subscriber".to_string()))?;
+
+    println!("read stored messages with a new user");
+    let mut reader = Subscriber::new("READER9FILE9SEED", encoding, PAYLOAD_BYTES, subscriber_transport.clone());
+    reader.receive_announcement(&announcement_link).await?;
+    let (_pk, unwrapped_public, _) = reader.receive_signed_packet(&signed_packet_link).await?;
+    try_or!(
+        public_payload == unwrapped_public,
+        PublicPayloadMismatch(public_payload.to_string(), unwrapped_public.to_string())
+    )?;
+
+    Ok(())
+}
+
+#[cfg(not(feature = "async"))]
+pub fn snapshot_example(transport: SharedTransport<BucketTransport>) -> Result<()> {
+    let encoding = "utf-8";
+    let mut author = Author::new("AUTHOR9SNAPSHOT9SEED", encoding, PAYLOAD_BYTES, false, transport.clone());
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+
+    println!("load bucket dump");
+    let announcement_link = author.send_announce()?;
+    let (packet_link, _) = author.send_signed_packet(&announcement_link, &public_payload, &masked_payload)?;
+    let dump = transport.borrow().dump()?;
+    let loaded = new_shared_transport(BucketTransport::load(&dump)?);
+    ensure!(loaded.borrow().dump()? == dump, "loaded bucket differs from the dumped one");
+
+    let mut subscriber = Subscriber::new("SUBSCRIBER9SNAPSHOT9SEED", encoding, PAYLOAD_BYTES, loaded.clone());
+    subscriber.receive_announcement(&announcement_link)?;
+    let (_pk, unwrapped_public, _) = subscriber.receive_signed_packet(&packet_link)?;
+    try_or!(
+        public_payload == unwrapped_public,
+        PublicPayloadMismatch(public_payload.to_string(), unwrapped_public.to_string())
+    )?;
+
+    println!("merge buckets");
+    let (packet_link, _) = author.send_signed_packet(&announcement_link, &public_payload, &masked_payload)?;
+    ensure!(
+        subscriber.receive_signed_packet(&packet_link).is_err(),
+        "packet was found before merging buckets"
+    );
+    let dump = transport.borrow().dump()?;
+    loaded.borrow_mut().merge(BucketTransport::load(&dump)?);
+    ensure!(loaded.borrow().dump()? == dump, "merged bucket contains duplicate messages");
+    subscriber.receive_signed_packet(&packet_link)?;
+
+    Ok(())
+}
+
+#[cfg(feature = "async")]
+pub async fn snapshot_example(transport: SharedTransport<BucketTransport>) -> Result<()> {
+    let encoding = "utf-8";
+    let mut author = Author::new("AUTHOR9SNAPSHOT9SEED", encoding, PAYLOAD_BYTES, false, transport.clone());
+
+    let public_payload = Bytes("PUBLICPAYLOAD".as_bytes().to_vec());
+    let masked_payload = Bytes("MASKEDPAYLOAD".as_bytes().to_vec());
+
+    println!("load bucket dump");
+    let announcement_link = author.send_announce().await?;
+    let (packet_link, _) = author.send_signed_packet(&announcement_link, &public_payload, &masked_payload).await?;
+    let dump = transport.lock().await.dump()?;
+    let loaded = new_shared_transport(BucketTransport::load(&dump)?);
+    ensure!(loaded.lock().await.dump()? == dump, "loaded bucket differs from the dumped one");
+
+    let mut subscriber = Subscriber::new("SUBSCRIBER9SNAPSHOT9SEED", encoding, PAYLOAD_BYTES, loaded.clone());
+    subscriber.receive_announcement(&announcement_link).await?;
+    let (_pk, unwrapped_public, _) = subscriber.receive_signed_packet(&packet_link).await?;
+    try_or!(
+        public_payload == unwrapped_public,
+        PublicPayloadMismatch(public_payload.to_string(), unwrapped_public.to_string())
+    )?;
+
+    println!("merge buckets");
+    let (packet_link, _) = author.send_signed_packet(&announcement_link, &public_payload, &masked_payload).await?;
+    ensure!(
+        subscriber.receive_signed_packet(&packet_link).await.is_err(),
+        "packet was found before merging buckets"
+    );
+    let dump = transport.lock().await.dump()?;
+    loaded.lock().await.merge(BucketTransport::load(&dump)?);
+    ensure!(loaded.lock().await.dump()? == dump, "merged bucket contains duplicate messages");
+    subscriber.receive_signed_packet(&packet_link).await?;
+
+    Ok(())
+}
+
+/// Retry policy retrying unpublished messages a few times.
+pub fn retry_options() -> RetryOptions {
+    RetryOptions {
+        max_attempts: 3,
+        initial_delay: core::time::Duration::from_millis(1),
+        multiplier: 2,
+        max_delay: core::time::Duration::from_millis(10),
+        retry_if: |err| match err.downcast_ref::<Errors>() {
+            Some(MessageLinkNotFound(_)) => true,
+            _ => false,
+        },
+    }
+}
+
+#[cfg(not(feature = "async"))]
+pub fn retry_example<T: Transport + Clone>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let mut author = Author::new("AUTHOR9RETRY9SEED", encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new("SUBSCRIBER9RETRY9SEED", encoding, PAYLOAD_BYTES, transport.clone());
+
+    println!("pass messages through");
+    let announcement_link = author.send_announce()?;
+    subscriber.receive_announcement(&announcement_link)?;
+
+    println!("give up retrying");
+    let missing_link = Address::from_str(&announcement_link.appinst.to_string(), "000000000000000000000000").unwrap();
+    match subscriber.receive_signed_packet(&missing_link) {
+        Ok(_) => err!(MessageUnwrapFailure("missing packet".to_string())),
+        Err(err) => match err.downcast_ref::<Errors>() {
+            Some(MessageLinkNotFound(_)) => Ok(()),
+            _ => Err(err),
+        },
+    }
+}
+
+#[cfg(feature = "async")]
+pub async fn retry_example<T: Transport + Clone>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let mut author = Author::new("AUTHOR9RETRY9SEED", encoding, PAYLOAD_BYTES, false, transport.clone());
+    let mut subscriber = Subscriber::new("SUBSCRIBER9RETRY9SEED", encoding, PAYLOAD_BYTES, transport.clone());
+
+    println!("pass messages through");
+    let announcement_link = author.send_announce().await?;
+    subscriber.receive_announcement(&announcement_link).await?;
+
+    println!("give up retrying");
+    let missing_link = Address::from_str(&announcement_link.appinst.to_string(), "000000000000000000000000").unwrap();
+    match subscriber.receive_signed_packet(&missing_link).await {
+        Ok(_) => err!(MessageUnwrapFailure("missing packet".to_string())),
+        Err(err) => match err.downcast_ref::<Errors>() {
+            Some(MessageLinkNotFound(_)) => Ok(()),
+            _ => Err(err),
+        },
+    }
+}
+
+#[cfg(not(feature = "async"))]
+pub fn multi_channel_example<T: Transport + Clone>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let mut author = MultiChannelAuthor::new("AUTHOR9MULTI9SEED", encoding, PAYLOAD_BYTES, false, transport.clone());
+    author.new_channel(1)?;
+    ensure!(author.new_channel(1).is_err(), "channel 1 created twice");
+
+    let mut subscriber = Subscriber::new("SUBSCRIBER9MULTI9SEED", encoding, PAYLOAD_BYTES, transport.clone());
+
+    println!("announce channels");
+    let announcement0_link = author.channel_mut(0).unwrap().send_announce()?;
+    let announcement1_link = author.channel_mut(1).unwrap().send_announce()?;
+    ensure!(announcement0_link.appinst != announcement1_link.appinst, "channels share application instance");
+    {
+        let (author0, author1) = (author.channel(0).unwrap(), author.channel(1).unwrap());
+        ensure!(author0.get_pk() == author1.get_pk(), "channels have different identities");
+    }
+
+    subscriber.receive_announcement(&announcement1_link)?;
+    ensure!(
+        subscriber.channel_address() == author.channel(1).unwrap().channel_address(),
+        "bad channel address"
+    );
+
+    println!("share keyload in channel 1");
+    author.channel_mut(1).unwrap().store_new_subscriber(subscriber.get_pk().clone())?;
+    let (keyload_link, _) = author.channel_mut(1).unwrap().send_keyload_for_everyone(&announcement1_link)?;
+    try_or!(subscriber.receive_keyload(&keyload_link)?, MessageUnwrapFailure("subscriber".to_string()))?;
+    ensure!(author.channel(0).unwrap().subscribers().is_empty(), "channels share subscribers");
+
+    let dump = author.export("pwdMultiAuthor")?;
+    let author2 = MultiChannelAuthor::import(dump.as_ref(), "pwdMultiAuthor", transport.clone())?;
+    ensure!(author2.channel_indices() == author.channel_indices(), "channels were not imported");
+    ensure!(
+        author2.channel(1).unwrap().channel_address() == author.channel(1).unwrap().channel_address(),
+        "channel 1 was not imported"
+    );
+
+    Ok(())
+}
+
+#[cfg(feature = "async")]
+pub async fn multi_channel_example<T: Transport + Clone>(transport: T) -> Result<()> {
+    let encoding = "utf-8";
+    let mut author = MultiChannelAuthor::new("AUTHOR9MULTI9SEED", encoding, PAYLOAD_BYTES, false, transport.clone());
+    author.new_channel(1)?;
+    ensure!(author.new_channel(1).is_err(), "channel 1 created twice");
+
+    let mut subscriber = Subscriber::new("SUBSCRIBER9MULTI9SEED", encoding, PAYLOAD_BYTES, transport.clone());
+
+    println!("announce channels");
+    let announcement0_link = author.channel_mut(0).unwrap().send_announce().await?;
+    let announcement1_link = author.channel_mut(1).unwrap().send_announce().await?;
+    ensure!(announcement0_link.appinst != announcement1_link.appinst, "channels share application instance");
+    {
+        let (author0, author1) = (author.channel(0).unwrap(), author.channel(1).unwrap());
+        ensure!(author0.get_pk() == author1.get_pk(), "channels have different identities");
+    }
+
+    subscriber.receive_announcement(&announcement1_link).await?;
+    ensure!(
+        subscriber.channel_address() == author.channel(1).unwrap().channel_address(),
+        "bad channel address"
+    );
+
+    println!("share keyload in channel 1");
+    author.channel_mut(1).unwrap().store_new_subscriber(subscriber.get_pk().clone())?;
+    let (keyload_link, _) = author.channel_mut(1).unwrap().send_keyload_for_everyone(&announcement1_link).await?;
+    try_or!(subscriber.receive_keyload(&keyload_link).await?, MessageUnwrapFailure("subscriber".to_string()))?;
+    ensure!(author.channel(0).unwrap().subscribers().is_empty(), "channels share subscribers");
+
+    let dump = author.export("pwdMultiAuthor")?;
+    let author2 = MultiChannelAuthor::import(dump.as_ref(), "pwdMultiAuthor", transport.clone())?;
+    ensure!(author2.channel_indices() == author.channel_indices(), "channels were not imported");
+    ensure!(
+        author2.channel(1).unwrap().channel_address() == author.channel(1).unwrap().channel_address(),
+        "channel 1 was not imported"
+    );
+
+    Ok(())
+}
+
+/// Bucket transport shared by the users of a scenario.
+#[cfg(test)]
+fn bucket_transport() -> SharedTransport<BucketTransport> {
+    new_shared_transport(BucketTransport::new())
+}
+
+/// Run a scenario to completion, async scenarios are driven by `smol`.
+#[cfg(all(test, not(feature = "async")))]
+fn run<R>(scenario: R) -> R {
+    scenario
+}
+
+/// Run a scenario to completion, async scenarios are driven by `smol`.
+#[cfg(all(test, feature = "async"))]
+fn run<S: core::future::Future>(scenario: S) -> S::Output {
+    smol::block_on(scenario)
+}
+
+#[test]
+fn run_basic_scenario() {
+    assert!(dbg!(run(example(bucket_transport()))).is_ok());
+}
+
+#[test]
+fn run_ratchet_scenario() {
+    assert!(dbg!(run(ratchet_example(bucket_transport()))).is_ok());
+}
+
+#[test]
+fn run_multi_channel_scenario() {
+    assert!(dbg!(run(multi_channel_example(bucket_transport()))).is_ok());
+}
+
+#[test]
+fn run_delegation_scenario() {
+    assert!(dbg!(run(delegation_example(bucket_transport()))).is_ok());
+}
+
+#[test]
+fn run_large_payload_scenario() {
+    assert!(dbg!(run(large_payload_example(bucket_transport()))).is_ok());
+}
+
+#[test]
+fn run_padding_scenario() {
+    assert!(dbg!(run(padding_example(bucket_transport()))).is_ok());
+}
+
+#[test]
+fn run_timestamp_scenario() {
+    assert!(dbg!(run(timestamp_example(bucket_transport()))).is_ok());
+}
+
+#[test]
+fn run_metadata_scenario() {
+    assert!(dbg!(run(metadata_example(bucket_transport()))).is_ok());
+}
+
+#[test]
+fn run_history_scenario() {
+    assert!(dbg!(run(history_example(bucket_transport()))).is_ok());
+}
+
+#[test]
+#[cfg(feature = "async")]
+fn run_stream_scenario() {
+    assert!(dbg!(run(stream_example(bucket_transport()))).is_ok());
+}
+
+#[test]
+#[cfg(not(feature = "async"))]
+fn run_iter_scenario() {
+    assert!(dbg!(run(iter_example(bucket_transport()))).is_ok());
+}
+
+#[test]
+fn run_report_scenario() {
+    assert!(dbg!(run(report_example(bucket_transport()))).is_ok());
+}
+
+#[test]
+fn run_file_scenario() {
+    let root = std::env::temp_dir().join(format!("streams-file-transport-{}", std::process::id()));
+    let result = crate::api::tangle::FileTransport::new(&root).and_then(|author_transport| {
+        let subscriber_transport = crate::api::tangle::FileTransport::new(&root)?;
+        run(file_example(author_transport, subscriber_transport))
+    });
+    let _ = std::fs::remove_dir_all(&root);
+    assert!(dbg!(result).is_ok());
+}
+
+#[test]
+fn run_snapshot_scenario() {
+    assert!(dbg!(run(snapshot_example(bucket_transport()))).is_ok());
+}
+
+/// Author state exported with the first layout version, after announcing the channel, accepting
+/// one subscriber and sending them a keyload. Password is "pwdLegacy".
+const LEGACY_AUTHOR_EXPORT: &str = "\
+    0000d06eae5ce53b56456d8f366c49e065e887c903b1b41f559c6aa40c4992a9906b0001057574662d38000000000000\
+    044201b930b01b02fe174bc689c2dd1a76147c1c4fc49392ad22f155c954f4030603b700000000000000002e80924f77\
+    08961b4312aa8401b930b01b02fe174bc689c2dd1a76147c1c4fc49392ad22f155c954f4030603b701032e80924f7708\
+    961b4312aa84d888d24b5afb032f97c50b613c814cde20164b24eaf5c177acabefc4b99433ec005f9d83335a9a4f1f98\
+    ef82860b32335bd4e314b0b597b99459cdfe51d43ab7b212be1d50ca03a181b353973304daf83e392dcdbcc38a0d90fa\
+    ce29c81781df6e06fd349cb79819b6898e10b883bf70d0e14a55ac8338147fe601000102b930b01b02fe174bc689c2dd\
+    1a76147c1c4fc49392ad22f155c954f4030603b72e80924f7708961b4312aa840000000000000003aed5f285af257a64\
+    007a3bdfc27c4978122c63a5a9b2cc6a54f7a010885c8d432e80924f7708961b4312aa840000000000000003afdb1cba\
+    f4af789fc6d891521e9ba50f546bfe95b84f5fecd429ceed3e7a08bc";
+
+#[test]
+fn import_legacy_export() {
+    let fresh = Author::new("AUTHOR9LEGACY9SEED", "utf-8", PAYLOAD_BYTES, false, bucket_transport());
+    let bytes = hex::decode(LEGACY_AUTHOR_EXPORT).unwrap();
+    let author = Author::import(&bytes, "pwdLegacy", bucket_transport()).unwrap();
+    assert_eq!(author.get_pk(), fresh.get_pk());
+    assert_eq!(
+        author.channel_address().map(|appinst| appinst.to_string()),
+        Some("b930b01b02fe174bc689c2dd1a76147c1c4fc49392ad22f155c954f4030603b70000000000000000".to_string())
+    );
+
+    // Once imported the state is exported with the current layout.
+    let bytes = author.export("pwdLegacy").unwrap();
+    let author = Author::import(&bytes, "pwdLegacy", bucket_transport()).unwrap();
+    assert_eq!(author.get_pk(), fresh.get_pk());
+}
+
+#[test]
+#[cfg(feature = "async")]
+fn run_shared_transport_scenario() {
+    assert!(dbg!(run(shared_transport_example(bucket_transport()))).is_ok());
+}
+
+#[test]
+#[cfg(feature = "async-send")]
+fn run_send_scenario() {
+    let transport = bucket_transport();
+    // Spawned futures must be `Send`, the executor runs them on its own threads.
+    let task = smol::spawn(example(transport.clone()));
+    assert!(dbg!(run(task)).is_ok());
+    let task = smol::spawn(shared_transport_example(transport.clone()));
+    assert!(dbg!(run(task)).is_ok());
+
+    let author = iota_streams_core::prelude::Arc::new(futures::lock::Mutex::new(
+        Author::new("AUTHOR9SEND9SEED", "utf-8", PAYLOAD_BYTES, false, transport)));
+    let task = {
+        let author = author.clone();
+        smol::spawn(async move { author.lock().await.send_announce().await })
+    };
+    assert!(dbg!(run(task)).is_ok());
+}
+
+#[test]
+fn run_retry_scenario() {
+    let transport = iota_streams_app::transport::RetryTransport::with_options(bucket_transport(), retry_options());
+    assert!(dbg!(run(retry_example(transport))).is_ok());
 }
@@ -1,17 +1,74 @@
 use iota_streams_app::message::{
+    frames,
+    BinaryMessage,
     HasLink as _,
     LinkGenerator,
 };
-use iota_streams_core::{prelude::Vec, prng, {err, Result, LOCATION_LOG}, Errors::{UserNotRegistered, UnknownMsgType}, panic_if_not};
+#[cfg(feature = "std")]
+use iota_streams_app::transport::tangle::timestamp_now;
+use iota_streams_core::{
+    prelude::{
+        string::ToString,
+        Vec,
+    },
+    prng,
+    {err, Error, Result, LOCATION_LOG},
+    Errors::{
+        self,
+        IndexNotFound,
+        MessageLinkNotFound,
+        MessageLinkNotFoundInTangle,
+        PreviousMessageNotFound,
+        UnknownMsgType,
+        UserNotRegistered,
+    },
+};
 
 use super::*;
 use crate::{
     api,
     message,
 };
+use iota_streams_core_edsig::signature::ed25519;
+
+#[cfg(feature = "async")]
+use core::time::Duration;
+#[cfg(feature = "async")]
+use futures::stream::{
+    self,
+    Stream,
+};
+use iota_streams_core::prelude::vec;
 
 type UserImp = api::user::User<DefaultF, Address, LinkGen, LinkStore, PkStore, PskStore>;
 
+/// Whether the transport failed to find a message because it has not been published yet.
+fn is_not_published(e: &Error) -> bool {
+    match e.downcast_ref::<Errors>() {
+        Some(MessageLinkNotFound(_)) | Some(MessageLinkNotFoundInTangle(_)) | Some(IndexNotFound) => true,
+        _ => false,
+    }
+}
+
+/// Cursor over the messages pending in the transport, shared by `messages` iterator and stream.
+struct PendingMsgs {
+    ids: vec::IntoIter<(PublicKey, Cursor<Address>)>,
+    /// Whether a message has been handled since `ids` were generated.
+    found: bool,
+    /// Links of failed messages, each failure is yielded once.
+    failed: Vec<Address>,
+}
+
+impl PendingMsgs {
+    fn new() -> Self {
+        Self {
+            ids: Vec::new().into_iter(),
+            found: true,
+            failed: Vec::new(),
+        }
+    }
+}
+
 /// Baseline User api object. Contains the api user implementation as well as the transport object
 pub struct User<Trans> {
     pub user: UserImp,
@@ -31,13 +88,19 @@ impl<Trans> User<Trans>
     ///
     pub fn new(seed: &str, encoding: &str, payload_length: usize, multi_branching: bool, transport: Trans) -> Self {
         let nonce = "TANGLEUSERNONCE".as_bytes().to_vec();
-        let user = UserImp::gen(
+        #[allow(unused_mut)]
+        let mut user = UserImp::gen(
             prng::from_seed("IOTA Streams Channels user sig keypair", seed),
             nonce,
             if multi_branching { 1 } else { 0 },
             encoding.as_bytes().to_vec(),
             payload_length,
         );
+        // There is no clock in `no_std` environment, packets are published without timestamp.
+        #[cfg(feature = "std")]
+        {
+            user.clock = Some(timestamp_now);
+        }
         Self { user, transport }
     }
 
@@ -53,6 +116,61 @@ impl<Trans> User<Trans>
         self.user.is_multi_branching()
     }
 
+    /// Return boolean representing whether packets in the channel are ratcheted
+    pub fn is_ratcheted(&self) -> bool {
+        self.user.is_ratcheted()
+    }
+
+    /// Enable forward-secret ratchet for packets in the channel [Author]. Spongos state of the message
+    /// packets are linked to is advanced in a one-way fashion as many times as the packet sequence
+    /// number, the state of the message itself is left intact. Must be set
+    /// before the channel is announced.
+    ///
+    ///   # Arguments
+    ///   * `ratchet` - Boolean representing whether packets should be ratcheted
+    ///
+    pub fn set_ratchet(&mut self, ratchet: bool) -> Result<()> {
+        self.user.set_ratchet(ratchet)
+    }
+
+    /// Return boolean representing whether masked payloads in the channel are padded
+    pub fn is_padded(&self) -> bool {
+        self.user.is_padded()
+    }
+
+    /// Enable padding of masked payloads in the channel [Author]. Masked payload of each packet is padded
+    /// so that the packet fills a whole number of `payload_length` sized frames, the padding is
+    /// removed on unwrap. Participants should use the same `payload_length`. Must be set before
+    /// the channel is announced.
+    ///
+    ///   # Arguments
+    ///   * `padding` - Boolean representing whether masked payloads should be padded
+    ///
+    pub fn set_padding(&mut self, padding: bool) -> Result<()> {
+        self.user.set_padding(padding)
+    }
+
+    /// Include authenticated timestamp into signed and tagged packets published by the user.
+    /// Timestamp is taken from the local clock in milliseconds since Unix epoch; there is no clock
+    /// in `no_std` environment and packets are published without timestamp.
+    ///
+    ///   # Arguments
+    ///   * `timestamping` - Boolean representing whether packets should be timestamped
+    ///
+    pub fn set_timestamping(&mut self, timestamping: bool) {
+        self.user.set_timestamping(timestamping)
+    }
+
+    /// Set the maximum allowed difference between the timestamp of a received packet and the
+    /// local clock. Packets outside of the window or without a timestamp are rejected.
+    ///
+    ///   # Arguments
+    ///   * `clock_skew` - Window size in milliseconds, `None` accepts packets regardless of timestamp
+    ///
+    pub fn set_clock_skew(&mut self, clock_skew: Option<u64>) {
+        self.user.set_clock_skew(clock_skew)
+    }
+
     /// Fetch the user ed25519 public key
     pub fn get_pk(&self) -> &PublicKey {
         &self.user.sig_kp.public
@@ -106,11 +224,111 @@ impl<Trans> User<Trans>
         self.user.gen_next_msg_ids(branching)
     }
 
+    /// Fetch the ed25519 public keys of the channel subscribers
+    /// [Author]
+    pub fn subscribers(&self) -> Vec<&PublicKey> {
+        self.user.subscribers()
+    }
+
+    /// Store a subscriber public key enrolled out-of-band
+    /// [Author]
+    ///
+    ///   # Arguments
+    ///   * `pk` - ed25519 Public Key of the subscriber
+    ///
+    pub fn store_new_subscriber(&mut self, pk: PublicKey) -> Result<()> {
+        self.user.store_new_subscriber(pk)
+    }
+
+    /// Remove a subscriber public key from the channel
+    /// [Author]
+    ///
+    ///   # Arguments
+    ///   * `pk` - ed25519 Public Key of the subscriber
+    ///
+    pub fn remove_subscriber(&mut self, pk: &PublicKey) -> Result<()> {
+        self.user.remove_subscriber(pk)
+    }
+
+    /// Store a pre-shared key
+    /// [Author, Subscriber]
+    ///
+    ///   # Arguments
+    ///   * `pskid` - Identifier of the pre-shared key
+    ///   * `psk` - Pre-shared key
+    ///
+    pub fn store_psk(&mut self, pskid: PskId, psk: Psk) {
+        self.user.store_psk(pskid, psk)
+    }
+
+    /// Remove a pre-shared key
+    /// [Author, Subscriber]
+    ///
+    ///   # Arguments
+    ///   * `pskid` - Identifier of the pre-shared key
+    ///
+    pub fn remove_psk(&mut self, pskid: &PskId) -> Result<()> {
+        self.user.remove_psk(pskid)
+    }
+
+    /// Fetch the identifiers of the stored pre-shared keys
+    /// [Author, Subscriber]
+    pub fn psk_ids(&self) -> PskIds {
+        self.user.psk_ids()
+    }
+
+    /// Fetch publishing rights of a participant, None if the participant is not known
+    /// [Author, Subscriber]
+    ///
+    ///   # Arguments
+    ///   * `pk` - ed25519 Public Key of the participant
+    ///
+    pub fn permission(&self, pk: &PublicKey) -> Option<&Permission> {
+        self.user.permission(pk)
+    }
+
+    /// Check whether a participant has writer role for packets linked to the given message
+    /// [Author, Subscriber]
+    ///
+    ///   # Arguments
+    ///   * `pk` - ed25519 Public Key of the participant
+    ///   * `link_to` - Address of the message packets are linked to
+    ///
+    pub fn is_writer(&self, pk: &PublicKey, link_to: &Address) -> bool {
+        self.user.is_writer(pk, &link_to.msgid)
+    }
+
+    /// Derive a new ed25519 key pair from seed the same way as it's done for a new User
+    fn gen_sig_kp(seed: &str) -> ed25519::Keypair {
+        let nonce = "TANGLEUSERNONCE".as_bytes().to_vec();
+        let prng = prng::from_seed::<DefaultF>("IOTA Streams Channels user sig keypair", seed);
+        ed25519::Keypair::generate(&mut prng::Rng::new(prng, nonce))
+    }
+
+    /// Create a new User with the same identity for the channel `channel_idx` [Author].
+    ///
+    /// # Arguments
+    /// * `channel_idx` - Index of the channel application instance
+    /// * `transport` - Transport object used for sending and receiving
+    ///
+    pub fn new_channel(&self, channel_idx: u64, transport: Trans) -> Result<Self> {
+        Ok(Self {
+            user: self.user.new_channel(channel_idx)?,
+            transport,
+        })
+    }
+
     pub fn export(&self, flag: u8, pwd: &str) -> Result<Vec<u8>> {
         self.user.export(flag, pwd)
     }
     pub fn import(bytes: &[u8], flag: u8, pwd: &str, tsp: Trans) -> Result<Self> {
-        UserImp::import(bytes, flag, pwd).map(|u| Self { user: u, transport: tsp, })
+        #[allow(unused_mut)]
+        let mut user = UserImp::import(bytes, flag, pwd)?;
+        #[cfg(feature = "std")]
+        {
+            user.clock = Some(timestamp_now);
+        }
+        Ok(Self { user, transport: tsp })
     }
 }
 
@@ -119,6 +337,33 @@ impl<Trans: Transport> User<Trans>
 {
     // Send
 
+    /// Send a message, splitting it into payload frames if it doesn't fit into a single one.
+    fn send_frames(&mut self, msg: BinaryMessage<DefaultF, Address>) -> Result<()> {
+        for frame in self.user.split_frames(msg)? {
+            self.transport.send_message(&Message::new(frame))?;
+        }
+        Ok(())
+    }
+
+    /// Receive a message, fetching and joining the rest of its payload frames if any.
+    fn recv_message(&mut self, link: &Address) -> Result<Message> {
+        let mut msg = self.transport.recv_message(link)?;
+        if let Some(key) = self.user.frames_key(&msg.binary)? {
+            let count = msg.binary.parse_header()?.header.get_payload_frame_count();
+            let mut spongos = msg.binary.frames_spongos(&key);
+            let mut prev = link.clone();
+            for num in 2..=count {
+                let frame_link = frames::frame_link(&self.user.link_gen, &prev, num);
+                // Anyone can publish at the frame link, the frame is picked by its MAC.
+                let candidates = self.transport.recv_messages(&frame_link)?;
+                let candidates = candidates.iter().map(|frame| &frame.binary);
+                msg.binary.join_frame(&mut spongos, &prev, candidates, num, count)?;
+                prev = frame_link;
+            }
+        }
+        Ok(msg)
+    }
+
     /// Send a message with sequencing logic. If channel is single-branched, then no secondary
     /// sequence message is sent and None is returned for the address.
     ///
@@ -139,7 +384,7 @@ impl<Trans: Transport> User<Trans>
 
     /// Send a message without using sequencing logic. Reserved for Announce and Subscribe messages
     fn send_message(&mut self, msg: WrappedMessage, info: MsgInfo) -> Result<Address> {
-        self.transport.send_message(&Message::new(msg.message))?;
+        self.send_frames(msg.message)?;
         self.user.commit_wrapped(msg.wrapped, info)
     }
 
@@ -157,7 +402,7 @@ impl<Trans: Transport> User<Trans>
         info: MsgInfo,
     ) -> Result<(Address, Option<Address>)> {
         let seq = self.user.wrap_sequence(ref_link)?;
-        self.transport.send_message(&Message::new(msg.message))?;
+        self.send_frames(msg.message)?;
         let seq_link = self.send_sequence(seq)?;
         let msg_link = self.user.commit_wrapped(msg.wrapped, info)?;
         Ok((msg_link, seq_link))
@@ -169,6 +414,19 @@ impl<Trans: Transport> User<Trans>
         self.send_message(msg, MsgInfo::Announce)
     }
 
+    /// Send a change key message, replacing own signature key pair with a new one derived from seed [Author].
+    ///
+    ///  # Arguments
+    ///  * `seed` - A string slice representing the seed of the new key pair
+    ///
+    pub fn send_change_key(&mut self, seed: &str) -> Result<Address> {
+        let new_sig_kp = Self::gen_sig_kp(seed);
+        let msg = self.user.change_key(&new_sig_kp.public)?;
+        let link = self.send_message(msg, MsgInfo::ChangeKey)?;
+        self.user.set_sig_kp(new_sig_kp)?;
+        Ok(link)
+    }
+
     /// Create and send a signed packet [Author, Subscriber].
     ///
     ///  # Arguments
@@ -220,6 +478,23 @@ impl<Trans: Transport> User<Trans>
         self.send_message_sequenced(msg, link_to.rel(), MsgInfo::Keyload)
     }
 
+    /// Create and send a new keyload for a list of subscribers with blinded recipient identifiers [Author].
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload will be attached to
+    ///  * `psk_ids` - Vector of Pre-shared key ids to be included in message
+    ///  * `ke_pks`  - Vector of Public Keys to be included in message
+    ///
+    pub fn send_anonymous_keyload(
+        &mut self,
+        link_to: &Address,
+        psk_ids: &PskIds,
+        ke_pks: &Vec<PublicKey>,
+    ) -> Result<(Address, Option<Address>)> {
+        let msg = self.user.share_anonymous_keyload(&link_to.msgid, psk_ids, ke_pks)?;
+        self.send_message_sequenced(msg, link_to.rel(), MsgInfo::Keyload)
+    }
+
     /// Create and send keyload for all subscribed subscribers [Author].
     ///
     ///  # Arguments
@@ -230,6 +505,28 @@ impl<Trans: Transport> User<Trans>
         self.send_message_sequenced(msg, link_to.rel(), MsgInfo::Keyload)
     }
 
+    /// Create and send a permission message granting or revoking write permission of a
+    /// participant [Author].
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the permission will be attached to
+    ///  * `pk` - ed25519 Public Key of the participant
+    ///  * `write` - Boolean representing whether write permission is granted or revoked
+    ///  * `branch` - Optional address of the message packets must be linked to, all branches if None
+    ///
+    pub fn send_permission(
+        &mut self,
+        link_to: &Address,
+        pk: &PublicKey,
+        write: bool,
+        branch: Option<&Address>,
+    ) -> Result<(Address, Option<Address>)> {
+        let msg = self.user.share_permission(&link_to.msgid, pk, write, branch.map(|b| &b.msgid))?;
+        let links = self.send_message_sequenced(msg, link_to.rel(), MsgInfo::Permission)?;
+        self.user.store_permission(pk.clone(), write, branch.map(|b| b.msgid.clone()))?;
+        Ok(links)
+    }
+
     /// Create and Send a Subscribe message to a Channel app instance [Subscriber].
     ///
     /// # Arguments
@@ -240,6 +537,16 @@ impl<Trans: Transport> User<Trans>
         self.send_message(msg, MsgInfo::Subscribe)
     }
 
+    /// Create and Send an Unsubscribe message to a Channel app instance [Subscriber].
+    ///
+    /// # Arguments
+    /// * `link_to` - Address of the Subscribe message previously sent by the user
+    ///
+    pub fn send_unsubscribe(&mut self, link_to: &Address) -> Result<Address> {
+        let msg = self.user.unsubscribe(&link_to.msgid)?;
+        self.send_message(msg, MsgInfo::Unsubscribe)
+    }
+
     // Receive
 
     /// Receive and process a sequence message [Author, Subscriber].
@@ -248,7 +555,7 @@ impl<Trans: Transport> User<Trans>
     ///  * `link` - Address of the message to be processed
     ///
     pub fn receive_sequence(&mut self, link: &Address) -> Result<Address> {
-        let msg = self.transport.recv_message(link)?;
+        let msg = self.recv_message(link)?;
         if let Some(_addr) = &self.user.appinst {
             let seq_link = msg.binary.link.clone();
             let seq_msg = self.user.handle_sequence(msg.binary, MsgInfo::Sequence)?.body;
@@ -275,10 +582,11 @@ impl<Trans: Transport> User<Trans>
     ///  * `link` - Address of the message to be processed
     ///
     pub fn receive_signed_packet(&mut self, link: &Address) -> Result<(PublicKey, Bytes, Bytes)> {
-        let msg = self.transport.recv_message(link)?;
+        let msg = self.recv_message(link)?;
         // TODO: msg.timestamp is lost
         let m = self.user.handle_signed_packet(msg.binary, MsgInfo::SignedPacket)?;
-        Ok(m.body)
+        let (_link_to, pk, public, masked, _timestamp) = m.body;
+        Ok((pk, public, masked))
     }
 
     /// Receive and process a tagged packet message [Author, Subscriber].
@@ -287,9 +595,10 @@ impl<Trans: Transport> User<Trans>
     ///  * `link` - Address of the message to be processed
     ///
     pub fn receive_tagged_packet(&mut self, link: &Address) -> Result<(Bytes, Bytes)> {
-        let msg = self.transport.recv_message(link)?;
+        let msg = self.recv_message(link)?;
         let m = self.user.handle_tagged_packet(msg.binary, MsgInfo::TaggedPacket)?;
-        Ok(m.body)
+        let (_link_to, _sender, public, masked, _timestamp) = m.body;
+        Ok((public, masked))
     }
 
     /// Receive and process a subscribe message [Author].
@@ -298,18 +607,54 @@ impl<Trans: Transport> User<Trans>
     ///  * `link` - Address of the message to be processed
     ///
     pub fn receive_subscribe(&mut self, link: &Address) -> Result<()> {
-        let msg = self.transport.recv_message(link)?;
+        let msg = self.recv_message(link)?;
         // TODO: Timestamp is lost.
         self.user.handle_subscribe(msg.binary, MsgInfo::Subscribe)
     }
 
+    /// Receive and process an unsubscribe message [Author].
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///
+    pub fn receive_unsubscribe(&mut self, link: &Address) -> Result<()> {
+        let msg = self.recv_message(link)?;
+        self.user.handle_unsubscribe(msg.binary, MsgInfo::Unsubscribe)?;
+        Ok(())
+    }
+
+    /// Receive and process a change key message, returns the new Author's public key [Subscriber].
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///
+    pub fn receive_change_key(&mut self, link: &Address) -> Result<PublicKey> {
+        let msg = self.recv_message(link)?;
+        let m = self.user.handle_change_key(msg.binary, MsgInfo::ChangeKey)?;
+        let (_link_to, new_pk) = m.body;
+        Ok(new_pk)
+    }
+
+    /// Receive and process a permission message, returns the participant's public key and whether
+    /// write permission has been granted [Subscriber].
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///
+    pub fn receive_permission(&mut self, link: &Address) -> Result<(PublicKey, bool)> {
+        let msg = self.recv_message(link)?;
+        let m = self.user.handle_permission(msg.binary, MsgInfo::Permission)?;
+        let (_link_to, pk, write) = m.body;
+        Ok((pk, write))
+    }
+
     /// Receive and Process an announcement message [Subscriber].
     ///
     /// # Arguments
     /// * `link_to` - Address of the Channel Announcement message
     ///
     pub fn receive_announcement(&mut self, link: &Address) -> Result<()> {
-        let msg = self.transport.recv_message(link)?;
+        let msg = self.recv_message(link)?;
         self.user.handle_announcement(msg.binary, MsgInfo::Announce)
     }
 
@@ -319,9 +664,10 @@ impl<Trans: Transport> User<Trans>
     ///  * `link` - Address of the message to be processed
     ///
     pub fn receive_keyload(&mut self, link: &Address) -> Result<bool> {
-        let msg = self.transport.recv_message(link)?;
+        let msg = self.recv_message(link)?;
         let m = self.user.handle_keyload(msg.binary, MsgInfo::Keyload)?;
-        Ok(m.body)
+        let (_link_to, allowed) = m.body;
+        Ok(allowed)
     }
 
     /// Receive and process a message of unknown type. Message will be handled appropriately and
@@ -331,15 +677,17 @@ impl<Trans: Transport> User<Trans>
     ///   * `link` - Address of the message to be processed
     ///
     pub fn receive_message(&mut self, link: &Address) -> Result<UnwrappedMessage> {
-        let msg = self.transport.recv_message(link)?;
+        let msg = self.recv_message(link)?;
         self.handle_message(msg)
     }
 
 
-    /// Retrieves the next message for each user (if present in transport layer) and returns them [Author, Subscriber]
-    pub fn fetch_next_msgs(&mut self) -> Vec<UnwrappedMessage> {
-        let ids = self.user.gen_next_msg_ids(self.user.is_multi_branching());
-        let mut msgs = Vec::new();
+    /// Retrieves the next message for each user (if present in transport layer) and returns them
+    /// together with the failures to fetch or handle published messages [Author, Subscriber]
+    pub fn fetch_next_msgs(&mut self) -> FetchReport {
+        let multi_branching = self.user.is_multi_branching();
+        let ids = self.user.gen_next_msg_ids(multi_branching);
+        let mut report = FetchReport::default();
 
         for (
             _pk,
@@ -350,21 +698,150 @@ impl<Trans: Transport> User<Trans>
             },
         ) in ids
         {
-            let msg = self.transport.recv_message(&link);
-
-            if msg.is_ok() {
-                let msg = self.handle_message(msg.unwrap());
-                if let Ok(msg) = msg {
-                    if !self.user.is_multi_branching() {
-                        let stored = self.user.store_state_for_all(link.msgid, seq_no);
-                        panic_if_not!(stored.is_ok())
-                    }
+            let msg = match self.recv_message(&link) {
+                Ok(msg) => msg,
+                Err(e) if is_not_published(&e) => continue,
+                Err(e) => {
+                    report.errors.push((link, e));
+                    continue;
+                },
+            };
+
+            let unwrapped = match self.handle_message(msg) {
+                Ok(msg) if !multi_branching => self
+                    .user
+                    .store_state_for_all(link.msgid.clone(), seq_no)
+                    .map(|_| msg),
+                r => r,
+            };
+            match unwrapped {
+                Ok(msg) => report.msgs.push(msg),
+                Err(e) => report.errors.push((link, e)),
+            }
+        }
+        report
+    }
 
-                    msgs.push(msg);
+    /// Iterator over messages pending in the transport [Author, Subscriber]. Messages are fetched
+    /// and handled lazily one by one, sequencing state is advanced as they are yielded.
+    pub fn messages(&mut self) -> MessagesIter<'_, Trans> {
+        MessagesIter {
+            user: self,
+            pending: PendingMsgs::new(),
+        }
+    }
+
+    /// Fetch and handle the next message pending in the transport, `None` if no new message has
+    /// been published since all the pending ones have been handled.
+    fn next_pending_msg(&mut self, pending: &mut PendingMsgs) -> Option<Result<UnwrappedMessage>> {
+        let multi_branching = self.is_multi_branching();
+        loop {
+            let (_pk, cursor) = match pending.ids.next() {
+                Some(id) => id,
+                None => {
+                    // Sequencing state has not changed, no more messages can be found
+                    if !pending.found {
+                        return None;
+                    }
+                    pending.found = false;
+                    pending.ids = self.user.gen_next_msg_ids(multi_branching).into_iter();
+                    continue;
+                },
+            };
+            if pending.failed.contains(&cursor.link) {
+                continue;
+            }
+            let msg = match self.recv_message(&cursor.link) {
+                Ok(msg) => msg,
+                Err(e) if is_not_published(&e) => continue,
+                Err(e) => {
+                    pending.failed.push(cursor.link);
+                    return Some(Err(e));
+                },
+            };
+            let unwrapped = self.handle_message(msg).and_then(|msg| {
+                if !multi_branching {
+                    self.user.store_state_for_all(cursor.link.msgid.clone(), cursor.seq_no)?;
                 }
+                Ok(msg)
+            });
+            match unwrapped {
+                Ok(msg) => {
+                    pending.found = true;
+                    if !multi_branching {
+                        // Ids generated for the other publishers are stale now
+                        pending.ids = Vec::new().into_iter();
+                    }
+                    return Some(Ok(msg));
+                },
+                Err(e) => {
+                    pending.failed.push(cursor.link);
+                    return Some(Err(e));
+                },
+            }
+        }
+    }
+
+    /// Retrieves all messages published in the channel from the current sequence state up to the
+    /// latest one, ie. fetches next messages until there are none left [Author, Subscriber]
+    pub fn sync_state(&mut self) -> FetchReport {
+        let mut report = FetchReport::default();
+        loop {
+            let next = self.fetch_next_msgs();
+            // A message failing on every pass is reported once
+            for (link, e) in next.errors {
+                report.push_error(link, e);
+            }
+            // Failed messages don't advance sequencing state
+            if next.msgs.is_empty() {
+                break;
             }
+            report.msgs.extend(next.msgs);
         }
-        msgs
+        report
+    }
+
+    /// Retrieves the message the message at `link` has been linked to [Author, Subscriber].
+    /// Both messages must have already been handled, own state is not updated.
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the message whose predecessor is fetched
+    ///
+    pub fn fetch_prev_msg(&mut self, link: &Address) -> Result<UnwrappedMessage> {
+        let msg = self.recv_message(link)?;
+        match self.process_message(msg, false)?.link_to {
+            Some(prev_link) => {
+                let msg = self.recv_message(&prev_link)?;
+                self.process_message(msg, false)
+            },
+            None => err!(PreviousMessageNotFound(link.to_string())),
+        }
+    }
+
+    /// Retrieves up to `max` messages preceding the message at `link` following `link_to` chain
+    /// towards the channel announcement, the closest message comes first [Author, Subscriber].
+    /// Own state is not updated, packets of a ratcheted channel can't be retrieved.
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the message to start from
+    ///   * `max` - Maximum number of messages to retrieve
+    ///
+    pub fn fetch_prev_msgs(&mut self, link: &Address, max: usize) -> Result<Vec<UnwrappedMessage>> {
+        let msg = self.recv_message(link)?;
+        let mut link_to = self.process_message(msg, false)?.link_to;
+        let mut msgs = Vec::new();
+        while msgs.len() < max {
+            match link_to {
+                Some(prev_link) => {
+                    let msg = self.recv_message(&prev_link)?;
+                    let unwrapped = self.process_message(msg, false)?;
+                    link_to = unwrapped.link_to.clone();
+                    msgs.push(unwrapped);
+                },
+                None => break,
+            }
+        }
+        Ok(msgs)
     }
 
     /// Handle message of unknown type. Ingests a message and unwraps it according to its determined
@@ -372,56 +849,176 @@ impl<Trans: Transport> User<Trans>
     ///
     /// # Arguments
     /// * `msg` - Binary message of unknown type
-    /// * `pk` - Optional ed25519 Public Key of the sending participant. None if unknown
     ///
-    pub fn handle_message(&mut self, mut msg0: Message) -> Result<UnwrappedMessage> {
+    pub fn handle_message(&mut self, msg: Message) -> Result<UnwrappedMessage> {
+        self.process_message(msg, true)
+    }
+
+    /// Unwrap message of unknown type, own state is updated only if `store` is set.
+    fn process_message(&mut self, mut msg0: Message, store: bool) -> Result<UnwrappedMessage> {
         loop {
-            // Forget TangleMessage and timestamp
+            let timestamp = msg0.timestamp;
             let msg = msg0.binary;
             let preparsed = msg.parse_header()?;
-            match preparsed.header.content_type {
+            let content_type = preparsed.header.content_type;
+            let seq_num = preparsed.header.get_seq_num();
+            // Keyloads and Permissions are signed by the Author, ChangeKey by the current Author's key
+            let author_pk = self.user.author_sig_pk.clone();
+            let (link, link_to, pk, body) = match content_type {
+                message::ANNOUNCE => {
+                    let link = msg.link.clone();
+                    let pk = if store {
+                        self.user.handle_announcement(msg, MsgInfo::Announce)?;
+                        self.user.author_sig_pk.clone()
+                    } else {
+                        Some(self.user.unwrap_announcement(preparsed)?.pcf.content.sig_pk)
+                    };
+                    (link, None, pk, MessageContent::new_announce())
+                },
                 message::SIGNED_PACKET => {
-                    let m = self.user.handle_signed_packet(msg, MsgInfo::SignedPacket)?;
-                    let u = m.map(|(pk, public, masked)| MessageContent::new_signed_packet(pk, public, masked));
-                    return Ok(u)
+                    let m = if store {
+                        self.user.handle_signed_packet(msg, MsgInfo::SignedPacket)?
+                    } else {
+                        self.user.read_signed_packet(msg)?
+                    };
+                    let (link_to, pk, public, masked, packet_timestamp) = m.body;
+                    let body = MessageContent::new_signed_packet(pk.clone(), public, masked, packet_timestamp);
+                    (m.link, Some(link_to), Some(pk), body)
                 },
                 message::TAGGED_PACKET => {
-                    let m = self.user.handle_tagged_packet(msg, MsgInfo::TaggedPacket)?;
-                    let u = m.map(|(public, masked)| MessageContent::new_tagged_packet(public, masked));
-                    return Ok(u)
+                    let m = if store {
+                        self.user.handle_tagged_packet(msg, MsgInfo::TaggedPacket)?
+                    } else {
+                        self.user.read_tagged_packet(msg)?
+                    };
+                    let (link_to, sender, public, masked, packet_timestamp) = m.body;
+                    let body = MessageContent::new_tagged_packet(public, masked, packet_timestamp);
+                    (m.link, Some(link_to), Some(sender), body)
                 },
                 message::KEYLOAD => {
                     // So long as the unwrap has not failed, we will return a blank object to
                     // inform the user that a message was present, even if the use wasn't part of
                     // the keyload itself. This is to prevent sequencing failures
-                    let m = self.user.handle_keyload(msg, MsgInfo::Keyload)?;
+                    let m = if store {
+                        self.user.handle_keyload(msg, MsgInfo::Keyload)?
+                    } else {
+                        self.user.read_keyload(msg)?
+                    };
                     // TODO: Verify content, whether user is allowed or not!
-                    let u = m.map(|_allowed| MessageContent::new_keyload());
-                    return Ok(u)
+                    let (link_to, _allowed) = m.body;
+                    (m.link, Some(link_to), author_pk, MessageContent::new_keyload())
                 },
                 message::SEQUENCE => {
                     let store_link = msg.link.rel().clone();
-                    let unwrapped = self.user.handle_sequence(msg, MsgInfo::Sequence)?;
+                    let unwrapped = if store {
+                        self.user.handle_sequence(msg, MsgInfo::Sequence)?
+                    } else {
+                        self.user.read_sequence(msg)?
+                    };
                     let msg_link = self.user.link_gen.link_from(
                         &unwrapped.body.pk,
                         Cursor::new_at(&unwrapped.body.ref_link, 0, unwrapped.body.seq_num.0 as u32),
                     );
-                    let msg = self.transport.recv_message(&msg_link)?;
-                    self.user.store_state(unwrapped.body.pk.clone(), store_link)?;
+                    let msg = self.recv_message(&msg_link)?;
+                    if store {
+                        self.user.store_state(unwrapped.body.pk.clone(), store_link)?;
+                    }
                     msg0 = msg;
+                    continue
+                },
+                message::UNSUBSCRIBE => {
+                    let m = if store {
+                        self.user.handle_unsubscribe(msg, MsgInfo::Unsubscribe)?
+                    } else {
+                        self.user.read_unsubscribe(msg)?
+                    };
+                    let (link_to, pk) = m.body;
+                    (m.link, Some(link_to), Some(pk), MessageContent::new_unsubscribe())
+                },
+                message::CHANGE_KEY => {
+                    let m = if store {
+                        self.user.handle_change_key(msg, MsgInfo::ChangeKey)?
+                    } else {
+                        self.user.read_change_key(msg)?
+                    };
+                    let (link_to, new_pk) = m.body;
+                    (m.link, Some(link_to), author_pk, MessageContent::new_change_key(new_pk))
+                },
+                message::PERMISSION => {
+                    let m = if store {
+                        self.user.handle_permission(msg, MsgInfo::Permission)?
+                    } else {
+                        self.user.read_permission(msg)?
+                    };
+                    let (link_to, pk, write) = m.body;
+                    (m.link, Some(link_to), author_pk, MessageContent::new_permission(pk, write))
                 },
                 unknown_content => return err!(UnknownMsgType(unknown_content)),
-            }
+            };
+            let link_to = link_to.map(|link_to| Address::new(link.appinst.clone(), link_to));
+            return Ok(UnwrappedMessage {
+                link,
+                body,
+                content_type,
+                seq_num,
+                pk,
+                link_to,
+                timestamp,
+            })
         }
     }
 
 }
 
+/// Lazy iterator over messages pending in the transport, see `User::messages`. Each message
+/// which has been found but failed to be handled is yielded as an error once.
+#[cfg(not(feature = "async"))]
+pub struct MessagesIter<'a, Trans> {
+    user: &'a mut User<Trans>,
+    pending: PendingMsgs,
+}
+
+#[cfg(not(feature = "async"))]
+impl<'a, Trans: Transport> Iterator for MessagesIter<'a, Trans> {
+    type Item = Result<UnwrappedMessage>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.user.next_pending_msg(&mut self.pending)
+    }
+}
+
 #[cfg(feature = "async")]
 impl<Trans: Transport> User<Trans>
 {
     // Send
 
+    /// Send a message, splitting it into payload frames if it doesn't fit into a single one.
+    async fn send_frames(&mut self, msg: BinaryMessage<DefaultF, Address>) -> Result<()> {
+        for frame in self.user.split_frames(msg)? {
+            self.transport.send_message(&Message::new(frame)).await?;
+        }
+        Ok(())
+    }
+
+    /// Receive a message, fetching and joining the rest of its payload frames if any.
+    async fn recv_message(&mut self, link: &Address) -> Result<Message> {
+        let mut msg = self.transport.recv_message(link).await?;
+        if let Some(key) = self.user.frames_key(&msg.binary)? {
+            let count = msg.binary.parse_header()?.header.get_payload_frame_count();
+            let mut spongos = msg.binary.frames_spongos(&key);
+            let mut prev = link.clone();
+            for num in 2..=count {
+                let frame_link = frames::frame_link(&self.user.link_gen, &prev, num);
+                // Anyone can publish at the frame link, the frame is picked by its MAC.
+                let candidates = self.transport.recv_messages(&frame_link).await?;
+                let candidates = candidates.iter().map(|frame| &frame.binary);
+                msg.binary.join_frame(&mut spongos, &prev, candidates, num, count)?;
+                prev = frame_link;
+            }
+        }
+        Ok(msg)
+    }
+
     /// Send a message with sequencing logic. If channel is single-branched, then no secondary
     /// sequence message is sent and None is returned for the address.
     ///
@@ -442,7 +1039,7 @@ impl<Trans: Transport> User<Trans>
 
     /// Send a message without using sequencing logic. Reserved for Announce and Subscribe messages
     async fn send_message(&mut self, msg: WrappedMessage, info: MsgInfo) -> Result<Address> {
-        self.transport.send_message(&Message::new(msg.message)).await?;
+        self.send_frames(msg.message).await?;
         self.user.commit_wrapped(msg.wrapped, info)
     }
 
@@ -460,7 +1057,7 @@ impl<Trans: Transport> User<Trans>
         info: MsgInfo,
     ) -> Result<(Address, Option<Address>)> {
         let seq = self.user.wrap_sequence(ref_link)?;
-        self.transport.send_message(&Message::new(msg.message)).await?;
+        self.send_frames(msg.message).await?;
         let seq_link = self.send_sequence(seq).await?;
         let msg_link = self.user.commit_wrapped(msg.wrapped, info)?;
         Ok((msg_link, seq_link))
@@ -472,6 +1069,19 @@ impl<Trans: Transport> User<Trans>
         self.send_message(msg, MsgInfo::Announce).await
     }
 
+    /// Send a change key message, replacing own signature key pair with a new one derived from seed [Author].
+    ///
+    ///  # Arguments
+    ///  * `seed` - A string slice representing the seed of the new key pair
+    ///
+    pub async fn send_change_key(&mut self, seed: &str) -> Result<Address> {
+        let new_sig_kp = Self::gen_sig_kp(seed);
+        let msg = self.user.change_key(&new_sig_kp.public)?;
+        let link = self.send_message(msg, MsgInfo::ChangeKey).await?;
+        self.user.set_sig_kp(new_sig_kp)?;
+        Ok(link)
+    }
+
     /// Create and send a signed packet [Author, Subscriber].
     ///
     ///  # Arguments
@@ -523,6 +1133,23 @@ impl<Trans: Transport> User<Trans>
         self.send_message_sequenced(msg, link_to.rel(), MsgInfo::Keyload).await
     }
 
+    /// Create and send a new keyload for a list of subscribers with blinded recipient identifiers [Author].
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the keyload will be attached to
+    ///  * `psk_ids` - Vector of Pre-shared key ids to be included in message
+    ///  * `ke_pks`  - Vector of Public Keys to be included in message
+    ///
+    pub async fn send_anonymous_keyload(
+        &mut self,
+        link_to: &Address,
+        psk_ids: &PskIds,
+        ke_pks: &Vec<PublicKey>,
+    ) -> Result<(Address, Option<Address>)> {
+        let msg = self.user.share_anonymous_keyload(&link_to.msgid, psk_ids, ke_pks)?;
+        self.send_message_sequenced(msg, link_to.rel(), MsgInfo::Keyload).await
+    }
+
     /// Create and send keyload for all subscribed subscribers [Author].
     ///
     ///  # Arguments
@@ -533,6 +1160,28 @@ impl<Trans: Transport> User<Trans>
         self.send_message_sequenced(msg, link_to.rel(), MsgInfo::Keyload).await
     }
 
+    /// Create and send a permission message granting or revoking write permission of a
+    /// participant [Author].
+    ///
+    ///  # Arguments
+    ///  * `link_to` - Address of the message the permission will be attached to
+    ///  * `pk` - ed25519 Public Key of the participant
+    ///  * `write` - Boolean representing whether write permission is granted or revoked
+    ///  * `branch` - Optional address of the message packets must be linked to, all branches if None
+    ///
+    pub async fn send_permission(
+        &mut self,
+        link_to: &Address,
+        pk: &PublicKey,
+        write: bool,
+        branch: Option<&Address>,
+    ) -> Result<(Address, Option<Address>)> {
+        let msg = self.user.share_permission(&link_to.msgid, pk, write, branch.map(|b| &b.msgid))?;
+        let links = self.send_message_sequenced(msg, link_to.rel(), MsgInfo::Permission).await?;
+        self.user.store_permission(pk.clone(), write, branch.map(|b| b.msgid.clone()))?;
+        Ok(links)
+    }
+
     /// Create and Send a Subscribe message to a Channel app instance [Subscriber].
     ///
     /// # Arguments
@@ -543,6 +1192,16 @@ impl<Trans: Transport> User<Trans>
         self.send_message(msg, MsgInfo::Subscribe).await
     }
 
+    /// Create and Send an Unsubscribe message to a Channel app instance [Subscriber].
+    ///
+    /// # Arguments
+    /// * `link_to` - Address of the Subscribe message previously sent by the user
+    ///
+    pub async fn send_unsubscribe(&mut self, link_to: &Address) -> Result<Address> {
+        let msg = self.user.unsubscribe(&link_to.msgid)?;
+        self.send_message(msg, MsgInfo::Unsubscribe).await
+    }
+
     // Receive
 
     /// Receive and process a sequence message [Author, Subscriber].
@@ -551,7 +1210,7 @@ impl<Trans: Transport> User<Trans>
     ///  * `link` - Address of the message to be processed
     ///
     pub async fn receive_sequence(&mut self, link: &Address) -> Result<Address> {
-        let msg = self.transport.recv_message(link).await?;
+        let msg = self.recv_message(link).await?;
         if let Some(_addr) = &self.user.appinst {
             let seq_link = msg.binary.link.clone();
             let seq_msg = self.user.handle_sequence(msg.binary, MsgInfo::Sequence)?.body;
@@ -578,10 +1237,11 @@ impl<Trans: Transport> User<Trans>
     ///  * `link` - Address of the message to be processed
     ///
     pub async fn receive_signed_packet(&mut self, link: &Address) -> Result<(PublicKey, Bytes, Bytes)> {
-        let msg = self.transport.recv_message(link).await?;
+        let msg = self.recv_message(link).await?;
         // TODO: msg.timestamp is lost
         let m = self.user.handle_signed_packet(msg.binary, MsgInfo::SignedPacket)?;
-        Ok(m.body)
+        let (_link_to, pk, public, masked, _timestamp) = m.body;
+        Ok((pk, public, masked))
     }
 
     /// Receive and process a tagged packet message [Author, Subscriber].
@@ -590,9 +1250,10 @@ impl<Trans: Transport> User<Trans>
     ///  * `link` - Address of the message to be processed
     ///
     pub async fn receive_tagged_packet(&mut self, link: &Address) -> Result<(Bytes, Bytes)> {
-        let msg = self.transport.recv_message(link).await?;
+        let msg = self.recv_message(link).await?;
         let m = self.user.handle_tagged_packet(msg.binary, MsgInfo::TaggedPacket)?;
-        Ok(m.body)
+        let (_link_to, _sender, public, masked, _timestamp) = m.body;
+        Ok((public, masked))
     }
 
 
@@ -602,18 +1263,54 @@ impl<Trans: Transport> User<Trans>
     ///  * `link` - Address of the message to be processed
     ///
     pub async fn receive_subscribe(&mut self, link: &Address) -> Result<()> {
-        let msg = self.transport.recv_message(link).await?;
+        let msg = self.recv_message(link).await?;
         // TODO: Timestamp is lost.
         self.user.handle_subscribe(msg.binary, MsgInfo::Subscribe)
     }
 
+    /// Receive and process an unsubscribe message [Author].
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///
+    pub async fn receive_unsubscribe(&mut self, link: &Address) -> Result<()> {
+        let msg = self.recv_message(link).await?;
+        self.user.handle_unsubscribe(msg.binary, MsgInfo::Unsubscribe)?;
+        Ok(())
+    }
+
+    /// Receive and process a change key message, returns the new Author's public key [Subscriber].
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///
+    pub async fn receive_change_key(&mut self, link: &Address) -> Result<PublicKey> {
+        let msg = self.recv_message(link).await?;
+        let m = self.user.handle_change_key(msg.binary, MsgInfo::ChangeKey)?;
+        let (_link_to, new_pk) = m.body;
+        Ok(new_pk)
+    }
+
+    /// Receive and process a permission message, returns the participant's public key and whether
+    /// write permission has been granted [Subscriber].
+    ///
+    ///  # Arguments
+    ///  * `link` - Address of the message to be processed
+    ///
+    pub async fn receive_permission(&mut self, link: &Address) -> Result<(PublicKey, bool)> {
+        let msg = self.recv_message(link).await?;
+        let m = self.user.handle_permission(msg.binary, MsgInfo::Permission)?;
+        let (_link_to, pk, write) = m.body;
+        Ok((pk, write))
+    }
+
     /// Receive and Process an announcement message [Subscriber].
     ///
     /// # Arguments
     /// * `link_to` - Address of the Channel Announcement message
     ///
     pub async fn receive_announcement(&mut self, link: &Address) -> Result<()> {
-        let msg = self.transport.recv_message(link).await?;
+        let msg = self.recv_message(link).await?;
         self.user.handle_announcement(msg.binary, MsgInfo::Announce)
     }
 
@@ -623,9 +1320,10 @@ impl<Trans: Transport> User<Trans>
     ///  * `link` - Address of the message to be processed
     ///
     pub async fn receive_keyload(&mut self, link: &Address) -> Result<bool> {
-        let msg = self.transport.recv_message(link).await?;
+        let msg = self.recv_message(link).await?;
         let m = self.user.handle_keyload(msg.binary, MsgInfo::Keyload)?;
-        Ok(m.body)
+        let (_link_to, allowed) = m.body;
+        Ok(allowed)
     }
 
     /// Receive and process a message of unknown type. Message will be handled appropriately and
@@ -636,15 +1334,17 @@ impl<Trans: Transport> User<Trans>
     ///   * `pk` - Optional ed25519 Public Key of the sending participant. None if unknown
     ///
     pub async fn receive_message(&mut self, link: &Address) -> Result<UnwrappedMessage> {
-        let msg = self.transport.recv_message(link).await?;
+        let msg = self.recv_message(link).await?;
         self.handle_message(msg).await
     }
 
 
-    /// Retrieves the next message for each user (if present in transport layer) and returns them [Author, Subscriber]
-    pub async fn fetch_next_msgs(&mut self) -> Vec<UnwrappedMessage> {
-        let ids = self.user.gen_next_msg_ids(self.user.is_multi_branching());
-        let mut msgs = Vec::new();
+    /// Retrieves the next message for each user (if present in transport layer) and returns them
+    /// together with the failures to fetch or handle published messages [Author, Subscriber]
+    pub async fn fetch_next_msgs(&mut self) -> FetchReport {
+        let multi_branching = self.user.is_multi_branching();
+        let ids = self.user.gen_next_msg_ids(multi_branching);
+        let mut report = FetchReport::default();
 
         for (
             _pk,
@@ -655,20 +1355,167 @@ impl<Trans: Transport> User<Trans>
             },
         ) in ids
         {
-            let msg = self.transport.recv_message(&link).await;
+            let msg = match self.recv_message(&link).await {
+                Ok(msg) => msg,
+                Err(e) if is_not_published(&e) => continue,
+                Err(e) => {
+                    report.errors.push((link, e));
+                    continue;
+                },
+            };
+
+            let unwrapped = match self.handle_message(msg).await {
+                Ok(msg) if !multi_branching => self
+                    .user
+                    .store_state_for_all(link.msgid.clone(), seq_no)
+                    .map(|_| msg),
+                r => r,
+            };
+            match unwrapped {
+                Ok(msg) => report.msgs.push(msg),
+                Err(e) => report.errors.push((link, e)),
+            }
+        }
+        report
+    }
+
+    /// Stream of incoming messages [Author, Subscriber]. Messages are fetched and handled lazily
+    /// one by one as the stream is polled, the transport is polled every `interval` while there are
+    /// no new messages. Sequencing state is advanced only for the yielded messages, failure to
+    /// fetch or handle a published message is yielded as an error once. The stream never ends on
+    /// its own, dropping it stops polling and releases the user.
+    ///
+    ///   # Arguments
+    ///   * `interval` - Delay between polls of the transport when there are no new messages
+    ///
+    pub fn messages<'a>(&'a mut self, interval: Duration) -> impl Stream<Item = Result<UnwrappedMessage>> + 'a {
+        stream::unfold((self, PendingMsgs::new()), move |(user, mut pending)| async move {
+            loop {
+                if let Some(msg) = user.next_pending_msg(&mut pending).await {
+                    return Some((msg, (user, pending)));
+                }
+                if wasm_timer::Delay::new(interval).await.is_err() {
+                    // Timer is not available anymore
+                    return None;
+                }
+                // Look for the messages published in the meantime
+                pending.found = true;
+            }
+        })
+    }
 
-            if let Ok(msg) = msg {
-                if let Ok(msg) = self.handle_message(msg).await {
-                    if !self.user.is_multi_branching() {
-                        let stored = self.user.store_state_for_all(link.msgid, seq_no);
-                        panic_if_not!(stored.is_ok())
+    /// Fetch and handle the next message pending in the transport, `None` if no new message has
+    /// been published since all the pending ones have been handled.
+    async fn next_pending_msg(&mut self, pending: &mut PendingMsgs) -> Option<Result<UnwrappedMessage>> {
+        let multi_branching = self.is_multi_branching();
+        loop {
+            let (_pk, cursor) = match pending.ids.next() {
+                Some(id) => id,
+                None => {
+                    // Sequencing state has not changed, no more messages can be found
+                    if !pending.found {
+                        return None;
+                    }
+                    pending.found = false;
+                    pending.ids = self.user.gen_next_msg_ids(multi_branching).into_iter();
+                    continue;
+                },
+            };
+            if pending.failed.contains(&cursor.link) {
+                continue;
+            }
+            let msg = match self.recv_message(&cursor.link).await {
+                Ok(msg) => msg,
+                Err(e) if is_not_published(&e) => continue,
+                Err(e) => {
+                    pending.failed.push(cursor.link);
+                    return Some(Err(e));
+                },
+            };
+            let unwrapped = match self.handle_message(msg).await {
+                Ok(msg) if !multi_branching => self
+                    .user
+                    .store_state_for_all(cursor.link.msgid.clone(), cursor.seq_no)
+                    .map(|_| msg),
+                r => r,
+            };
+            match unwrapped {
+                Ok(msg) => {
+                    pending.found = true;
+                    if !multi_branching {
+                        // Ids generated for the other publishers are stale now
+                        pending.ids = Vec::new().into_iter();
                     }
+                    return Some(Ok(msg));
+                },
+                Err(e) => {
+                    pending.failed.push(cursor.link);
+                    return Some(Err(e));
+                },
+            }
+        }
+    }
 
-                    msgs.push(msg);
-                }
+    /// Retrieves all messages published in the channel from the current sequence state up to the
+    /// latest one, ie. fetches next messages until there are none left [Author, Subscriber]
+    pub async fn sync_state(&mut self) -> FetchReport {
+        let mut report = FetchReport::default();
+        loop {
+            let next = self.fetch_next_msgs().await;
+            // A message failing on every pass is reported once
+            for (link, e) in next.errors {
+                report.push_error(link, e);
+            }
+            // Failed messages don't advance sequencing state
+            if next.msgs.is_empty() {
+                break;
+            }
+            report.msgs.extend(next.msgs);
+        }
+        report
+    }
+
+    /// Retrieves the message the message at `link` has been linked to [Author, Subscriber].
+    /// Both messages must have already been handled, own state is not updated.
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the message whose predecessor is fetched
+    ///
+    pub async fn fetch_prev_msg(&mut self, link: &Address) -> Result<UnwrappedMessage> {
+        let msg = self.recv_message(link).await?;
+        match self.process_message(msg, false).await?.link_to {
+            Some(prev_link) => {
+                let msg = self.recv_message(&prev_link).await?;
+                self.process_message(msg, false).await
+            },
+            None => err!(PreviousMessageNotFound(link.to_string())),
+        }
+    }
+
+    /// Retrieves up to `max` messages preceding the message at `link` following `link_to` chain
+    /// towards the channel announcement, the closest message comes first [Author, Subscriber].
+    /// Own state is not updated, packets of a ratcheted channel can't be retrieved.
+    ///
+    ///   # Arguments
+    ///   * `link` - Address of the message to start from
+    ///   * `max` - Maximum number of messages to retrieve
+    ///
+    pub async fn fetch_prev_msgs(&mut self, link: &Address, max: usize) -> Result<Vec<UnwrappedMessage>> {
+        let msg = self.recv_message(link).await?;
+        let mut link_to = self.process_message(msg, false).await?.link_to;
+        let mut msgs = Vec::new();
+        while msgs.len() < max {
+            match link_to {
+                Some(prev_link) => {
+                    let msg = self.recv_message(&prev_link).await?;
+                    let unwrapped = self.process_message(msg, false).await?;
+                    link_to = unwrapped.link_to.clone();
+                    msgs.push(unwrapped);
+                },
+                None => break,
             }
         }
-        msgs
+        Ok(msgs)
     }
 
     /// Handle message of unknown type. Ingests a message and unwraps it according to its determined
@@ -677,44 +1524,121 @@ impl<Trans: Transport> User<Trans>
     /// # Arguments
     /// * `msg` - Binary message of unknown type
     ///
-    pub async fn handle_message(&mut self, mut msg0: Message) -> Result<UnwrappedMessage> {
+    pub async fn handle_message(&mut self, msg: Message) -> Result<UnwrappedMessage> {
+        self.process_message(msg, true).await
+    }
+
+    /// Unwrap message of unknown type, own state is updated only if `store` is set.
+    async fn process_message(&mut self, mut msg0: Message, store: bool) -> Result<UnwrappedMessage> {
         loop {
-            // Forget TangleMessage and timestamp
+            let timestamp = msg0.timestamp;
             let msg = msg0.binary;
             let preparsed = msg.parse_header()?;
-            match preparsed.header.content_type {
+            let content_type = preparsed.header.content_type;
+            let seq_num = preparsed.header.get_seq_num();
+            // Keyloads and Permissions are signed by the Author, ChangeKey by the current Author's key
+            let author_pk = self.user.author_sig_pk.clone();
+            let (link, link_to, pk, body) = match content_type {
+                message::ANNOUNCE => {
+                    let link = msg.link.clone();
+                    let pk = if store {
+                        self.user.handle_announcement(msg, MsgInfo::Announce)?;
+                        self.user.author_sig_pk.clone()
+                    } else {
+                        Some(self.user.unwrap_announcement(preparsed)?.pcf.content.sig_pk)
+                    };
+                    (link, None, pk, MessageContent::new_announce())
+                },
                 message::SIGNED_PACKET => {
-                    let m = self.user.handle_signed_packet(msg, MsgInfo::SignedPacket)?;
-                    let u = m.map(|(pk, public, masked)| MessageContent::new_signed_packet(pk, public, masked));
-                    return Ok(u)
+                    let m = if store {
+                        self.user.handle_signed_packet(msg, MsgInfo::SignedPacket)?
+                    } else {
+                        self.user.read_signed_packet(msg)?
+                    };
+                    let (link_to, pk, public, masked, packet_timestamp) = m.body;
+                    let body = MessageContent::new_signed_packet(pk.clone(), public, masked, packet_timestamp);
+                    (m.link, Some(link_to), Some(pk), body)
                 },
                 message::TAGGED_PACKET => {
-                    let m = self.user.handle_tagged_packet(msg, MsgInfo::TaggedPacket)?;
-                    let u = m.map(|(public, masked)| MessageContent::new_tagged_packet(public, masked));
-                    return Ok(u)
+                    let m = if store {
+                        self.user.handle_tagged_packet(msg, MsgInfo::TaggedPacket)?
+                    } else {
+                        self.user.read_tagged_packet(msg)?
+                    };
+                    let (link_to, sender, public, masked, packet_timestamp) = m.body;
+                    let body = MessageContent::new_tagged_packet(public, masked, packet_timestamp);
+                    (m.link, Some(link_to), Some(sender), body)
                 },
                 message::KEYLOAD => {
                     // So long as the unwrap has not failed, we will return a blank object to
                     // inform the user that a message was present, even if the use wasn't part of
                     // the keyload itself. This is to prevent sequencing failures
-                    let m = self.user.handle_keyload(msg, MsgInfo::Keyload)?;
+                    let m = if store {
+                        self.user.handle_keyload(msg, MsgInfo::Keyload)?
+                    } else {
+                        self.user.read_keyload(msg)?
+                    };
                     // TODO: Verify content, whether user is allowed or not!
-                    let u = m.map(|_allowed| MessageContent::new_keyload());
-                    return Ok(u)
+                    let (link_to, _allowed) = m.body;
+                    (m.link, Some(link_to), author_pk, MessageContent::new_keyload())
                 },
                 message::SEQUENCE => {
                     let store_link = msg.link.rel().clone();
-                    let unwrapped = self.user.handle_sequence(msg, MsgInfo::Sequence)?;
+                    let unwrapped = if store {
+                        self.user.handle_sequence(msg, MsgInfo::Sequence)?
+                    } else {
+                        self.user.read_sequence(msg)?
+                    };
                     let msg_link = self.user.link_gen.link_from(
                         &unwrapped.body.pk,
                         Cursor::new_at(&unwrapped.body.ref_link, 0, unwrapped.body.seq_num.0 as u32),
                     );
-                    let msg = self.transport.recv_message(&msg_link).await?;
-                    self.user.store_state(unwrapped.body.pk.clone(), store_link)?;
+                    let msg = self.recv_message(&msg_link).await?;
+                    if store {
+                        self.user.store_state(unwrapped.body.pk.clone(), store_link)?;
+                    }
                     msg0 = msg;
+                    continue
+                },
+                message::UNSUBSCRIBE => {
+                    let m = if store {
+                        self.user.handle_unsubscribe(msg, MsgInfo::Unsubscribe)?
+                    } else {
+                        self.user.read_unsubscribe(msg)?
+                    };
+                    let (link_to, pk) = m.body;
+                    (m.link, Some(link_to), Some(pk), MessageContent::new_unsubscribe())
+                },
+                message::CHANGE_KEY => {
+                    let m = if store {
+                        self.user.handle_change_key(msg, MsgInfo::ChangeKey)?
+                    } else {
+                        self.user.read_change_key(msg)?
+                    };
+                    let (link_to, new_pk) = m.body;
+                    (m.link, Some(link_to), author_pk, MessageContent::new_change_key(new_pk))
+                },
+                message::PERMISSION => {
+                    let m = if store {
+                        self.user.handle_permission(msg, MsgInfo::Permission)?
+                    } else {
+                        self.user.read_permission(msg)?
+                    };
+                    let (link_to, pk, write) = m.body;
+                    (m.link, Some(link_to), author_pk, MessageContent::new_permission(pk, write))
                 },
                 unknown_content => return err!(UnknownMsgType(unknown_content)),
-            }
+            };
+            let link_to = link_to.map(|link_to| Address::new(link.appinst.clone(), link_to));
+            return Ok(UnwrappedMessage {
+                link,
+                body,
+                content_type,
+                seq_num,
+                pk,
+                link_to,
+                timestamp,
+            })
         }
     }
 
@@ -13,7 +13,10 @@ use iota_streams_core::{
     },
     prng,
     psk,
-    sponge::prp::{Inner, PRP},
+    sponge::{
+        prp::{Inner, PRP},
+        spongos,
+    },
     try_or,
     err,
     Errors::*,
@@ -27,6 +30,8 @@ use iota_streams_core_edsig::{
 use iota_streams_app::message::{
     hdf::{
         FLAG_BRANCHING_MASK,
+        FLAG_PADDING_MASK,
+        FLAG_RATCHET_MASK,
         HDF,
     },
     *,
@@ -38,6 +43,7 @@ use iota_streams_ddml::{
     link_store::{
         EmptyLinkStore,
         LinkStore,
+        RatchetLinkStore,
     },
     types::*,
 };
@@ -54,6 +60,10 @@ const ANN_MESSAGE_NUM: u32 = 0;
 const SUB_MESSAGE_NUM: u32 = 0;
 const SEQ_MESSAGE_NUM: u32 = 1;
 
+/// Version of the user state layout written by `User::export`. States exported with earlier
+/// versions can still be imported, the fields added since then get their default values.
+const EXPORT_VERSION: u8 = 4;
+
 /// Wrapped sequencing information with optional WrapState
 pub struct WrapStateSequence<F, Link: HasLink>(
     pub(crate) Cursor<<Link as HasLink>::Rel>,
@@ -108,6 +118,25 @@ impl<F, Link: HasLink> WrappedSequence<F, Link> {
     }
 }
 
+/// Link of the message a packet is linked to, content of signed and tagged packets starts with it.
+#[derive(Default)]
+struct PacketLink<Rel>(Rel);
+
+impl<F, Rel, Store> ContentUnwrap<F, Store> for PacketLink<Rel>
+where
+    F: PRP,
+    Rel: SkipFallback<F>,
+{
+    fn unwrap<'c, IS: io::IStream>(
+        &mut self,
+        _store: &Store,
+        ctx: &'c mut unwrap::Context<F, IS>,
+    ) -> Result<&'c mut unwrap::Context<F, IS>> {
+        self.0.unwrap_skip(ctx)?;
+        Ok(ctx)
+    }
+}
+
 pub struct User<F, Link, LG, LS, PKS, PSKS>
 where
     F: PRP,
@@ -126,7 +155,8 @@ where
     /// User' pre-shared keys.
     pub(crate) psk_store: PSKS,
 
-    /// Users' trusted public keys together with additional sequencing info: (msgid, seq_no).
+    /// Users' trusted public keys together with additional sequencing info: (msgid, seq_no),
+    /// and publishing rights.
     pub(crate) pk_store: PKS,
 
     /// Author's Ed25519 public key.
@@ -148,6 +178,28 @@ where
     pub message_encoding: Vec<u8>,
 
     pub uniform_payload_length: usize,
+
+    /// Clock used to timestamp packets and to check timestamps of received packets,
+    /// returns milliseconds since Unix epoch.
+    pub clock: Option<fn() -> u64>,
+
+    /// Include timestamp into published packets.
+    pub timestamping: bool,
+
+    /// Maximum allowed difference in milliseconds between timestamp of a received packet and
+    /// the clock. Packets outside of the window or without timestamp are rejected.
+    pub clock_skew: Option<u64>,
+
+    /// Latest keyload sent by the user.
+    pub(crate) latest_keyload: Option<<Link as HasLink>::Rel>,
+
+    /// Keyloads superseded by a revocation keyload, packets linked to a superseded keyload
+    /// are linked to the revocation keyload instead.
+    pub(crate) superseded_keyloads: Vec<(<Link as HasLink>::Rel, <Link as HasLink>::Rel)>,
+
+    /// Ratchet positions of the messages packets have been linked to in a ratcheted channel,
+    /// the spongos states of these messages in the link store are advanced to the positions.
+    pub(crate) ratchet_positions: Vec<(<Link as HasLink>::Rel, u64)>,
 }
 
 impl<F, Link, LG, LS, PKS, PSKS> Default for User<F, Link, LG, LS, PKS, PSKS>
@@ -156,7 +208,7 @@ where
     Link: HasLink,
     LG: LinkGenerator<Link>,
     LS: LinkStore<F, <Link as HasLink>::Rel> + Default,
-    PKS: PublicKeyStore<Cursor<<Link as HasLink>::Rel>>,
+    PKS: PublicKeyStore<PkInfo<<Link as HasLink>::Rel>>,
     PSKS: PresharedKeyStore,
 {
     fn default() -> Self {
@@ -180,6 +232,12 @@ where
             flags: 0,
             message_encoding: Vec::new(),
             uniform_payload_length: 0,
+            clock: None,
+            timestamping: false,
+            clock_skew: None,
+            latest_keyload: None,
+            superseded_keyloads: Vec::new(),
+            ratchet_positions: Vec::new(),
         }
     }
 }
@@ -192,7 +250,7 @@ where
     <Link as HasLink>::Rel: Eq + fmt::Debug + SkipFallback<F> + AbsorbFallback<F>,
     LG: LinkGenerator<Link>,
     LS: LinkStore<F, <Link as HasLink>::Rel> + Default,
-    PKS: PublicKeyStore<Cursor<<Link as HasLink>::Rel>>,
+    PKS: PublicKeyStore<PkInfo<<Link as HasLink>::Rel>>,
     PSKS: PresharedKeyStore,
 {
     /// Create a new User and generate Ed25519 key pair and corresponding X25519 key pair.
@@ -233,6 +291,12 @@ where
             flags,
             message_encoding,
             uniform_payload_length,
+            clock: None,
+            timestamping: false,
+            clock_skew: None,
+            latest_keyload: None,
+            superseded_keyloads: Vec::new(),
+            ratchet_positions: Vec::new(),
         }
     }
 
@@ -246,13 +310,48 @@ where
         
         self.pk_store.insert(
             self.sig_kp.public.clone(),
-            Cursor::new_at(appinst.rel().clone(), 0, 2_u32),
+            PkInfo::new(Cursor::new_at(appinst.rel().clone(), 0, 2_u32)),
         )?;
         self.author_sig_pk = Some(self.sig_kp.public);
         self.appinst = Some(appinst);
         Ok(())
     }
 
+    /// Create a new User with the same identity and settings and create channel `channel_idx` for it.
+    /// The new User has its own link generator, link store, sequencing state and subscribers.
+    pub fn new_channel(&self, channel_idx: u64) -> Result<Self> {
+        // Secret key bytes are taken from a valid key pair.
+        let sig_kp = ed25519::Keypair {
+            secret: ed25519::SecretKey::from_bytes(self.sig_kp.secret.as_bytes()).unwrap(),
+            public: self.sig_kp.public,
+        };
+        let ke_kp = x25519::keypair_from_ed25519(&sig_kp);
+
+        let mut user = Self {
+            _phantom: core::marker::PhantomData,
+            sig_kp,
+            ke_kp,
+
+            psk_store: PSKS::default(),
+            pk_store: PKS::default(),
+            author_sig_pk: None,
+            link_gen: LG::default(),
+            link_store: RefCell::new(LS::default()),
+            appinst: None,
+            flags: self.flags,
+            message_encoding: self.message_encoding.clone(),
+            uniform_payload_length: self.uniform_payload_length,
+            clock: self.clock,
+            timestamping: self.timestamping,
+            clock_skew: self.clock_skew,
+            latest_keyload: None,
+            superseded_keyloads: Vec::new(),
+            ratchet_positions: Vec::new(),
+        };
+        user.create_channel(channel_idx)?;
+        Ok(user)
+    }
+
     /// Save spongos and info associated to the message link
     pub fn commit_wrapped(
         &mut self,
@@ -320,8 +419,8 @@ where
         // At the moment the Author is free to choose any address, not tied to PK.
 
         let cursor = Cursor::new_at(link.rel().clone(), 0, 2_u32);
-        self.pk_store.insert(content.sig_pk.clone(), cursor.clone())?;
-        self.pk_store.insert(self.sig_kp.public.clone(), cursor)?;
+        self.pk_store.insert(content.sig_pk.clone(), PkInfo::new(cursor.clone()))?;
+        self.pk_store.insert(self.sig_kp.public.clone(), PkInfo::new(cursor))?;
         // Reset link_gen
         self.link_gen.reset(link.clone());
         self.appinst = Some(link);
@@ -391,17 +490,411 @@ where
         let subscriber_sig_pk = content.subscriber_sig_pk;
         let ref_link = self.appinst.as_ref().unwrap().rel().clone();
         self.pk_store
-            .insert(subscriber_sig_pk, Cursor::new_at(ref_link, 0, SEQ_MESSAGE_NUM))?;
+            .insert(subscriber_sig_pk, PkInfo::new(Cursor::new_at(ref_link, 0, SEQ_MESSAGE_NUM)))?;
         // Unwrapped unsubscribe_key is not used explicitly.
         Ok(())
     }
 
+    /// Prepare Unsubscribe message.
+    pub fn prepare_unsubscribe<'a>(
+        &'a mut self,
+        link_to: &'a <Link as HasLink>::Rel,
+    ) -> Result<PreparedMessage<'a, F, Link, LS, unsubscribe::ContentWrap<'a, F, Link>>> {
+        try_or!(self.appinst.is_some(), UserNotRegistered)?;
+        let msg_link = self
+            .link_gen
+            .link_from(&self.sig_kp.public, Cursor::new_at(link_to, 0, SUB_MESSAGE_NUM));
+        let header = HDF::new(msg_link)
+            .with_content_type(UNSUBSCRIBE)?
+            .with_payload_length(1)?
+            .with_seq_num(SUB_MESSAGE_NUM);
+        let content = unsubscribe::ContentWrap {
+            link: link_to,
+            subscriber_sig_kp: &self.sig_kp,
+            _phantom: core::marker::PhantomData,
+        };
+        Ok(PreparedMessage::new(self.link_store.borrow(), header, content))
+    }
+
+    /// Unsubscribe from the channel, `link_to` is the link to own Subscribe message.
+    pub fn unsubscribe(&mut self, link_to: &<Link as HasLink>::Rel) -> Result<WrappedMessage<F, Link>> {
+        self.prepare_unsubscribe(link_to)?.wrap()
+    }
+
+    pub fn unwrap_unsubscribe<'a>(
+        &self,
+        preparsed: PreparsedMessage<'a, F, Link>,
+    ) -> Result<UnwrappedMessage<F, Link, unsubscribe::ContentUnwrap<F, Link>>> {
+        self.ensure_appinst(&preparsed)?;
+        let content = unsubscribe::ContentUnwrap::default();
+        preparsed.unwrap(&*self.link_store.borrow(), content)
+    }
+
+    /// Verify subscriber's signature and remove subscriber's public key from the channel.
+    pub fn handle_unsubscribe<'a>(
+        &mut self,
+        msg: BinaryMessage<F, Link>,
+        info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
+    ) -> Result<GenericMessage<Link, (<Link as HasLink>::Rel, ed25519::PublicKey)>> {
+        let preparsed = msg.parse_header()?;
+        let content = self
+            .unwrap_unsubscribe(preparsed)?
+            .commit(self.link_store.borrow_mut(), info)?;
+        // Subscribe spongos state is not needed anymore.
+        self.link_store.borrow_mut().erase(&content.link);
+        self.remove_subscriber(&content.subscriber_sig_pk)?;
+        Ok(GenericMessage::new(msg.link, (content.link, content.subscriber_sig_pk)))
+    }
+
+    /// Verify subscriber's signature without updating the channel.
+    pub fn read_unsubscribe(
+        &self,
+        msg: BinaryMessage<F, Link>,
+    ) -> Result<GenericMessage<Link, (<Link as HasLink>::Rel, ed25519::PublicKey)>> {
+        let preparsed = msg.parse_header()?;
+        let content = self.unwrap_unsubscribe(preparsed)?.pcf.content;
+        Ok(GenericMessage::new(msg.link, (content.link, content.subscriber_sig_pk)))
+    }
+
+    /// Public keys of the channel subscribers, own public key is not included.
+    pub fn subscribers(&self) -> Vec<&ed25519::PublicKey> {
+        self.pk_store
+            .iter()
+            .into_iter()
+            .filter_map(|(pk, _info)| if *pk != self.sig_kp.public { Some(pk) } else { None })
+            .collect()
+    }
+
+    /// Enroll subscriber public key out-of-band, ie. without processing a Subscribe message.
+    pub fn store_new_subscriber(&mut self, pk: ed25519::PublicKey) -> Result<()> {
+        let ref_link = match &self.appinst {
+            Some(appinst) => appinst.rel().clone(),
+            None => return err!(UserNotRegistered),
+        };
+        self.pk_store.insert(pk, PkInfo::new(Cursor::new_at(ref_link, 0, SEQ_MESSAGE_NUM)))
+    }
+
+    /// Remove subscriber public key from the channel. Subsequent keyloads for everyone won't include it.
+    pub fn remove_subscriber(&mut self, pk: &ed25519::PublicKey) -> Result<()> {
+        try_or!(*pk != self.sig_kp.public, SubscriberNotFound(hex::encode(pk.as_bytes())))?;
+        match self.pk_store.remove(pk) {
+            Some(_info) => Ok(()),
+            None => err!(SubscriberNotFound(hex::encode(pk.as_bytes()))),
+        }
+    }
+
+    /// Remove subscriber and optionally pre-shared key of a revoked user. The removed entries are
+    /// returned so that they can be put back with `restore_revoked` if the revocation fails.
+    pub fn remove_revoked(
+        &mut self,
+        pk: &ed25519::PublicKey,
+        pskid: Option<&psk::PskId>,
+    ) -> Result<(PkInfo<<Link as HasLink>::Rel>, Option<psk::Psk>)> {
+        try_or!(*pk != self.sig_kp.public, SubscriberNotFound(hex::encode(pk.as_bytes())))?;
+        if let Some(pskid) = pskid {
+            try_or!(self.psk_store.get(pskid).is_some(), PskNotFound(hex::encode(pskid)))?;
+        }
+        let info = match self.pk_store.remove(pk) {
+            Some(info) => info,
+            None => return err!(SubscriberNotFound(hex::encode(pk.as_bytes()))),
+        };
+        let psk = pskid.and_then(|pskid| self.psk_store.remove(pskid));
+        Ok((info, psk))
+    }
+
+    /// Put back subscriber and pre-shared key removed with `remove_revoked`.
+    pub fn restore_revoked(
+        &mut self,
+        pk: &ed25519::PublicKey,
+        pskid: Option<&psk::PskId>,
+        revoked: (PkInfo<<Link as HasLink>::Rel>, Option<psk::Psk>),
+    ) -> Result<()> {
+        let (info, psk) = revoked;
+        self.pk_store.insert(pk.clone(), info)?;
+        if let (Some(pskid), Some(psk)) = (pskid, psk) {
+            self.psk_store.insert(pskid.clone(), psk);
+        }
+        Ok(())
+    }
+
+    /// Prepare ChangeKey message.
+    pub fn prepare_change_key<'a>(
+        &'a self,
+        new_sig_pk: &'a ed25519::PublicKey,
+    ) -> Result<PreparedMessage<'a, F, Link, LS, change_key::ContentWrap<'a, F, Link>>> {
+        match &self.appinst {
+            Some(appinst) => {
+                let link_to = appinst.rel();
+                let msg_link = self
+                    .link_gen
+                    .link_from(&self.sig_kp.public, Cursor::new_at(link_to, 0, ANN_MESSAGE_NUM));
+                let header = HDF::new(msg_link)
+                    .with_content_type(CHANGE_KEY)?
+                    .with_payload_length(1)?
+                    .with_seq_num(ANN_MESSAGE_NUM);
+                let content = change_key::ContentWrap {
+                    link: link_to,
+                    new_sig_pk,
+                    sig_kp: &self.sig_kp,
+                    _phantom: core::marker::PhantomData,
+                };
+                Ok(PreparedMessage::new(self.link_store.borrow(), header, content))
+            },
+            None => err!(UserNotRegistered),
+        }
+    }
+
+    /// Create ChangeKey message signed with the current key. Own key is not replaced until
+    /// `set_sig_kp` is called, ie. after the message has been sent.
+    pub fn change_key(&self, new_sig_pk: &ed25519::PublicKey) -> Result<WrappedMessage<F, Link>> {
+        self.prepare_change_key(new_sig_pk)?.wrap()
+    }
+
+    /// Replace own Ed25519 key pair, sequencing state is moved over to the new public key.
+    pub fn set_sig_kp(&mut self, sig_kp: ed25519::Keypair) -> Result<()> {
+        if let Some(info) = self.pk_store.remove(&self.sig_kp.public) {
+            self.pk_store.insert(sig_kp.public.clone(), info)?;
+        }
+        if self.author_sig_pk.as_ref() == Some(&self.sig_kp.public) {
+            self.author_sig_pk = Some(sig_kp.public.clone());
+        }
+        self.ke_kp = x25519::keypair_from_ed25519(&sig_kp);
+        self.sig_kp = sig_kp;
+        Ok(())
+    }
+
+    pub fn unwrap_change_key<'a>(
+        &self,
+        preparsed: PreparsedMessage<'a, F, Link>,
+    ) -> Result<UnwrappedMessage<F, Link, change_key::ContentUnwrap<F, Link>>> {
+        self.ensure_appinst(&preparsed)?;
+        match &self.author_sig_pk {
+            Some(author_sig_pk) => {
+                let content = change_key::ContentUnwrap::new(author_sig_pk.clone());
+                preparsed.unwrap(&*self.link_store.borrow(), content)
+            },
+            None => err!(AuthorSigKeyNotFound),
+        }
+    }
+
+    /// Verify ChangeKey message signature with the current Author's public key and
+    /// replace it with the new one.
+    pub fn handle_change_key<'a>(
+        &mut self,
+        msg: BinaryMessage<F, Link>,
+        info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
+    ) -> Result<GenericMessage<Link, (<Link as HasLink>::Rel, ed25519::PublicKey)>> {
+        let preparsed = msg.parse_header()?;
+        let content = self
+            .unwrap_change_key(preparsed)?
+            .commit(self.link_store.borrow_mut(), info)?;
+        if let Some(info) = self.pk_store.remove(&content.sig_pk) {
+            self.pk_store.insert(content.new_sig_pk.clone(), info)?;
+        }
+        self.author_sig_pk = Some(content.new_sig_pk.clone());
+        Ok(GenericMessage::new(msg.link, (content.link, content.new_sig_pk)))
+    }
+
+    /// Verify ChangeKey message signature with the current Author's public key without
+    /// replacing it.
+    pub fn read_change_key(
+        &self,
+        msg: BinaryMessage<F, Link>,
+    ) -> Result<GenericMessage<Link, (<Link as HasLink>::Rel, ed25519::PublicKey)>> {
+        let preparsed = msg.parse_header()?;
+        let content = self.unwrap_change_key(preparsed)?.pcf.content;
+        Ok(GenericMessage::new(msg.link, (content.link, content.new_sig_pk)))
+    }
+
+    /// Prepare Permission message.
+    pub fn prepare_permission<'a>(
+        &'a self,
+        link_to: &'a <Link as HasLink>::Rel,
+        pk: &'a ed25519::PublicKey,
+        write: bool,
+        branch: Option<&'a <Link as HasLink>::Rel>,
+    ) -> Result<PreparedMessage<'a, F, Link, LS, permission::ContentWrap<'a, F, Link>>> {
+        match self.get_seq_no() {
+            Some(seq_no) => {
+                let msg_link = self
+                    .link_gen
+                    .link_from(&self.sig_kp.public, Cursor::new_at(link_to, 0, seq_no));
+                let header = HDF::new(msg_link)
+                    .with_content_type(PERMISSION)?
+                    .with_payload_length(1)?
+                    .with_seq_num(seq_no);
+                let content = permission::ContentWrap {
+                    link: link_to,
+                    pk,
+                    permission: if write { permission::WRITE } else { permission::READ },
+                    branch,
+                    sig_kp: &self.sig_kp,
+                    _phantom: core::marker::PhantomData,
+                };
+                Ok(PreparedMessage::new(self.link_store.borrow(), header, content))
+            },
+            None => err!(SeqNumRetrievalFailure)
+        }
+    }
+
+    /// Create Permission message granting (`write` is set) or revoking write permission of `pk`
+    /// for all branches or for packets linked to `branch` only. Own public key store is not
+    /// updated until `store_permission` is called, ie. after the message has been sent.
+    pub fn share_permission(
+        &self,
+        link_to: &<Link as HasLink>::Rel,
+        pk: &ed25519::PublicKey,
+        write: bool,
+        branch: Option<&<Link as HasLink>::Rel>,
+    ) -> Result<WrappedMessage<F, Link>> {
+        self.prepare_permission(link_to, pk, write, branch)?.wrap()
+    }
+
+    pub fn unwrap_permission<'a>(
+        &self,
+        preparsed: PreparsedMessage<'a, F, Link>,
+    ) -> Result<UnwrappedMessage<F, Link, permission::ContentUnwrap<F, Link>>> {
+        self.ensure_appinst(&preparsed)?;
+        match &self.author_sig_pk {
+            Some(author_sig_pk) => {
+                let content = permission::ContentUnwrap::new(author_sig_pk.clone());
+                preparsed.unwrap(&*self.link_store.borrow(), content)
+            },
+            None => err!(AuthorSigKeyNotFound),
+        }
+    }
+
+    /// Verify Permission message signature with the Author's public key and update
+    /// publishing rights of the participant.
+    pub fn handle_permission<'a>(
+        &mut self,
+        msg: BinaryMessage<F, Link>,
+        info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
+    ) -> Result<GenericMessage<Link, (<Link as HasLink>::Rel, ed25519::PublicKey, bool)>> {
+        let preparsed = msg.parse_header()?;
+        let content = self
+            .unwrap_permission(preparsed)?
+            .commit(self.link_store.borrow_mut(), info)?;
+        let write = content.permission.0 == permission::WRITE;
+        self.store_permission(content.pk.clone(), write, content.branch)?;
+        Ok(GenericMessage::new(msg.link, (content.link, content.pk, write)))
+    }
+
+    /// Verify Permission message signature with the Author's public key without updating
+    /// publishing rights of the participant.
+    pub fn read_permission(
+        &self,
+        msg: BinaryMessage<F, Link>,
+    ) -> Result<GenericMessage<Link, (<Link as HasLink>::Rel, ed25519::PublicKey, bool)>> {
+        let preparsed = msg.parse_header()?;
+        let content = self.unwrap_permission(preparsed)?.pcf.content;
+        let write = content.permission.0 == permission::WRITE;
+        Ok(GenericMessage::new(msg.link, (content.link, content.pk, write)))
+    }
+
+    /// Grant or revoke write permission of `pk` in own public key store. Unknown participant
+    /// is stored with read-only permission first.
+    pub fn store_permission(
+        &mut self,
+        pk: ed25519::PublicKey,
+        write: bool,
+        branch: Option<<Link as HasLink>::Rel>,
+    ) -> Result<()> {
+        if self.pk_store.get(&pk).is_none() {
+            let ref_link = match &self.appinst {
+                Some(appinst) => appinst.rel().clone(),
+                None => return err!(UserNotRegistered),
+            };
+            // Store at state 2 since 0 and 1 are reserved states
+            self.pk_store.insert(pk.clone(), PkInfo::new(Cursor::new_at(ref_link, 0, 2)))?;
+        }
+        if let Some(info) = self.pk_store.get_mut(&pk) {
+            if write {
+                info.permission.grant(branch);
+            } else {
+                info.permission.revoke(branch.as_ref());
+            }
+        }
+        Ok(())
+    }
+
+    /// Replace publishing rights with the roles carried in a keyload: listed participants are
+    /// writers for all branches or for the given ones, everyone else is a reader.
+    fn store_roles(&mut self, writers: Vec<(ed25519::PublicKey, Option<Vec<<Link as HasLink>::Rel>>)>) -> Result<()> {
+        for (_pk, info) in self.pk_store.iter_mut() {
+            info.permission = Permission::Read;
+        }
+        for (pk, branches) in writers {
+            match branches {
+                None => self.store_permission(pk, true, None)?,
+                Some(branches) => {
+                    for branch in branches {
+                        self.store_permission(pk.clone(), true, Some(branch))?;
+                    }
+                },
+            }
+        }
+        Ok(())
+    }
+
+    /// Publishing rights of the participant, None if the public key is not known.
+    pub fn permission(&self, pk: &ed25519::PublicKey) -> Option<&Permission<<Link as HasLink>::Rel>> {
+        self.pk_store.get(pk).map(|info| &info.permission)
+    }
+
+    /// Check whether `pk` has writer role for packets linked to `link_to`, participants
+    /// without write permission are readers. Channel owner is always a writer.
+    pub fn is_writer(&self, pk: &ed25519::PublicKey, link_to: &<Link as HasLink>::Rel) -> bool {
+        self.author_sig_pk.as_ref() == Some(pk)
+            || self.permission(pk).map_or(false, |permission| permission.can_write(link_to))
+    }
+
+    /// Store pre-shared key, it can then be used in keyloads.
+    pub fn store_psk(&mut self, pskid: psk::PskId, psk: psk::Psk) {
+        self.psk_store.insert(pskid, psk);
+    }
+
+    /// Remove pre-shared key, subsequent keyloads won't be able to use it.
+    pub fn remove_psk(&mut self, pskid: &psk::PskId) -> Result<()> {
+        match self.psk_store.remove(pskid) {
+            Some(_psk) => Ok(()),
+            None => err!(PskNotFound(hex::encode(pskid))),
+        }
+    }
+
+    /// Identifiers of the stored pre-shared keys.
+    pub fn psk_ids(&self) -> psk::PskIds {
+        self.psk_store.iter().into_iter().map(|(pskid, _psk)| pskid.clone()).collect()
+    }
+
+    /// Message packets linked to `link_to` must be attached to: the revocation keyload if `link_to`
+    /// is a keyload superseded by it, `link_to` otherwise.
+    pub fn keyload_link<'a>(&'a self, link_to: &'a <Link as HasLink>::Rel) -> &'a <Link as HasLink>::Rel {
+        self.superseded_keyloads
+            .iter()
+            .find(|(keyload, _revocation)| keyload == link_to)
+            .map_or(link_to, |(_keyload, revocation)| revocation)
+    }
+
+    /// Supersede `keyloads` and keyloads superseded earlier by the `revocation` keyload.
+    pub fn supersede_keyloads(&mut self, keyloads: Vec<<Link as HasLink>::Rel>, revocation: &<Link as HasLink>::Rel) {
+        for (_keyload, superseding) in self.superseded_keyloads.iter_mut() {
+            *superseding = revocation.clone();
+        }
+        for keyload in keyloads {
+            if keyload != *revocation && self.superseded_keyloads.iter().all(|(superseded, _)| *superseded != keyload) {
+                self.superseded_keyloads.push((keyload, revocation.clone()));
+            }
+        }
+    }
+
     fn do_prepare_keyload<'a, Psks, KePks>(
         &'a self,
         header: HDF<Link>,
         link_to: &'a <Link as HasLink>::Rel,
         psks: Psks,
         ke_pks: KePks,
+        anonymous: bool,
     ) -> Result<PreparedMessage<'a, F, Link, LS, keyload::ContentWrap<'a, F, Link, Psks, KePks>>>
     where
         Psks: Clone + ExactSizeIterator<Item = psk::IPsk<'a>>,
@@ -409,23 +902,38 @@ where
     {
         let nonce = NBytes::from(prng::random_nonce());
         let key = NBytes::from(prng::random_key());
+        // Roles are shared with every keyload so that recipients joining later know the writers.
+        let writers = self
+            .pk_store
+            .iter()
+            .into_iter()
+            .filter_map(|(pk, info)| match &info.permission {
+                Permission::Read => None,
+                Permission::Write => Some((pk, None)),
+                Permission::WriteBranches(branches) => Some((pk, Some(&branches[..]))),
+            })
+            .collect();
         let content = keyload::ContentWrap {
             link: link_to,
             nonce: nonce,
             key: key,
             psks: psks,
             ke_pks: ke_pks,
-            sig_kp: &self.sig_kp,
+            sig_kp: Some(&self.sig_kp),
+            anonymous: anonymous,
+            writers,
             _phantom: core::marker::PhantomData,
         };
+        let header = header.with_content_version(content.content_version())?;
         Ok(PreparedMessage::new(self.link_store.borrow(), header, content))
     }
 
-    pub fn prepare_keyload<'a>(
+    fn prepare_keyload_for<'a>(
         &'a mut self,
         link_to: &'a <Link as HasLink>::Rel,
         psk_ids: &psk::PskIds,
         pks: &'a Vec<ed25519::PublicKey>,
+        anonymous: bool,
     ) -> Result<
         PreparedMessage<
             'a,
@@ -452,12 +960,61 @@ where
                     .with_seq_num(seq_no);
                 let psks = self.psk_store.filter(psk_ids);
                 let ke_pks = self.pk_store.filter(pks);
-                self.do_prepare_keyload(header, link_to, psks.into_iter(), ke_pks.into_iter())
+                self.do_prepare_keyload(header, link_to, psks.into_iter(), ke_pks.into_iter(), anonymous)
             },
             None => err!(SeqNumRetrievalFailure)
         }
     }
 
+    /// Prepare Keyload message for the given recipients.
+    pub fn prepare_keyload<'a>(
+        &'a mut self,
+        link_to: &'a <Link as HasLink>::Rel,
+        psk_ids: &psk::PskIds,
+        pks: &'a Vec<ed25519::PublicKey>,
+    ) -> Result<
+        PreparedMessage<
+            'a,
+            F,
+            Link,
+            LS,
+            keyload::ContentWrap<
+                'a,
+                F,
+                Link,
+                vec::IntoIter<psk::IPsk<'a>>,
+                vec::IntoIter<(ed25519::IPk<'a>, x25519::IPk<'a>)>,
+            >,
+        >,
+    > {
+        self.prepare_keyload_for(link_to, psk_ids, pks, false)
+    }
+
+    /// Prepare Keyload message for the given recipients with their identifiers replaced
+    /// with blinded tags.
+    pub fn prepare_anonymous_keyload<'a>(
+        &'a mut self,
+        link_to: &'a <Link as HasLink>::Rel,
+        psk_ids: &psk::PskIds,
+        pks: &'a Vec<ed25519::PublicKey>,
+    ) -> Result<
+        PreparedMessage<
+            'a,
+            F,
+            Link,
+            LS,
+            keyload::ContentWrap<
+                'a,
+                F,
+                Link,
+                vec::IntoIter<psk::IPsk<'a>>,
+                vec::IntoIter<(ed25519::IPk<'a>, x25519::IPk<'a>)>,
+            >,
+        >,
+    > {
+        self.prepare_keyload_for(link_to, psk_ids, pks, true)
+    }
+
     pub fn prepare_keyload_for_everyone<'a>(
         &'a mut self,
         link_to: &'a <Link as HasLink>::Rel,
@@ -487,7 +1044,7 @@ where
                     .with_seq_num(seq_no);
                 let ipsks = self.psk_store.iter();
                 let ike_pks = self.pk_store.keys();
-                self.do_prepare_keyload(header, link_to, ipsks.into_iter(), ike_pks.into_iter())
+                self.do_prepare_keyload(header, link_to, ipsks.into_iter(), ike_pks.into_iter(), false)
             },
             None => err!(SeqNumRetrievalFailure)
         }
@@ -504,6 +1061,18 @@ where
         self.prepare_keyload(link_to, psk_ids, ke_pks)?.wrap()
     }
 
+    /// Create keyload message with a new session key shared with recipients
+    /// identified by pre-shared key IDs and by NTRU public key IDs. Recipients
+    /// identifiers are replaced with blinded tags.
+    pub fn share_anonymous_keyload(
+        &mut self,
+        link_to: &<Link as HasLink>::Rel,
+        psk_ids: &psk::PskIds,
+        ke_pks: &Vec<ed25519::PublicKey>,
+    ) -> Result<WrappedMessage<F, Link>> {
+        self.prepare_anonymous_keyload(link_to, psk_ids, ke_pks)?.wrap()
+    }
+
     /// Create keyload message with a new session key shared with all Subscribers
     /// known to Author.
     pub fn share_keyload_for_everyone(&mut self, link_to: &<Link as HasLink>::Rel) -> Result<WrappedMessage<F, Link>> {
@@ -548,7 +1117,15 @@ where
                 Self,
                 for<'c> fn(&'c Self, &psk::PskId) -> Option<&'c psk::Psk>,
                 for<'c> fn(&'c Self, &ed25519::PublicKey) -> Option<&'c x25519::StaticSecret>,
-            >::new(self, Self::lookup_psk, Self::lookup_ke_sk, author_sig_pk);
+            >::new(
+                preparsed.header.get_content_version(),
+                self,
+                Self::lookup_psk,
+                Self::lookup_ke_sk,
+                self.psk_ids(),
+                &self.sig_kp.public,
+                author_sig_pk,
+            );
             let unwrapped = preparsed.unwrap(&*self.link_store.borrow(), content)?;
             Ok(unwrapped)
         } else {
@@ -561,15 +1138,20 @@ where
         &mut self,
         msg: BinaryMessage<F, Link>,
         info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
-    ) -> Result<GenericMessage<Link, bool>> {
+    ) -> Result<GenericMessage<Link, (<Link as HasLink>::Rel, bool)>> {
         let preparsed = msg.parse_header()?;
 
         let unwrapped = self
             .unwrap_keyload(preparsed)?;
+        let link_to = unwrapped.pcf.content.link.clone();
 
         if unwrapped.pcf.content.key.is_some() {
+            // Only keyloads signed by the Author are accepted, signature itself is verified during unwrap
+            try_or!(unwrapped.pcf.content.signed, KeyloadNotSigned)?;
             // Do not commit if key not found hence spongos state is invalid
             let content = unwrapped.commit(self.link_store.borrow_mut(), info)?;
+            // Content borrows the user, take roles out of it before updating stores
+            let writers = content.writers;
 
             // Presence of the key indicates the user is allowed
             // Unwrapped nonce and key in content are not used explicitly.
@@ -579,16 +1161,34 @@ where
                 for ke_pk in content.ke_pks {
                     if self.pk_store.get(&ke_pk).is_none() {
                         // Store at state 2 since 0 and 1 are reserved states
-                        self.pk_store.insert(ke_pk, Cursor::new_at(appinst.rel().clone(), 0, 2))?;
+                        self.pk_store.insert(ke_pk, PkInfo::new(Cursor::new_at(appinst.rel().clone(), 0, 2)))?;
                     }
                 }
             }
-            Ok(GenericMessage::new(msg.link, true))
+            if let Some(writers) = writers {
+                self.store_roles(writers)?;
+            }
+            Ok(GenericMessage::new(msg.link, (link_to, true)))
         } else {
-            Ok(GenericMessage::new(msg.link, false))
+            Ok(GenericMessage::new(msg.link, (link_to, false)))
         }
     }
 
+    /// Try unwrapping session key from keyload without storing the spongos state and publishers.
+    pub fn read_keyload(
+        &self,
+        msg: BinaryMessage<F, Link>,
+    ) -> Result<GenericMessage<Link, (<Link as HasLink>::Rel, bool)>> {
+        let preparsed = msg.parse_header()?;
+        let unwrapped = self.unwrap_keyload(preparsed)?;
+        let content = &unwrapped.pcf.content;
+        if content.key.is_some() {
+            try_or!(content.signed, KeyloadNotSigned)?;
+        }
+        let body = (content.link.clone(), content.key.is_some());
+        Ok(GenericMessage::new(msg.link, body))
+    }
+
     /// Prepare SignedPacket message.
     pub fn prepare_signed_packet<'a>(
         &'a mut self,
@@ -596,6 +1196,7 @@ where
         public_payload: &'a Bytes,
         masked_payload: &'a Bytes,
     ) -> Result<PreparedMessage<'a, F, Link, LS, signed_packet::ContentWrap<'a, F, Link>>> {
+        let timestamp = self.timestamp();
         match self.get_seq_no() {
             Some(seq_no) => {
                 let msg_link = self
@@ -607,12 +1208,17 @@ where
                     .with_seq_num(seq_no);
                 let content = signed_packet::ContentWrap {
                     link: link_to,
+                    ratchet_positions: &self.ratchet_positions,
+                    ratchet: self.ratchet_position(seq_no as u64),
+                    timestamp,
                     public_payload: public_payload,
                     masked_payload: masked_payload,
                     sig_kp: &self.sig_kp,
                     _phantom: core::marker::PhantomData,
                 };
-                Ok(PreparedMessage::new(self.link_store.borrow(), header, content))
+                let header = header.with_content_version(content.content_version())?;
+                PreparedMessage::new(self.link_store.borrow(), header, content)
+                    .with_frame_size(self.uniform_payload_length)
             },
             None => err!(SeqNumRetrievalFailure)
         }
@@ -625,8 +1231,21 @@ where
         public_payload: &Bytes,
         masked_payload: &Bytes,
     ) -> Result<WrappedMessage<F, Link>> {
-        self.prepare_signed_packet(link_to, public_payload, masked_payload)?
-            .wrap()
+        let wrapped = if self.is_padded() {
+            let masked_payload = self.pad_payload(masked_payload, |user, masked_payload| {
+                user.prepare_signed_packet(link_to, public_payload, masked_payload)?.sizeof()
+            })?;
+            let prepared = self.prepare_signed_packet(link_to, public_payload, &masked_payload)?;
+            prepared.wrap()
+        } else {
+            self.prepare_signed_packet(link_to, public_payload, masked_payload)?
+                .wrap()
+        }?;
+        // Own sequence number is advanced when the packet is sent, it is still the packet one.
+        if let Some(seq_no) = self.get_seq_no() {
+            self.advance_ratchet(link_to, seq_no as u64)?;
+        }
+        Ok(wrapped)
     }
 
     pub fn unwrap_signed_packet<'a>(
@@ -634,26 +1253,72 @@ where
         preparsed: PreparsedMessage<'a, F, Link>,
     ) -> Result<UnwrappedMessage<F, Link, signed_packet::ContentUnwrap<F, Link>>> {
         self.ensure_appinst(&preparsed)?;
-        let content = signed_packet::ContentUnwrap::default();
-        preparsed.unwrap(&*self.link_store.borrow(), content)
+        let mut content = signed_packet::ContentUnwrap::default();
+        content.content_version = preparsed.header.get_content_version();
+        let position = self.ratchet_position(preparsed.header.get_seq_num());
+        let store = self.link_store.borrow();
+        preparsed.unwrap(&RatchetLinkStore::new(&*store, &self.ratchet_positions, position), content)
     }
 
-    /// Verify new Author's MSS public key and update Author's MSS public key.
+    /// Verify signature of the packet, the signer must be the Author or a participant with
+    /// writer role for the branch the packet is linked to.
     pub fn handle_signed_packet<'a>(
         &'a mut self,
         msg: BinaryMessage<F, Link>,
         info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
-    ) -> Result<GenericMessage<Link, (ed25519::PublicKey, Bytes, Bytes)>> {
-        // TODO: pass author_pk to unwrap
+    ) -> Result<GenericMessage<Link, (<Link as HasLink>::Rel, ed25519::PublicKey, Bytes, Bytes, Option<u64>)>> {
         let preparsed = msg.parse_header()?;
 
-        let content = self
-            .unwrap_signed_packet(preparsed)?
-            .commit(self.link_store.borrow_mut(), info)?;
-        let body = (content.sig_pk, content.public_payload, content.masked_payload);
+        let seq_num = preparsed.header.get_seq_num();
+        let unwrapped = self.unwrap_signed_packet(preparsed)?;
+        self.check_signed_packet(&unwrapped.pcf.content)?;
+        let content = unwrapped.commit(self.link_store.borrow_mut(), info)?;
+        self.advance_ratchet(&content.link, seq_num)?;
+        let masked_payload = self.unpad_payload(content.masked_payload)?;
+        let body = (
+            content.link,
+            content.sig_pk,
+            content.public_payload,
+            masked_payload,
+            content.timestamp,
+        );
+        Ok(GenericMessage::new(msg.link, body))
+    }
+
+    /// Verify signature of the packet without storing the spongos state, so an already handled
+    /// packet can be read again. The signer must have writer role, timestamp is not checked.
+    pub fn read_signed_packet(
+        &self,
+        msg: BinaryMessage<F, Link>,
+    ) -> Result<GenericMessage<Link, (<Link as HasLink>::Rel, ed25519::PublicKey, Bytes, Bytes, Option<u64>)>> {
+        let preparsed = msg.parse_header()?;
+
+        let content = self.unwrap_signed_packet(preparsed)?.pcf.content;
+        let sig_pk = &content.sig_pk;
+        try_or!(
+            self.is_writer(sig_pk, &content.link),
+            NotAWriter(hex::encode(sig_pk.as_bytes()))
+        )?;
+        let masked_payload = self.unpad_payload(content.masked_payload)?;
+        let body = (
+            content.link,
+            content.sig_pk,
+            content.public_payload,
+            masked_payload,
+            content.timestamp,
+        );
         Ok(GenericMessage::new(msg.link, body))
     }
 
+    /// The signer of the packet must have writer role and its timestamp must be within the clock skew.
+    fn check_signed_packet(&self, content: &signed_packet::ContentUnwrap<F, Link>) -> Result<()> {
+        try_or!(
+            self.is_writer(&content.sig_pk, &content.link),
+            NotAWriter(hex::encode(content.sig_pk.as_bytes()))
+        )?;
+        self.check_timestamp(content.timestamp)
+    }
+
     /// Prepare TaggedPacket message.
     pub fn prepare_tagged_packet<'a>(
         &'a mut self,
@@ -661,6 +1326,7 @@ where
         public_payload: &'a Bytes,
         masked_payload: &'a Bytes,
     ) -> Result<PreparedMessage<'a, F, Link, LS, tagged_packet::ContentWrap<'a, F, Link>>> {
+        let timestamp = self.timestamp();
         match self.get_seq_no() {
             Some(seq_no) => {
                 let msg_link = self
@@ -672,11 +1338,17 @@ where
                     .with_seq_num(seq_no);
                 let content = tagged_packet::ContentWrap {
                     link: link_to,
+                    ratchet_positions: &self.ratchet_positions,
+                    ratchet: self.ratchet_position(seq_no as u64),
+                    timestamp,
                     public_payload: public_payload,
                     masked_payload: masked_payload,
+                    sig_kp: &self.sig_kp,
                     _phantom: core::marker::PhantomData,
                 };
-                Ok(PreparedMessage::new(self.link_store.borrow(), header, content))
+                let header = header.with_content_version(content.content_version())?;
+                PreparedMessage::new(self.link_store.borrow(), header, content)
+                    .with_frame_size(self.uniform_payload_length)
             },
             None => err!(SeqNumRetrievalFailure)
         }
@@ -690,8 +1362,21 @@ where
         public_payload: &Bytes,
         masked_payload: &Bytes,
     ) -> Result<WrappedMessage<F, Link>> {
-        self.prepare_tagged_packet(link_to, public_payload, masked_payload)?
-            .wrap()
+        let wrapped = if self.is_padded() {
+            let masked_payload = self.pad_payload(masked_payload, |user, masked_payload| {
+                user.prepare_tagged_packet(link_to, public_payload, masked_payload)?.sizeof()
+            })?;
+            let prepared = self.prepare_tagged_packet(link_to, public_payload, &masked_payload)?;
+            prepared.wrap()
+        } else {
+            self.prepare_tagged_packet(link_to, public_payload, masked_payload)?
+                .wrap()
+        }?;
+        // Own sequence number is advanced when the packet is sent, it is still the packet one.
+        if let Some(seq_no) = self.get_seq_no() {
+            self.advance_ratchet(link_to, seq_no as u64)?;
+        }
+        Ok(wrapped)
     }
 
     pub fn unwrap_tagged_packet<'a>(
@@ -699,25 +1384,68 @@ where
         preparsed: PreparsedMessage<'a, F, Link>,
     ) -> Result<UnwrappedMessage<F, Link, tagged_packet::ContentUnwrap<F, Link>>> {
         self.ensure_appinst(&preparsed)?;
-        let content = tagged_packet::ContentUnwrap::new();
-        preparsed.unwrap(&*self.link_store.borrow(), content)
+        let mut content = tagged_packet::ContentUnwrap::new();
+        content.content_version = preparsed.header.get_content_version();
+        let position = self.ratchet_position(preparsed.header.get_seq_num());
+        let store = self.link_store.borrow();
+        preparsed.unwrap(&RatchetLinkStore::new(&*store, &self.ratchet_positions, position), content)
     }
 
-    /// Get public payload, decrypt masked payload and verify MAC.
+    /// Get public payload, decrypt masked payload, verify MAC and signature of the sender.
+    ///
+    /// Any recipient of the keyload can produce the MAC, the sender is authenticated by the
+    /// signature and must have writer role. Packets without sender can't be attributed to
+    /// a writer and are rejected.
     pub fn handle_tagged_packet<'a>(
         &mut self,
         msg: BinaryMessage<F, Link>,
         info: <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info,
-    ) -> Result<GenericMessage<Link, (Bytes, Bytes)>> {
+    ) -> Result<GenericMessage<Link, (<Link as HasLink>::Rel, ed25519::PublicKey, Bytes, Bytes, Option<u64>)>> {
         let preparsed = msg.parse_header()?;
 
-        let content = self
-            .unwrap_tagged_packet(preparsed)?
-            .commit(self.link_store.borrow_mut(), info)?;
-        let body = (content.public_payload, content.masked_payload);
+        let seq_num = preparsed.header.get_seq_num();
+        let unwrapped = self.unwrap_tagged_packet(preparsed)?;
+        let sender = self.tagged_packet_writer(&unwrapped.pcf.content)?;
+        self.check_timestamp(unwrapped.pcf.content.timestamp)?;
+        let content = unwrapped.commit(self.link_store.borrow_mut(), info)?;
+        self.advance_ratchet(&content.link, seq_num)?;
+        let masked_payload = self.unpad_payload(content.masked_payload)?;
+        let body = (content.link, sender, content.public_payload, masked_payload, content.timestamp);
         Ok(GenericMessage::new(msg.link, body))
     }
 
+    /// Get public payload, decrypt masked payload, verify MAC and signature of the sender without
+    /// storing the spongos state. The sender must have writer role, timestamp is not checked.
+    pub fn read_tagged_packet(
+        &self,
+        msg: BinaryMessage<F, Link>,
+    ) -> Result<GenericMessage<Link, (<Link as HasLink>::Rel, ed25519::PublicKey, Bytes, Bytes, Option<u64>)>> {
+        let preparsed = msg.parse_header()?;
+
+        let content = self.unwrap_tagged_packet(preparsed)?.pcf.content;
+        let sender = self.tagged_packet_writer(&content)?;
+        let masked_payload = self.unpad_payload(content.masked_payload)?;
+        let body = (content.link, sender, content.public_payload, masked_payload, content.timestamp);
+        Ok(GenericMessage::new(msg.link, body))
+    }
+
+    /// The sender of the tagged packet must be known and have writer role.
+    fn tagged_packet_writer(&self, content: &tagged_packet::ContentUnwrap<F, Link>) -> Result<ed25519::PublicKey> {
+        match &content.sender {
+            Some(sender) => {
+                try_or!(
+                    self.is_writer(sender, &content.link),
+                    NotAWriter(hex::encode(sender.as_bytes()))
+                )?;
+                Ok(sender.clone())
+            },
+            None => err!(InvalidContentVersion(
+                tagged_packet::CONTENT_VERSION_SENDER,
+                content.content_version
+            )),
+        }
+    }
+
     pub fn prepare_sequence<'a>(
         &'a mut self,
         link_to: &'a <Link as HasLink>::Rel,
@@ -744,8 +1472,8 @@ where
 
     pub fn wrap_sequence(&self, ref_link: &<Link as HasLink>::Rel) -> Result<WrappedSequence<F, Link>> {
         match self.pk_store.get(&self.sig_kp.public) {
-            Some(cursor) => {
-                let mut cursor = cursor.clone();
+            Some(info) => {
+                let mut cursor = info.cursor.clone();
                 if (self.flags & FLAG_BRANCHING_MASK) != 0 {
                     let msg_link = self
                         .link_gen
@@ -789,7 +1517,7 @@ where
                 cursor.link = wrapped.link.rel().clone();
                 cursor.next_seq();
                 wrapped.commit(self.link_store.borrow_mut(), info)?;
-                self.pk_store.insert(self.sig_kp.public.clone(), cursor)?;
+                self.store_cursor(self.sig_kp.public.clone(), cursor)?;
                 Ok(Some(link))
             }
             None => {
@@ -861,13 +1589,198 @@ where
         Ok(GenericMessage::new(msg.link, content))
     }
 
+    // Unwrap sequence message without storing its spongos state
+    pub fn read_sequence(
+        &self,
+        msg: BinaryMessage<F, Link>,
+    ) -> Result<GenericMessage<Link, sequence::ContentUnwrap<Link>>> {
+        let preparsed = msg.parse_header()?;
+        let content = self.unwrap_sequence(preparsed)?.pcf.content;
+        Ok(GenericMessage::new(msg.link, content))
+    }
+
     pub fn is_multi_branching(&self) -> bool {
         (self.flags & FLAG_BRANCHING_MASK) != 0
     }
 
+    pub fn is_ratcheted(&self) -> bool {
+        (self.flags & FLAG_RATCHET_MASK) != 0
+    }
+
+    /// Enable or disable forward-secret ratchet for packets. The flag is announced to
+    /// subscribers, so it can't be changed once the channel has been announced.
+    pub fn set_ratchet(&mut self, ratchet: bool) -> Result<()> {
+        if let Some(appinst) = &self.appinst {
+            try_or!(
+                self.link_store.borrow().lookup(appinst.rel()).is_err(),
+                ChannelAlreadyAnnounced(appinst.base().to_string())
+            )?;
+        }
+        if ratchet {
+            self.flags |= FLAG_RATCHET_MASK;
+        } else {
+            self.flags &= !FLAG_RATCHET_MASK;
+        }
+        Ok(())
+    }
+
+    pub fn is_padded(&self) -> bool {
+        (self.flags & FLAG_PADDING_MASK) != 0
+    }
+
+    /// Enable or disable padding of masked payloads. The flag is announced to subscribers,
+    /// so it can't be changed once the channel has been announced.
+    pub fn set_padding(&mut self, padding: bool) -> Result<()> {
+        if let Some(appinst) = &self.appinst {
+            try_or!(
+                self.link_store.borrow().lookup(appinst.rel()).is_err(),
+                ChannelAlreadyAnnounced(appinst.base().to_string())
+            )?;
+        }
+        if padding {
+            self.flags |= FLAG_PADDING_MASK;
+        } else {
+            self.flags &= !FLAG_PADDING_MASK;
+        }
+        Ok(())
+    }
+
+    /// Pad masked payload with `0x80` followed by zero bytes, so that the wrapped packet size
+    /// is a multiple of `uniform_payload_length`, ie. the packet fills whole payload frames.
+    /// `sizeof` returns the size of the wrapped packet with the given masked payload.
+    fn pad_payload<S>(&mut self, masked_payload: &Bytes, mut sizeof: S) -> Result<Bytes>
+    where
+        S: FnMut(&mut Self, &Bytes) -> Result<usize>,
+    {
+        let mut padded = Bytes(Vec::with_capacity(masked_payload.0.len() + 1));
+        padded.0.extend_from_slice(&masked_payload.0);
+        padded.0.push(0x80);
+        if self.uniform_payload_length != 0 {
+            // Size of the payload length field may grow with padding, hence the loop.
+            loop {
+                let size = sizeof(self, &padded)?;
+                let full_size = frames::full_frames_size::<F>(size, self.uniform_payload_length)?;
+                if size == full_size {
+                    break;
+                }
+                let padded_len = padded.0.len() + full_size - size;
+                padded.0.resize(padded_len, 0);
+            }
+        }
+        Ok(padded)
+    }
+
+    /// Remove padding from unwrapped masked payload if the channel is padded.
+    fn unpad_payload(&self, mut masked_payload: Bytes) -> Result<Bytes> {
+        if self.is_padded() {
+            while masked_payload.0.last() == Some(&0) {
+                masked_payload.0.pop();
+            }
+            try_or!(masked_payload.0.pop() == Some(0x80), InvalidPadding)?;
+        }
+        Ok(masked_payload)
+    }
+
+    /// Include timestamp into published packets. Timestamps are taken from the `clock`.
+    pub fn set_timestamping(&mut self, timestamping: bool) {
+        self.timestamping = timestamping;
+    }
+
+    /// Reject received packets whose timestamp differs from the `clock` by more than
+    /// `clock_skew` milliseconds, `None` disables the check.
+    pub fn set_clock_skew(&mut self, clock_skew: Option<u64>) {
+        self.clock_skew = clock_skew;
+    }
+
+    /// Timestamp for a packet being published.
+    fn timestamp(&self) -> Option<u64> {
+        if self.timestamping {
+            self.clock.map(|clock| clock())
+        } else {
+            None
+        }
+    }
+
+    /// Check timestamp of a received packet against the clock skew window.
+    fn check_timestamp(&self, timestamp: Option<u64>) -> Result<()> {
+        if let Some(clock_skew) = self.clock_skew {
+            match (timestamp, self.clock) {
+                (Some(timestamp), Some(clock)) => {
+                    let now = clock();
+                    try_or!(
+                        timestamp <= now.saturating_add(clock_skew) && now <= timestamp.saturating_add(clock_skew),
+                        TimestampOutOfRange(timestamp, now)
+                    )?;
+                },
+                (None, _) => return err!(MissingTimestamp),
+                (Some(_), None) => return err!(ClockNotAvailable),
+            }
+        }
+        Ok(())
+    }
+
+    /// Spongos state the payload frames of packet `msg` are chained to, `None` if the packet is not
+    /// split into frames. It is the state of the message the packet is linked to ratcheted past the
+    /// packet, so only the recipients of that message can produce and check the frames. The sender
+    /// gets it once its ratchet is advanced, recipients before they handle the packet.
+    pub fn frames_key(&self, msg: &BinaryMessage<F, Link>) -> Result<Option<spongos::Spongos<F>>> {
+        let preparsed = msg.parse_header()?;
+        if preparsed.header.get_payload_frame_count() < 2 {
+            return Ok(None);
+        }
+        let content_type = preparsed.content_type();
+        try_or!(
+            content_type == SIGNED_PACKET || content_type == TAGGED_PACKET,
+            UnknownMsgType(content_type)
+        )?;
+        let position = self.ratchet_position(preparsed.header.get_seq_num()) + 1;
+        let link = preparsed.unwrap(&(), PacketLink::default())?.pcf.content.0;
+        let store = self.link_store.borrow();
+        let (spongos, _info) = RatchetLinkStore::new(&*store, &self.ratchet_positions, position).lookup(&link)?;
+        Ok(Some(spongos))
+    }
+
+    /// Split message `msg` into payload frames of `uniform_payload_length` bytes if it doesn't fit
+    /// into a single one.
+    pub fn split_frames(&self, msg: BinaryMessage<F, Link>) -> Result<Vec<BinaryMessage<F, Link>>> {
+        match self.frames_key(&msg)? {
+            Some(key) => msg.split_frames(&self.link_gen, &key, self.uniform_payload_length),
+            None => Ok(vec![msg]),
+        }
+    }
+
+    /// Ratchet position of the spongos state of the message a packet with sequence number `seq_num`
+    /// is linked to, each packet is wrapped with its own state derived in a one-way fashion.
+    fn ratchet_position(&self, seq_num: u64) -> u64 {
+        if self.is_ratcheted() {
+            seq_num
+        } else {
+            0
+        }
+    }
+
+    /// Advance the stored spongos state of the message `link` past the packet with sequence number
+    /// `seq_num` linked to it. The states at the positions up to the packet's one are overwritten,
+    /// so compromise of the user state does not allow to unwrap the packets handled so far. Packets
+    /// linked to `link` with lower sequence numbers can't be unwrapped anymore, hence a ratcheted
+    /// channel expects packets linked to the same message to be published and handled in order.
+    fn advance_ratchet(&mut self, link: &<Link as HasLink>::Rel, seq_num: u64) -> Result<()> {
+        if !self.is_ratcheted() {
+            return Ok(());
+        }
+        let (spongos, info) =
+            RatchetLinkStore::new(&*self.link_store.borrow(), &self.ratchet_positions, seq_num).lookup(link)?;
+        self.link_store.borrow_mut().update(link, spongos.ratchet(), info)?;
+        match self.ratchet_positions.iter_mut().find(|(l, _)| l == link) {
+            Some((_, position)) => *position = seq_num + 1,
+            None => self.ratchet_positions.push((link.clone(), seq_num + 1)),
+        }
+        Ok(())
+    }
+
     // TODO: own seq_no should be stored outside of pk_store to avoid lookup and Option
     pub fn get_seq_no(&self) -> Option<u32> {
-        self.pk_store.get(&self.sig_kp.public).map(|cursor| cursor.seq_no)
+        self.pk_store.get(&self.sig_kp.public).map(|info| info.cursor.seq_no)
     }
 
     pub fn ensure_appinst<'a>(&self, preparsed: &PreparsedMessage<'a, F, Link>) -> Result<()> {
@@ -885,15 +1798,18 @@ where
     fn gen_next_msg_id(
         ids: &mut Vec<(ed25519::PublicKey, Cursor<Link>)>,
         link_gen: &LG,
-        pk_info: (&ed25519::PublicKey, &Cursor<<Link as HasLink>::Rel>),
+        pk_info: (&ed25519::PublicKey, &PkInfo<<Link as HasLink>::Rel>),
         branching: bool,
     ) {
         let (
             pk,
-            Cursor {
-                link: seq_link,
-                branch_no: _,
-                seq_no,
+            PkInfo {
+                cursor: Cursor {
+                    link: seq_link,
+                    branch_no: _,
+                    seq_no,
+                },
+                permission: _,
             },
         ) = pk_info;
         if branching {
@@ -919,24 +1835,32 @@ where
     }
 
     pub fn store_state(&mut self, pk: ed25519::PublicKey, link: <Link as HasLink>::Rel) -> Result<()> {
-        if let Some(cursor) = self.pk_store.get(&pk) {
-            let mut cursor = cursor.clone();
-            cursor.link = link;
-            cursor.next_seq();
-            self.pk_store.insert(pk, cursor)?;
+        if let Some(info) = self.pk_store.get_mut(&pk) {
+            info.cursor.link = link;
+            info.cursor.next_seq();
         }
         Ok(())
     }
 
     pub fn store_state_for_all(&mut self, link: <Link as HasLink>::Rel, seq_no: u32) -> Result<()>{
-        self.pk_store
-            .insert(self.sig_kp.public.clone(), Cursor::new_at(link.clone(), 0, seq_no + 1))?;
-        for (_pk, cursor) in self.pk_store.iter_mut() {
-            cursor.link = link.clone();
-            cursor.seq_no = seq_no + 1;
+        self.store_cursor(self.sig_kp.public.clone(), Cursor::new_at(link.clone(), 0, seq_no + 1))?;
+        for (_pk, info) in self.pk_store.iter_mut() {
+            info.cursor.link = link.clone();
+            info.cursor.seq_no = seq_no + 1;
         }
         Ok(())
     }
+
+    /// Replace sequencing state of the publisher keeping its permission.
+    fn store_cursor(&mut self, pk: ed25519::PublicKey, cursor: Cursor<<Link as HasLink>::Rel>) -> Result<()> {
+        match self.pk_store.get_mut(&pk) {
+            Some(info) => {
+                info.cursor = cursor;
+                Ok(())
+            },
+            None => self.pk_store.insert(pk, PkInfo::new(cursor)),
+        }
+    }
 }
 
 impl<F, Link, LG, LS, PKS, PSKS> ContentSizeof<F> for User<F, Link, LG, LS, PKS, PSKS>
@@ -948,7 +1872,7 @@ where
     LG: LinkGenerator<Link>,
     LS: LinkStore<F, <Link as HasLink>::Rel> + Default,
     <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info: AbsorbFallback<F>,
-    PKS: PublicKeyStore<Cursor<<Link as HasLink>::Rel>>,
+    PKS: PublicKeyStore<PkInfo<<Link as HasLink>::Rel>>,
     PSKS: PresharedKeyStore,
 {
     fn sizeof<'c>(&self, ctx: &'c mut sizeof::Context<F>) -> Result<&'c mut sizeof::Context<F>> {
@@ -997,16 +1921,60 @@ where
                 Ok(ctx)
             })?
             .absorb(repeated_pks)?
-            .repeated(pks.into_iter(), |ctx, (pk, cursor)| {
+            .repeated(pks.into_iter(), |ctx, (pk, info)| {
+                let (oneof_permission, branches) = match &info.permission {
+                    Permission::Read => (Uint8(0), None),
+                    Permission::Write => (Uint8(1), None),
+                    Permission::WriteBranches(branches) => (Uint8(2), Some(branches)),
+                };
                 ctx
                     .absorb(pk)?
-                    .absorb(<&Fallback::<<Link as HasLink>::Rel>>::from(&cursor.link))?
-                    .absorb(Uint32(cursor.branch_no))?
-                    .absorb(Uint32(cursor.seq_no))?
+                    .absorb(<&Fallback::<<Link as HasLink>::Rel>>::from(&info.cursor.link))?
+                    .absorb(Uint32(info.cursor.branch_no))?
+                    .absorb(Uint32(info.cursor.seq_no))?
+                    .absorb(oneof_permission)?
                 ;
+                if let Some(branches) = branches {
+                    ctx
+                        .absorb(Size(branches.len()))?
+                        .repeated(branches.iter(), |ctx, branch| {
+                            ctx.absorb(<&Fallback::<<Link as HasLink>::Rel>>::from(branch))
+                        })?
+                    ;
+                }
                 Ok(ctx)
             })?
+        ;
+
+        let oneof_latest_keyload = Uint8(if self.latest_keyload.is_some() { 1 } else { 0 });
+        ctx.absorb(&oneof_latest_keyload)?;
+        if let Some(ref latest_keyload) = self.latest_keyload {
+            ctx.absorb(<&Fallback::<<Link as HasLink>::Rel>>::from(latest_keyload))?;
+        }
+
+        ctx
+            .absorb(Size(self.superseded_keyloads.len()))?
+            .repeated(self.superseded_keyloads.iter(), |ctx, (keyload, revocation)| {
+                ctx
+                    .absorb(<&Fallback::<<Link as HasLink>::Rel>>::from(keyload))?
+                    .absorb(<&Fallback::<<Link as HasLink>::Rel>>::from(revocation))
+            })?
+            .absorb(Uint8(if self.timestamping { 1 } else { 0 }))?
+        ;
 
+        let oneof_clock_skew = Uint8(if self.clock_skew.is_some() { 1 } else { 0 });
+        ctx.absorb(&oneof_clock_skew)?;
+        if let Some(clock_skew) = self.clock_skew {
+            ctx.absorb(Uint64(clock_skew))?;
+        }
+
+        ctx
+            .absorb(Size(self.ratchet_positions.len()))?
+            .repeated(self.ratchet_positions.iter(), |ctx, (link, position)| {
+                ctx
+                    .absorb(<&Fallback::<<Link as HasLink>::Rel>>::from(link))?
+                    .absorb(Uint64(*position))
+            })?
             .commit()?
             .squeeze(Mac(32))?
             ;
@@ -1024,7 +1992,7 @@ where
     LG: LinkGenerator<Link>,
     LS: LinkStore<F, <Link as HasLink>::Rel> + Default,
     <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info: AbsorbFallback<F>,
-    PKS: PublicKeyStore<Cursor<<Link as HasLink>::Rel>>,
+    PKS: PublicKeyStore<PkInfo<<Link as HasLink>::Rel>>,
     PSKS: PresharedKeyStore,
 {
     fn wrap<'c, OS: io::OStream>(
@@ -1077,16 +2045,60 @@ where
                 Ok(ctx)
             })?
             .absorb(repeated_pks)?
-            .repeated(pks.into_iter(), |ctx, (pk, cursor)| {
+            .repeated(pks.into_iter(), |ctx, (pk, info)| {
+                let (oneof_permission, branches) = match &info.permission {
+                    Permission::Read => (Uint8(0), None),
+                    Permission::Write => (Uint8(1), None),
+                    Permission::WriteBranches(branches) => (Uint8(2), Some(branches)),
+                };
                 ctx
                     .absorb(pk)?
-                    .absorb(<&Fallback::<<Link as HasLink>::Rel>>::from(&cursor.link))?
-                    .absorb(Uint32(cursor.branch_no))?
-                    .absorb(Uint32(cursor.seq_no))?
+                    .absorb(<&Fallback::<<Link as HasLink>::Rel>>::from(&info.cursor.link))?
+                    .absorb(Uint32(info.cursor.branch_no))?
+                    .absorb(Uint32(info.cursor.seq_no))?
+                    .absorb(oneof_permission)?
                 ;
+                if let Some(branches) = branches {
+                    ctx
+                        .absorb(Size(branches.len()))?
+                        .repeated(branches.iter(), |ctx, branch| {
+                            ctx.absorb(<&Fallback::<<Link as HasLink>::Rel>>::from(branch))
+                        })?
+                    ;
+                }
                 Ok(ctx)
             })?
+        ;
+
+        let oneof_latest_keyload = Uint8(if self.latest_keyload.is_some() { 1 } else { 0 });
+        ctx.absorb(&oneof_latest_keyload)?;
+        if let Some(ref latest_keyload) = self.latest_keyload {
+            ctx.absorb(<&Fallback::<<Link as HasLink>::Rel>>::from(latest_keyload))?;
+        }
 
+        ctx
+            .absorb(Size(self.superseded_keyloads.len()))?
+            .repeated(self.superseded_keyloads.iter(), |ctx, (keyload, revocation)| {
+                ctx
+                    .absorb(<&Fallback::<<Link as HasLink>::Rel>>::from(keyload))?
+                    .absorb(<&Fallback::<<Link as HasLink>::Rel>>::from(revocation))
+            })?
+            .absorb(Uint8(if self.timestamping { 1 } else { 0 }))?
+        ;
+
+        let oneof_clock_skew = Uint8(if self.clock_skew.is_some() { 1 } else { 0 });
+        ctx.absorb(&oneof_clock_skew)?;
+        if let Some(clock_skew) = self.clock_skew {
+            ctx.absorb(Uint64(clock_skew))?;
+        }
+
+        ctx
+            .absorb(Size(self.ratchet_positions.len()))?
+            .repeated(self.ratchet_positions.iter(), |ctx, (link, position)| {
+                ctx
+                    .absorb(<&Fallback::<<Link as HasLink>::Rel>>::from(link))?
+                    .absorb(Uint64(*position))
+            })?
             .commit()?
             .squeeze(Mac(32))?
             ;
@@ -1104,13 +2116,37 @@ where
     LG: LinkGenerator<Link>,
     LS: LinkStore<F, <Link as HasLink>::Rel> + Default,
     <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info: Default + AbsorbFallback<F>,
-    PKS: PublicKeyStore<Cursor<<Link as HasLink>::Rel>> + Default,
+    PKS: PublicKeyStore<PkInfo<<Link as HasLink>::Rel>> + Default,
     PSKS: PresharedKeyStore + Default,
 {
     fn unwrap<'c, IS: io::IStream>(
         &mut self,
         _store: &Store,
         ctx: &'c mut unwrap::Context<F, IS>,
+    ) -> Result<&'c mut unwrap::Context<F, IS>> {
+        self.unwrap_version(EXPORT_VERSION, ctx)
+    }
+}
+
+impl<F, Link, LG, LS, PKS, PSKS> User<F, Link, LG, LS, PKS, PSKS>
+where
+    F: PRP,
+    Link: HasLink + AbsorbExternalFallback<F> + AbsorbFallback<F>,
+    <Link as HasLink>::Base: Eq + fmt::Debug + fmt::Display,
+    <Link as HasLink>::Rel: Eq + fmt::Debug + SkipFallback<F> + AbsorbFallback<F>,
+    LG: LinkGenerator<Link>,
+    LS: LinkStore<F, <Link as HasLink>::Rel> + Default,
+    <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info: Default + AbsorbFallback<F>,
+    PKS: PublicKeyStore<PkInfo<<Link as HasLink>::Rel>> + Default,
+    PSKS: PresharedKeyStore + Default,
+{
+    /// Unwrap user state exported with layout `version`:
+    /// 1 adds publishing rights of the participants, 2 adds keyload history,
+    /// 3 adds timestamp settings and 4 adds ratchet positions.
+    fn unwrap_version<'c, IS: io::IStream>(
+        &mut self,
+        version: u8,
+        ctx: &'c mut unwrap::Context<F, IS>,
     ) -> Result<&'c mut unwrap::Context<F, IS>> {
         let mut sig_sk_bytes = NBytes::<U32>::default();
         let mut flags = Uint8(0);
@@ -1198,15 +2234,120 @@ where
                 let mut link = Fallback(<Link as HasLink>::Rel::default());
                 let mut branch_no = Uint32(0);
                 let mut seq_no = Uint32(0);
+                let mut oneof_permission = Uint8(0);
                 ctx
                     .absorb(&mut pk)?
                     .absorb(&mut link)?
                     .absorb(&mut branch_no)?
                     .absorb(&mut seq_no)?
                 ;
-                pk_store.insert(pk, Cursor::new_at(link.0, branch_no.0, seq_no.0))?;
+                if version >= 1 {
+                    ctx
+                        .absorb(&mut oneof_permission)?
+                        .guard(oneof_permission.0 < 3,
+                               InvalidPermission(oneof_permission.0)
+                        )?
+                    ;
+                }
+                let permission = match oneof_permission.0 {
+                    0 => Permission::Read,
+                    1 => Permission::Write,
+                    _ => {
+                        let mut repeated_branches = Size(0);
+                        let mut branches = Vec::new();
+                        ctx
+                            .absorb(&mut repeated_branches)?
+                            .repeated(repeated_branches, |ctx| {
+                                let mut branch = Fallback(<Link as HasLink>::Rel::default());
+                                ctx.absorb(&mut branch)?;
+                                branches.push(branch.0);
+                                Ok(ctx)
+                            })?
+                        ;
+                        Permission::WriteBranches(branches)
+                    },
+                };
+                let info = PkInfo {
+                    cursor: Cursor::new_at(link.0, branch_no.0, seq_no.0),
+                    permission,
+                };
+                pk_store.insert(pk, info)?;
                 Ok(ctx)
             })?
+        ;
+
+        let mut latest_keyload = None;
+        let mut superseded_keyloads = Vec::new();
+        if version >= 2 {
+            let mut oneof_latest_keyload = Uint8(0);
+            ctx
+                .absorb(&mut oneof_latest_keyload)?
+                .guard(oneof_latest_keyload.0 < 2,
+                       ValueOutOfRange(1, oneof_latest_keyload.0 as usize)
+                )?;
+
+            if oneof_latest_keyload.0 == 1 {
+                let mut keyload = Fallback(<Link as HasLink>::Rel::default());
+                ctx.absorb(&mut keyload)?;
+                latest_keyload = Some(keyload.0);
+            }
+
+            let mut repeated_superseded_keyloads = Size(0);
+            ctx
+                .absorb(&mut repeated_superseded_keyloads)?
+                .repeated(repeated_superseded_keyloads, |ctx| {
+                    let mut keyload = Fallback(<Link as HasLink>::Rel::default());
+                    let mut revocation = Fallback(<Link as HasLink>::Rel::default());
+                    ctx
+                        .absorb(&mut keyload)?
+                        .absorb(&mut revocation)?
+                    ;
+                    superseded_keyloads.push((keyload.0, revocation.0));
+                    Ok(ctx)
+                })?
+            ;
+        }
+
+        let mut timestamping = Uint8(0);
+        let mut clock_skew = None;
+        if version >= 3 {
+            let mut oneof_clock_skew = Uint8(0);
+            ctx
+                .absorb(&mut timestamping)?
+                .guard(timestamping.0 < 2,
+                       ValueOutOfRange(1, timestamping.0 as usize)
+                )?
+                .absorb(&mut oneof_clock_skew)?
+                .guard(oneof_clock_skew.0 < 2,
+                       ValueOutOfRange(1, oneof_clock_skew.0 as usize)
+                )?;
+
+            if oneof_clock_skew.0 == 1 {
+                let mut skew = Uint64(0);
+                ctx.absorb(&mut skew)?;
+                clock_skew = Some(skew.0);
+            }
+        }
+
+        let mut ratchet_positions = Vec::new();
+        if version >= 4 {
+            let mut repeated_ratchet_positions = Size(0);
+            ctx
+                .absorb(&mut repeated_ratchet_positions)?
+                .repeated(repeated_ratchet_positions, |ctx| {
+                    let mut link = Fallback(<Link as HasLink>::Rel::default());
+                    let mut position = Uint64(0);
+                    ctx
+                        .absorb(&mut link)?
+                        .absorb(&mut position)?
+                    ;
+                    ratchet_positions.push((link.0, position.0));
+                    Ok(ctx)
+                })?
+            ;
+        }
+
+        ctx
             .commit()?
             .squeeze(Mac(32))?
         ;
@@ -1229,6 +2370,11 @@ where
         self.flags = flags.0;
         self.message_encoding = message_encoding.0;
         self.uniform_payload_length = uniform_payload_length.0 as usize;
+        self.latest_keyload = latest_keyload;
+        self.superseded_keyloads = superseded_keyloads;
+        self.timestamping = timestamping.0 == 1;
+        self.clock_skew = clock_skew;
+        self.ratchet_positions = ratchet_positions;
         Ok(ctx)
     }
 }
@@ -1242,15 +2388,14 @@ where
     LG: LinkGenerator<Link>,
     LS: LinkStore<F, <Link as HasLink>::Rel> + Default,
     <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info: AbsorbFallback<F>,
-    PKS: PublicKeyStore<Cursor<<Link as HasLink>::Rel>>,
+    PKS: PublicKeyStore<PkInfo<<Link as HasLink>::Rel>>,
     PSKS: PresharedKeyStore,
 {
     pub fn export(&self, flag: u8, pwd: &str) -> Result<Vec<u8>> {
-        const VERSION: u8 = 0;
         let buf_size = {
             let mut ctx = sizeof::Context::<F>::new();
             ctx
-                .absorb(Uint8(VERSION))?
+                .absorb(Uint8(EXPORT_VERSION))?
                 .absorb(Uint8(flag))?
             ;
             self.sizeof(&mut ctx)?;
@@ -1264,7 +2409,7 @@ where
             let prng = prng::from_seed::<F>("IOTA Streams Channels app", pwd);
             let key = NBytes::<U32>(prng.gen_arr("user export key"));
             ctx
-                .absorb(Uint8(VERSION))?
+                .absorb(Uint8(EXPORT_VERSION))?
                 .absorb(Uint8(flag))?
                 .absorb(External(&key))?
             ;
@@ -1289,12 +2434,11 @@ where
     LG: LinkGenerator<Link>,
     LS: LinkStore<F, <Link as HasLink>::Rel> + Default,
     <LS as LinkStore<F, <Link as HasLink>::Rel>>::Info: Default + AbsorbFallback<F>,
-    PKS: PublicKeyStore<Cursor<<Link as HasLink>::Rel>> + Default,
+    PKS: PublicKeyStore<PkInfo<<Link as HasLink>::Rel>> + Default,
     PSKS: PresharedKeyStore + Default,
 {
+    /// Import user state exported with the current or an earlier layout version.
     pub fn import(bytes: &[u8], flag: u8, pwd: &str) -> Result<Self> {
-        const VERSION: u8 = 0;
-
         let mut ctx = unwrap::Context::new(bytes);
         let prng = prng::from_seed::<F>("IOTA Streams Channels app", pwd);
         let key = NBytes::<U32>(prng.gen_arr("user export key"));
@@ -1302,8 +2446,8 @@ where
         let mut flag2 = Uint8(0);
         ctx
             .absorb(&mut version)?
-            .guard(version.0 == VERSION,
-                   UserVersionRecoveryFailure(VERSION, version.0)
+            .guard(version.0 <= EXPORT_VERSION,
+                   UserVersionRecoveryFailure(EXPORT_VERSION, version.0)
             )?
             .absorb(&mut flag2)?
             .guard(flag2.0 == flag,
@@ -1313,8 +2457,7 @@ where
         ;
 
         let mut user = User::default();
-        let store = EmptyLinkStore::<F, <Link as HasLink>::Rel, ()>::default();
-        user.unwrap(&store, &mut ctx)?;
+        user.unwrap_version(version.0, &mut ctx)?;
         try_or!(
             ctx.stream.is_empty(),
             InputStreamNotFullyConsumed(ctx.stream.len())
@@ -5,22 +5,51 @@
 //! message Keyload {
 //!     join link msgid;
 //!     absorb u8 nonce[16];
+//!     if (content_version == 1) {
+//!         absorb u8 flags;
+//!     }
 //!     skip repeated {
 //!         fork;
-//!         mask u8 id[16];
+//!         if (flags & FLAG_ANONYMOUS) {
+//!             absorb u8 psk_tag[16];
+//!         } else {
+//!             mask u8 id[16];
+//!         }
 //!         absorb external u8 psk[32];
 //!         commit;
 //!         mask u8 key[32];
 //!     }
 //!     skip repeated {
 //!         fork;
-//!         mask u8 xpk[32];
-//!         absorb u8 eph_key[32];
+//!         if (flags & FLAG_ANONYMOUS) {
+//!             absorb u8 eph_key[32];
+//!             absorb u8 xkey_tag[16];
+//!         } else {
+//!             mask u8 xpk[32];
+//!             absorb u8 eph_key[32];
+//!         }
 //!         x25519(eph_key) u8 xkey[32];
 //!         commit;
 //!         mask u8 key[32];
 //!     }
 //!     absorb external u8 key[32];
+//!     if (flags & FLAG_ROLES) {
+//!         mask repeated {
+//!             mask u8 ed25519pk[32];
+//!             mask oneof branches {
+//!                 0:
+//!                 1:
+//!                     mask repeated {
+//!                         absorb link branch;
+//!                     }
+//!             }
+//!         }
+//!     }
+//!     if (flags & FLAG_SIGNED) {
+//!         commit;
+//!         squeeze external u8 hash[64];
+//!         ed25519(hash) sig;
+//!     }
 //!     commit;
 //! }
 //! ```
@@ -30,24 +59,47 @@
 //! * `nonce` -- A nonce to be used with the key encapsulated in the keyload.
 //! A unique nonce allows for session keys to be reused.
 //!
+//! * `flags` -- Keyload flags, `FLAG_SIGNED`, `FLAG_ANONYMOUS` and `FLAG_ROLES` bits are currently defined.
+//! The field is present only in content version 1 (signalled in the message header); the
+//! original content version 0 has no flags and is implicitly `FLAG_SIGNED`.
+//!
 //! * `id` -- Key (PSK or X25519 public key) identifier.
 //!
+//! * `psk_tag` -- Blinded PSK identifier; a legit recipient finds its fork by trial.
+//!
 //! * `psk` -- Pre-shared key known to the author and to a legit recipient.
 //!
 //! * `xpk` -- Recipient's X25519 public key.
 //!
 //! * `eph_key` -- X25519 random ephemeral key.
 //!
+//! * `xkey_tag` -- Blinded recipient identifier derived from the X25519 common key.
+//!
 //! * `xkey` -- X25519 common key.
 //!
 //! * `key` -- Session key; a legit recipient gets it from corresponding fork.
 //!
-//! * `sig` -- Optional signature; allows to authenticate keyload.
+//! * `ed25519pk` -- Ed25519 public key of a writer, participants not listed are readers.
+//!
+//! * `branches` -- links to the messages the writer's packets must be linked to;
+//! if absent the writer can publish in all branches.
+//!
+//! * `hash` -- hash value to be signed.
+//!
+//! * `sig` -- Signature generated with channel owner's Ed25519 private key;
+//! allows to authenticate keyload. It is present only if `FLAG_SIGNED` bit is set.
 //!
 //! Notes:
-//! 1) Keys identities are not encrypted and may be linked to recipients identities.
-//! 2) Keyload is not authenticated (signed). It can later be implicitly authenticated
-//!     via `SignedPacket`.
+//! 1) Keys identities are not encrypted and may be linked to recipients identities
+//!     unless `FLAG_ANONYMOUS` is set. Blinded tags are squeezed from the spongos state
+//!     at the beginning of the fork absorbed with PSK or X25519 common key, hence
+//!     depend on the message link and nonce and can't be linked across keyloads.
+//! 2) Signature can only be unwrapped by legit recipients as it follows the session key.
+//! 3) Keyloads with `flags == FLAG_SIGNED` are wrapped with content version 0 so that they
+//!     can be read by older implementations.
+//! 4) Roles are carried in every keyload sent while the channel has writers, so that recipients
+//!     learn them even if they can't read earlier `Permission` messages. Writers public keys are
+//!     masked with the session key, branch links are not.
 
 use iota_streams_core::Result;
 use iota_streams_app::message::{
@@ -55,10 +107,18 @@ use iota_streams_app::message::{
     HasLink,
 };
 use iota_streams_core::{
+    err,
+    Errors::{
+        InvalidBitReservation,
+        InvalidContentVersion,
+        ValueOutOfRange,
+    },
+    LOCATION_LOG,
     prelude::{
         Vec,
         typenum::Unsigned as _,
     },
+    prng,
     psk,
     sponge::{
         prp::PRP,
@@ -79,21 +139,74 @@ use iota_streams_ddml::{
     types::*,
 };
 
+/// Keyload is signed with channel owner's Ed25519 key.
+pub const FLAG_SIGNED: u8 = 1;
+/// Recipients identifiers are replaced with blinded tags.
+pub const FLAG_ANONYMOUS: u8 = 2;
+/// Keyload carries the roles of the channel participants.
+pub const FLAG_ROLES: u8 = 4;
+
+/// Original keyload layout without flags, keyload is always signed.
+pub const CONTENT_VERSION_LEGACY: u8 = 0;
+/// Keyload layout with flags following the nonce.
+pub const CONTENT_VERSION_FLAGS: u8 = 1;
+
+/// Size of the blinded recipient tag.
+pub type BlindTagSize = U16;
+
+/// Blind recipient's secret (PSK or X25519 common key) with message-specific spongos state.
+fn blind_tag<F: PRP>(spongos: &spongos::Spongos<F>, secret: &[u8]) -> NBytes<BlindTagSize> {
+    let mut s = spongos.fork();
+    s.absorb(secret);
+    s.commit();
+    NBytes::from(s.squeeze_arr::<BlindTagSize>())
+}
+
 pub struct ContentWrap<'a, F, Link: HasLink, Psks, KePks> {
     pub(crate) link: &'a <Link as HasLink>::Rel,
     pub nonce: NBytes<U16>,
     pub key: NBytes<U32>,
     pub(crate) psks: Psks,
     pub(crate) ke_pks: KePks,
-    pub(crate) sig_kp: &'a ed25519::Keypair,
+    /// Keyload is signed only if the key pair is present.
+    pub(crate) sig_kp: Option<&'a ed25519::Keypair>,
+    /// Hide recipients identifiers.
+    pub(crate) anonymous: bool,
+    /// Writers together with the branches they can publish in, all branches if None.
+    pub(crate) writers: Vec<(&'a ed25519::PublicKey, Option<&'a [<Link as HasLink>::Rel]>)>,
     pub(crate) _phantom: core::marker::PhantomData<(F, Link)>,
 }
 
+impl<'a, F, Link: HasLink, Psks, KePks> ContentWrap<'a, F, Link, Psks, KePks> {
+    fn flags(&self) -> Uint8 {
+        let mut flags = 0;
+        if self.sig_kp.is_some() {
+            flags |= FLAG_SIGNED;
+        }
+        if self.anonymous {
+            flags |= FLAG_ANONYMOUS;
+        }
+        if !self.writers.is_empty() {
+            flags |= FLAG_ROLES;
+        }
+        Uint8(flags)
+    }
+
+    /// Content version to be put into the message header.
+    pub fn content_version(&self) -> u8 {
+        if self.flags().0 == FLAG_SIGNED {
+            CONTENT_VERSION_LEGACY
+        } else {
+            CONTENT_VERSION_FLAGS
+        }
+    }
+}
+
 impl<'a, F, Link, Psks, KePks> message::ContentSizeof<F> for ContentWrap<'a, F, Link, Psks, KePks>
 where
     F: 'a + PRP, // weird 'a constraint, but compiler requires it somehow?!
     Link: HasLink,
-    <Link as HasLink>::Rel: 'a + Eq + SkipFallback<F>,
+    <Link as HasLink>::Rel: 'a + Eq + SkipFallback<F> + AbsorbFallback<F>,
     Psks: Clone + ExactSizeIterator<Item = psk::IPsk<'a>>,
     KePks: Clone + ExactSizeIterator<Item = (ed25519::IPk<'a>, x25519::IPk<'a>)>,
 {
@@ -101,24 +214,58 @@ where
         let store = EmptyLinkStore::<F, <Link as HasLink>::Rel, ()>::default();
         let repeated_psks = Size(self.psks.len());
         let repeated_ke_pks = Size(self.ke_pks.len());
-        ctx.join(&store, self.link)?
-            .absorb(&self.nonce)?
-            .skip(repeated_psks)?
+        let tag = NBytes::<BlindTagSize>::default();
+        ctx.join(&store, self.link)?.absorb(&self.nonce)?;
+        if self.content_version() != CONTENT_VERSION_LEGACY {
+            ctx.absorb(self.flags())?;
+        }
+        ctx.skip(repeated_psks)?
             .repeated(self.psks.clone(), |ctx, (pskid, psk)| {
                 ctx.fork(|ctx| {
-                    ctx.mask(<&NBytes<psk::PskIdSize>>::from(pskid))?
-                        .absorb(External(<&NBytes<psk::PskSize>>::from(psk)))?
+                    if self.anonymous {
+                        ctx.absorb(&tag)?;
+                    } else {
+                        ctx.mask(<&NBytes<psk::PskIdSize>>::from(pskid))?;
+                    }
+                    ctx.absorb(External(<&NBytes<psk::PskSize>>::from(psk)))?
                         .commit()?
                         .mask(&self.key)
                 })
             })?
             .skip(repeated_ke_pks)?
             .repeated(self.ke_pks.clone(), |ctx, (sig_pk, ke_pk)| {
-                ctx.fork(|ctx| ctx.absorb(sig_pk)?.x25519(ke_pk, &self.key))
+                ctx.fork(|ctx| {
+                    if self.anonymous {
+                        // Ephemeral key has the same size as recipient's key.
+                        ctx.absorb(ke_pk)?
+                            .absorb(&tag)?
+                            .commit()?
+                            .mask(&self.key)
+                    } else {
+                        ctx.absorb(sig_pk)?.x25519(ke_pk, &self.key)
+                    }
+                })
             })?
-            .absorb(External(&self.key))?
-            .ed25519(self.sig_kp, HashSig)?
-            .commit()?;
+            .absorb(External(&self.key))?;
+        if !self.writers.is_empty() {
+            ctx.mask(&Size(self.writers.len()))?
+                .repeated(self.writers.iter(), |ctx, (pk, branches)| {
+                    ctx.mask(*pk)?;
+                    match branches {
+                        None => ctx.mask(&Uint8(0)),
+                        Some(branches) => ctx
+                            .mask(&Uint8(1))?
+                            .mask(&Size(branches.len()))?
+                            .repeated(branches.iter(), |ctx, branch| {
+                                ctx.absorb(<&Fallback<<Link as HasLink>::Rel>>::from(branch))
+                            }),
+                    }
+                })?;
+        }
+        if let Some(sig_kp) = self.sig_kp {
+            ctx.ed25519(sig_kp, HashSig)?;
+        }
+        ctx.commit()?;
         Ok(ctx)
     }
 }
@@ -127,7 +274,7 @@ impl<'a, F, Link, Store, Psks, KePks> message::ContentWrap<F, Store> for Content
 where
     F: 'a + PRP, // weird 'a constraint, but compiler requires it somehow?!
     Link: HasLink,
-    <Link as HasLink>::Rel: 'a + Eq + SkipFallback<F>,
+    <Link as HasLink>::Rel: 'a + Eq + SkipFallback<F> + AbsorbFallback<F>,
     Store: LinkStore<F, <Link as HasLink>::Rel>,
     Psks: Clone + ExactSizeIterator<Item = psk::IPsk<'a>>,
     KePks: Clone + ExactSizeIterator<Item = (ed25519::IPk<'a>, x25519::IPk<'a>)>,
@@ -139,29 +286,66 @@ where
     ) -> Result<&'c mut wrap::Context<F, OS>> {
         let repeated_psks = Size(self.psks.len());
         let repeated_ke_pks = Size(self.ke_pks.len());
+        ctx.join(store, self.link)?.absorb(&self.nonce)?;
+        if self.content_version() != CONTENT_VERSION_LEGACY {
+            ctx.absorb(self.flags())?;
+        }
         ctx
-            .join(store, self.link)?
-            .absorb(&self.nonce)?
             .skip(repeated_psks)?
             .repeated(self.psks.clone().into_iter(), |ctx, (pskid, psk)| {
                 ctx.fork(|ctx| {
-                    ctx.mask(<&NBytes<psk::PskIdSize>>::from(pskid))?
-                        .absorb(External(<&NBytes<psk::PskSize>>::from(psk)))?
+                    if self.anonymous {
+                        let tag = blind_tag(&ctx.spongos, psk);
+                        ctx.absorb(&tag)?;
+                    } else {
+                        ctx.mask(<&NBytes<psk::PskIdSize>>::from(pskid))?;
+                    }
+                    ctx.absorb(External(<&NBytes<psk::PskSize>>::from(psk)))?
                         .commit()?
                         .mask(&self.key)
                 })
             })?
             .skip(repeated_ke_pks)?
             .repeated(self.ke_pks.clone().into_iter(), |ctx, (sig_pk, ke_pk)| {
-                ctx.fork(|ctx|
-                         ctx
-                         .absorb(sig_pk)?
-                         .x25519(ke_pk, &self.key)
-                )
+                ctx.fork(|ctx| {
+                    if self.anonymous {
+                        let mut eph_sk_bytes = [0_u8; 32];
+                        eph_sk_bytes.copy_from_slice(prng::random_key().as_slice());
+                        let eph_ke_sk = x25519::StaticSecret::from(eph_sk_bytes);
+                        let eph_ke_pk = x25519::PublicKey::from(&eph_ke_sk);
+                        ctx.absorb(&eph_ke_pk)?;
+                        let tag = blind_tag(&ctx.spongos, eph_ke_sk.diffie_hellman(ke_pk).as_bytes());
+                        ctx.absorb(&tag)?
+                            .x25519(&eph_ke_sk, ke_pk)?
+                            .commit()?
+                            .mask(&self.key)
+                    } else {
+                        ctx
+                            .absorb(sig_pk)?
+                            .x25519(ke_pk, &self.key)
+                    }
+                })
             })?
-            .absorb(External(&self.key))?
-            .ed25519(self.sig_kp, HashSig)?
-            .commit()?;
+            .absorb(External(&self.key))?;
+        if !self.writers.is_empty() {
+            ctx.mask(&Size(self.writers.len()))?
+                .repeated(self.writers.iter(), |ctx, (pk, branches)| {
+                    ctx.mask(*pk)?;
+                    match branches {
+                        None => ctx.mask(&Uint8(0)),
+                        Some(branches) => ctx
+                            .mask(&Uint8(1))?
+                            .mask(&Size(branches.len()))?
+                            .repeated(branches.iter(), |ctx, branch| {
+                                ctx.absorb(<&Fallback<<Link as HasLink>::Rel>>::from(branch))
+                            }),
+                    }
+                })?;
+        }
+        if let Some(sig_kp) = self.sig_kp {
+            ctx.ed25519(sig_kp, HashSig)?;
+        }
+        ctx.commit()?;
         Ok(ctx)
     }
 }
@@ -171,13 +355,26 @@ where
 pub struct ContentUnwrap<'a, F, Link: HasLink, LookupArg: 'a, LookupPsk, LookupKeSk> {
     pub link: <Link as HasLink>::Rel,
     pub nonce: NBytes<U16>, // TODO: unify with spongos::Spongos::<F>::NONCE_SIZE)
+    /// Content version taken from the message header.
+    pub(crate) content_version: u8,
     pub(crate) lookup_arg: &'a LookupArg,
     pub(crate) lookup_psk: LookupPsk,
     pub(crate) ke_pk: ed25519::PublicKey,
     pub(crate) lookup_ke_sk: LookupKeSk,
     pub(crate) ke_pks: Vec<ed25519::PublicKey>,
+    /// Identifiers of recipient's PSKs, tried against blinded tags in anonymous keyload.
+    pub(crate) psk_ids: psk::PskIds,
+    /// Recipient's own public key, tried against blinded tags in anonymous keyload.
+    pub(crate) own_ke_pk: &'a ed25519::PublicKey,
     pub key: Option<NBytes<U32>>, // TODO: unify with spongos::Spongos::<F>::KEY_SIZE
     pub(crate) sig_pk: &'a ed25519::PublicKey,
+    /// Keyload signature has been verified with `sig_pk`.
+    pub signed: bool,
+    /// Recipients identifiers were blinded.
+    pub anonymous: bool,
+    /// Writers together with the branches they can publish in (all branches if None),
+    /// None if the keyload carries no roles.
+    pub writers: Option<Vec<(ed25519::PublicKey, Option<Vec<<Link as HasLink>::Rel>>)>>,
     _phantom: core::marker::PhantomData<(F, Link)>,
 }
 
@@ -185,27 +382,36 @@ impl<'a, F, Link, LookupArg, LookupPsk, LookupKeSk> ContentUnwrap<'a, F, Link, L
 where
     F: PRP,
     Link: HasLink,
-    <Link as HasLink>::Rel: Eq + Default + SkipFallback<F>,
+    <Link as HasLink>::Rel: Eq + Default + SkipFallback<F> + AbsorbFallback<F>,
     LookupArg: 'a,
     LookupPsk: for<'b> Fn(&'b LookupArg, &psk::PskId) -> Option<&'b psk::Psk>,
     LookupKeSk: for<'b> Fn(&'b LookupArg, &ed25519::PublicKey) -> Option<&'b x25519::StaticSecret>,
 {
     pub fn new(
+        content_version: u8,
         lookup_arg: &'a LookupArg,
         lookup_psk: LookupPsk,
         lookup_ke_sk: LookupKeSk,
+        psk_ids: psk::PskIds,
+        own_ke_pk: &'a ed25519::PublicKey,
         sig_pk: &'a ed25519::PublicKey,
     ) -> Self {
         Self {
             link: <<Link as HasLink>::Rel as Default>::default(),
             nonce: NBytes::default(),
+            content_version,
             lookup_arg,
             lookup_psk,
             ke_pk: ed25519::PublicKey::default(),
             lookup_ke_sk,
             ke_pks: Vec::new(),
+            psk_ids,
+            own_ke_pk,
             key: None,
             sig_pk,
+            signed: false,
+            anonymous: false,
+            writers: None,
             _phantom: core::marker::PhantomData,
         }
     }
@@ -216,7 +422,7 @@ impl<'a, F, Link, Store, LookupArg, LookupPsk, LookupKeSk> message::ContentUnwra
 where
     F: PRP + Clone,
     Link: HasLink,
-    <Link as HasLink>::Rel: Eq + Default + SkipFallback<F>,
+    <Link as HasLink>::Rel: Eq + Default + SkipFallback<F> + AbsorbFallback<F>,
     Store: LinkStore<F, <Link as HasLink>::Rel>,
     LookupArg: 'a,
     LookupPsk: for<'b> Fn(&'b LookupArg, &psk::PskId) -> Option<&'b psk::Psk>,
@@ -230,16 +436,45 @@ where
         let mut repeated_psks = Size(0);
         let mut repeated_ke_pks = Size(0);
         let mut pskid = psk::PskId::default();
+        let mut tag = NBytes::<BlindTagSize>::default();
+        // Legacy keyloads carry no flags and are always signed.
+        let mut flags = Uint8(FLAG_SIGNED);
 
         ctx
             .join(store, &mut self.link)?
-            .absorb(&mut self.nonce)?
+            .absorb(&mut self.nonce)?;
+        match self.content_version {
+            CONTENT_VERSION_LEGACY => {}
+            CONTENT_VERSION_FLAGS => {
+                ctx.absorb(&mut flags)?
+                    .guard(
+                        flags.0 & !(FLAG_SIGNED | FLAG_ANONYMOUS | FLAG_ROLES) == 0,
+                        InvalidBitReservation,
+                    )?;
+            }
+            version => return err!(InvalidContentVersion(CONTENT_VERSION_FLAGS, version)),
+        }
+        self.anonymous = flags.0 & FLAG_ANONYMOUS != 0;
+
+        ctx
             .skip(&mut repeated_psks)?
             .repeated(repeated_psks, |ctx| {
                 if self.key.is_none() {
                     ctx.fork(|ctx| {
-                        ctx.mask(<&mut NBytes<psk::PskIdSize>>::from(&mut pskid))?;
-                        if let Some(psk) = (self.lookup_psk)(self.lookup_arg, &pskid) {
+                        let found_psk = if self.anonymous {
+                            let blind = ctx.spongos.fork();
+                            ctx.absorb(&mut tag)?;
+                            let lookup_arg = self.lookup_arg;
+                            let lookup_psk = &self.lookup_psk;
+                            self.psk_ids
+                                .iter()
+                                .filter_map(|pskid| (lookup_psk)(lookup_arg, pskid))
+                                .find(|psk| blind_tag(&blind, psk) == tag)
+                        } else {
+                            ctx.mask(<&mut NBytes<psk::PskIdSize>>::from(&mut pskid))?;
+                            (self.lookup_psk)(self.lookup_arg, &pskid)
+                        };
+                        if let Some(psk) = found_psk {
                             let mut key = NBytes::<U32>::default();
                             ctx.absorb(External(<&NBytes<psk::PskSize>>::from(psk)))?
                                 .commit()?
@@ -254,41 +489,359 @@ where
                     })
                 } else {
                     // Drop entire fork.
-                    let n = Size(psk::PSKID_SIZE + 0 + 0 + spongos::KeySize::<F>::USIZE);
+                    let id_size = if self.anonymous { BlindTagSize::USIZE } else { psk::PSKID_SIZE };
+                    let n = Size(id_size + 0 + 0 + spongos::KeySize::<F>::USIZE);
                     ctx.drop(n)
                 }
             })?
             .skip(&mut repeated_ke_pks)?
             .repeated(repeated_ke_pks, |ctx| {
                 ctx.fork(|ctx| {
-                    let mut ke_pk = ed25519::PublicKey::default();
-                    ctx.absorb(&mut ke_pk)?;
-                    if let Some(ke_sk) = (self.lookup_ke_sk)(self.lookup_arg, &ke_pk) {
-                        let mut key = NBytes::<U32>::default();
-                        ctx.x25519(ke_sk, &mut key)?;
-                        self.key = Some(key);
-                        // Save the relevant public key
-                        self.ke_pk = ke_pk.clone();
-                        self.ke_pks.push(ke_pk);
-                        Ok(ctx)
+                    if self.anonymous {
+                        let mut eph_ke_pk = x25519::PublicKey::from([0_u8; 32]);
+                        ctx.absorb(&mut eph_ke_pk)?;
+                        let blind = ctx.spongos.fork();
+                        ctx.absorb(&mut tag)?;
+                        let ke_sk = if self.key.is_none() {
+                            (self.lookup_ke_sk)(self.lookup_arg, self.own_ke_pk)
+                                .filter(|ke_sk| blind_tag(&blind, ke_sk.diffie_hellman(&eph_ke_pk).as_bytes()) == tag)
+                        } else {
+                            None
+                        };
+                        if let Some(ke_sk) = ke_sk {
+                            let mut key = NBytes::<U32>::default();
+                            ctx.x25519(ke_sk, &eph_ke_pk)?
+                                .commit()?
+                                .mask(&mut key)?;
+                            self.key = Some(key);
+                            // Only own public key is revealed to the recipient
+                            self.ke_pk = self.own_ke_pk.clone();
+                            self.ke_pks.push(self.own_ke_pk.clone());
+                            Ok(ctx)
+                        } else {
+                            // Just drop the rest of the forked message so not to waste Spongos operations
+                            let n = Size(spongos::KeySize::<F>::USIZE);
+                            ctx.drop(n)
+                        }
                     } else {
-                        self.ke_pks.push(ke_pk);
-                        // Just drop the rest of the forked message so not to waste Spongos operations
-                        // TODO: key length
-                        let n = Size(64);
-                        ctx.drop(n)
+                        let mut ke_pk = ed25519::PublicKey::default();
+                        ctx.absorb(&mut ke_pk)?;
+                        if let Some(ke_sk) = (self.lookup_ke_sk)(self.lookup_arg, &ke_pk) {
+                            let mut key = NBytes::<U32>::default();
+                            ctx.x25519(ke_sk, &mut key)?;
+                            self.key = Some(key);
+                            // Save the relevant public key
+                            self.ke_pk = ke_pk.clone();
+                            self.ke_pks.push(ke_pk);
+                            Ok(ctx)
+                        } else {
+                            self.ke_pks.push(ke_pk);
+                            // Just drop the rest of the forked message so not to waste Spongos operations
+                            // TODO: key length
+                            let n = Size(64);
+                            ctx.drop(n)
+                        }
                     }
                 })
             })?
             //.guard(self.key.is_some(), "Key not found")?
         ;
         if let Some(ref key) = self.key {
-            ctx
-                .absorb(External(key))?
-                .ed25519(self.sig_pk, HashSig)?
-                .commit()?
-            ;
+            ctx.absorb(External(key))?;
+            if flags.0 & FLAG_ROLES != 0 {
+                let mut repeated_writers = Size(0);
+                let mut writers = Vec::new();
+                ctx.mask(&mut repeated_writers)?
+                    .repeated(repeated_writers, |ctx| {
+                        let mut pk = ed25519::PublicKey::default();
+                        let mut oneof_branches = Uint8(0);
+                        ctx.mask(&mut pk)?
+                            .mask(&mut oneof_branches)?
+                            .guard(oneof_branches.0 < 2, ValueOutOfRange(1, oneof_branches.0 as usize))?;
+                        let branches = if oneof_branches.0 == 1 {
+                            let mut repeated_branches = Size(0);
+                            let mut branches = Vec::new();
+                            ctx.mask(&mut repeated_branches)?
+                                .repeated(repeated_branches, |ctx| {
+                                    let mut branch = Fallback(<Link as HasLink>::Rel::default());
+                                    ctx.absorb(&mut branch)?;
+                                    branches.push(branch.0);
+                                    Ok(ctx)
+                                })?;
+                            Some(branches)
+                        } else {
+                            None
+                        };
+                        writers.push((pk, branches));
+                        Ok(ctx)
+                    })?;
+                self.writers = Some(writers);
+            }
+            if flags.0 & FLAG_SIGNED != 0 {
+                ctx.ed25519(self.sig_pk, HashSig)?;
+                self.signed = true;
+            }
+            ctx.commit()?;
         }
         Ok(ctx)
     }
 }
+
+#[cfg(all(test, feature = "std", feature = "tangle"))]
+mod test {
+    use super::*;
+    use iota_streams_app::{
+        message::{
+            ContentSizeof as _,
+            ContentUnwrap as _,
+            ContentWrap as _,
+        },
+        transport::tangle::{
+            MsgId,
+            TangleAddress,
+        },
+    };
+    use iota_streams_core::{
+        try_or,
+        Errors::{
+            InputStreamNotFullyConsumed,
+            KeyloadNotSigned,
+            OutputStreamNotFullyConsumed,
+            ValueMismatch,
+        },
+    };
+    use iota_streams_core_keccak::sponge::prp::keccak::KeccakF1600;
+    use iota_streams_ddml::link_store::SingleLinkStore;
+
+    type F = KeccakF1600;
+    type Store = SingleLinkStore<F, MsgId, ()>;
+
+    struct Keys {
+        pskid: psk::PskId,
+        psk: psk::Psk,
+    }
+
+    fn lookup_psk<'b>(keys: &'b Keys, pskid: &psk::PskId) -> Option<&'b psk::Psk> {
+        if keys.pskid == *pskid {
+            Some(&keys.psk)
+        } else {
+            None
+        }
+    }
+
+    fn lookup_ke_sk<'b>(_keys: &'b Keys, _ke_pk: &ed25519::PublicKey) -> Option<&'b x25519::StaticSecret> {
+        None
+    }
+
+    /// Keyload content as it was wrapped before flags were introduced, PSK recipient only.
+    fn sizeof_legacy(
+        link: &MsgId,
+        nonce: &NBytes<U16>,
+        key: &NBytes<U32>,
+        keys: &Keys,
+        sig_kp: &ed25519::Keypair,
+    ) -> Result<usize> {
+        let store = EmptyLinkStore::<F, MsgId, ()>::default();
+        let mut ctx = sizeof::Context::<F>::new();
+        ctx.join(&store, link)?
+            .absorb(nonce)?
+            .skip(Size(1))?
+            .fork(|ctx| {
+                ctx.mask(<&NBytes<psk::PskIdSize>>::from(&keys.pskid))?
+                    .absorb(External(<&NBytes<psk::PskSize>>::from(&keys.psk)))?
+                    .commit()?
+                    .mask(key)
+            })?
+            .skip(Size(0))?
+            .absorb(External(key))?
+            .ed25519(sig_kp, HashSig)?
+            .commit()?;
+        Ok(ctx.get_size())
+    }
+
+    fn wrap_legacy(
+        store: &Store,
+        link: &MsgId,
+        nonce: &NBytes<U16>,
+        key: &NBytes<U32>,
+        keys: &Keys,
+        sig_kp: &ed25519::Keypair,
+    ) -> Result<Vec<u8>> {
+        let mut buf = vec![0_u8; sizeof_legacy(link, nonce, key, keys, sig_kp)?];
+        let mut ctx = wrap::Context::<F, &mut [u8]>::new(&mut buf[..]);
+        ctx.join(store, link)?
+            .absorb(nonce)?
+            .skip(Size(1))?
+            .fork(|ctx| {
+                ctx.mask(<&NBytes<psk::PskIdSize>>::from(&keys.pskid))?
+                    .absorb(External(<&NBytes<psk::PskSize>>::from(&keys.psk)))?
+                    .commit()?
+                    .mask(key)
+            })?
+            .skip(Size(0))?
+            .absorb(External(key))?
+            .ed25519(sig_kp, HashSig)?
+            .commit()?;
+        try_or!(ctx.stream.is_empty(), OutputStreamNotFullyConsumed(ctx.stream.len()))?;
+        Ok(buf)
+    }
+
+    fn wrap_current(
+        store: &Store,
+        link: &MsgId,
+        nonce: &NBytes<U16>,
+        key: &NBytes<U32>,
+        keys: &Keys,
+        sig_kp: &ed25519::Keypair,
+        anonymous: bool,
+        writers: Vec<(&ed25519::PublicKey, Option<&[MsgId]>)>,
+    ) -> Result<(u8, Vec<u8>)> {
+        let psks = vec![(&keys.pskid, &keys.psk)];
+        let ke_pks: Vec<(ed25519::IPk, x25519::IPk)> = Vec::new();
+        let content = ContentWrap::<F, TangleAddress, _, _> {
+            link,
+            nonce: nonce.clone(),
+            key: key.clone(),
+            psks: psks.into_iter(),
+            ke_pks: ke_pks.into_iter(),
+            sig_kp: Some(sig_kp),
+            anonymous,
+            writers,
+            _phantom: core::marker::PhantomData,
+        };
+        let mut buf = vec![0_u8; content.sizeof(&mut sizeof::Context::<F>::new())?.get_size()];
+        let mut ctx = wrap::Context::<F, &mut [u8]>::new(&mut buf[..]);
+        content.wrap(store, &mut ctx)?;
+        try_or!(ctx.stream.is_empty(), OutputStreamNotFullyConsumed(ctx.stream.len()))?;
+        Ok((content.content_version(), buf))
+    }
+
+    fn unwrap_key(
+        store: &Store,
+        keys: &Keys,
+        sig_pk: &ed25519::PublicKey,
+        content_version: u8,
+        buf: &[u8],
+    ) -> Result<(NBytes<U32>, bool)> {
+        unwrap_roles(store, keys, sig_pk, content_version, buf).map(|(key, anonymous, _writers)| (key, anonymous))
+    }
+
+    fn unwrap_roles(
+        store: &Store,
+        keys: &Keys,
+        sig_pk: &ed25519::PublicKey,
+        content_version: u8,
+        buf: &[u8],
+    ) -> Result<(NBytes<U32>, bool, Option<Vec<(ed25519::PublicKey, Option<Vec<MsgId>>)>>)> {
+        let own_ke_pk = ed25519::PublicKey::default();
+        let mut content = ContentUnwrap::<
+            F,
+            TangleAddress,
+            Keys,
+            for<'c> fn(&'c Keys, &psk::PskId) -> Option<&'c psk::Psk>,
+            for<'c> fn(&'c Keys, &ed25519::PublicKey) -> Option<&'c x25519::StaticSecret>,
+        >::new(
+            content_version,
+            keys,
+            lookup_psk,
+            lookup_ke_sk,
+            vec![keys.pskid.clone()],
+            &own_ke_pk,
+            sig_pk,
+        );
+        let mut ctx = unwrap::Context::<F, &[u8]>::new(buf);
+        content.unwrap(store, &mut ctx)?;
+        try_or!(ctx.stream.is_empty(), InputStreamNotFullyConsumed(ctx.stream.len()))?;
+        try_or!(content.signed, KeyloadNotSigned)?;
+        Ok((content.key.unwrap_or_default(), content.anonymous, content.writers))
+    }
+
+    fn legacy_keyload() -> Result<()> {
+        let prng = prng::dbg_init_str::<F>("KEYLOADLEGACYTEST");
+        let sig_kp = ed25519::Keypair::generate(&mut prng::Rng::new(prng.clone(), vec![0_u8]));
+        let keys = Keys {
+            pskid: psk::PskId::from_slice(&prng.gen_n(&[1_u8], psk::PSKID_SIZE)).clone(),
+            psk: psk::Psk::from_slice(&prng.gen_n(&[2_u8], psk::PSK_SIZE)).clone(),
+        };
+        let nonce = NBytes::<U16>::from(prng.gen_arr::<U16>(&[3_u8]));
+        let key = NBytes::<U32>::from(prng.gen_arr::<U32>(&[4_u8]));
+        let link = MsgId::from(&prng.gen_n(&[5_u8], 12)[..]);
+        let mut store = Store::default();
+        store.update(&link, spongos::Spongos::<F>::init(), ())?;
+
+        // Signed keyload with recipients in clear is wrapped with the legacy layout.
+        let legacy = wrap_legacy(&store, &link, &nonce, &key, &keys, &sig_kp)?;
+        let (version, current) = wrap_current(&store, &link, &nonce, &key, &keys, &sig_kp, false, Vec::new())?;
+        try_or!(
+            version == CONTENT_VERSION_LEGACY,
+            ValueMismatch(CONTENT_VERSION_LEGACY as usize, version as usize)
+        )?;
+        try_or!(legacy == current, ValueMismatch(legacy.len(), current.len()))?;
+
+        let (unwrapped_key, anonymous) = unwrap_key(&store, &keys, &sig_kp.public, CONTENT_VERSION_LEGACY, &legacy)?;
+        try_or!(unwrapped_key == key && !anonymous, KeyloadNotSigned)?;
+
+        // Anonymous keyload needs flags, hence the newer layout.
+        let (version, anonymous_keyload) = wrap_current(&store, &link, &nonce, &key, &keys, &sig_kp, true, Vec::new())?;
+        try_or!(
+            version == CONTENT_VERSION_FLAGS,
+            ValueMismatch(CONTENT_VERSION_FLAGS as usize, version as usize)
+        )?;
+        let (unwrapped_key, anonymous) = unwrap_key(&store, &keys, &sig_kp.public, version, &anonymous_keyload)?;
+        try_or!(unwrapped_key == key && anonymous, KeyloadNotSigned)?;
+
+        // Unknown content versions are rejected.
+        try_or!(
+            unwrap_key(&store, &keys, &sig_kp.public, CONTENT_VERSION_FLAGS + 1, &legacy).is_err(),
+            ValueMismatch(0, 1)
+        )?;
+        Ok(())
+    }
+
+    fn roles_keyload() -> Result<()> {
+        let prng = prng::dbg_init_str::<F>("KEYLOADROLESTEST");
+        let sig_kp = ed25519::Keypair::generate(&mut prng::Rng::new(prng.clone(), vec![0_u8]));
+        let writer_kp = ed25519::Keypair::generate(&mut prng::Rng::new(prng.clone(), vec![1_u8]));
+        let branch_writer_kp = ed25519::Keypair::generate(&mut prng::Rng::new(prng.clone(), vec![2_u8]));
+        let keys = Keys {
+            pskid: psk::PskId::from_slice(&prng.gen_n(&[3_u8], psk::PSKID_SIZE)).clone(),
+            psk: psk::Psk::from_slice(&prng.gen_n(&[4_u8], psk::PSK_SIZE)).clone(),
+        };
+        let nonce = NBytes::<U16>::from(prng.gen_arr::<U16>(&[5_u8]));
+        let key = NBytes::<U32>::from(prng.gen_arr::<U32>(&[6_u8]));
+        let link = MsgId::from(&prng.gen_n(&[7_u8], 12)[..]);
+        let branches = vec![MsgId::from(&prng.gen_n(&[8_u8], 12)[..])];
+        let mut store = Store::default();
+        store.update(&link, spongos::Spongos::<F>::init(), ())?;
+
+        let writers = vec![
+            (&writer_kp.public, None),
+            (&branch_writer_kp.public, Some(&branches[..])),
+        ];
+        let (version, keyload) = wrap_current(&store, &link, &nonce, &key, &keys, &sig_kp, false, writers)?;
+        try_or!(
+            version == CONTENT_VERSION_FLAGS,
+            ValueMismatch(CONTENT_VERSION_FLAGS as usize, version as usize)
+        )?;
+        let (unwrapped_key, _anonymous, writers) = unwrap_roles(&store, &keys, &sig_kp.public, version, &keyload)?;
+        try_or!(unwrapped_key == key, KeyloadNotSigned)?;
+        let expected = vec![(writer_kp.public, None), (branch_writer_kp.public, Some(branches))];
+        try_or!(writers.as_ref() == Some(&expected), ValueMismatch(2, 0))?;
+
+        // Keyloads without writers carry no roles.
+        let (version, keyload) = wrap_current(&store, &link, &nonce, &key, &keys, &sig_kp, false, Vec::new())?;
+        let (_unwrapped_key, _anonymous, writers) = unwrap_roles(&store, &keys, &sig_kp.public, version, &keyload)?;
+        try_or!(writers.is_none(), ValueMismatch(0, 1))?;
+        Ok(())
+    }
+
+    #[test]
+    fn unwrap_legacy_keyload() {
+        assert!(dbg!(legacy_keyload()).is_ok());
+    }
+
+    #[test]
+    fn unwrap_roles_keyload() {
+        assert!(dbg!(roles_keyload()).is_ok());
+    }
+}
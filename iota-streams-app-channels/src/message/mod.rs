@@ -18,6 +18,15 @@ pub mod subscribe;
 /// Sequence message.
 pub mod sequence;
 
+/// Unsubscribe message.
+pub mod unsubscribe;
+
+/// ChangeKey message.
+pub mod change_key;
+
+/// Permission message.
+pub mod permission;
+
 pub const ANNOUNCE: u8 = 0;
 pub const KEYLOAD: u8 = 1;
 pub const SEQUENCE: u8 = 2;
@@ -25,6 +34,5 @@ pub const SIGNED_PACKET: u8 = 3;
 pub const TAGGED_PACKET: u8 = 4;
 pub const SUBSCRIBE: u8 = 5;
 pub const UNSUBSCRIBE: u8 = 6;
-
-// Unsubscribe message.
-// pub mod unsubscribe;
+pub const CHANGE_KEY: u8 = 7;
+pub const PERMISSION: u8 = 8;
@@ -0,0 +1,182 @@
+//! `Permission` message content. The message is published by channel owner in order to
+//! assign writer or reader role to a participant, ie. to delegate publishing rights or to revoke
+//! them. Users accept `SignedPacket` messages only from the channel owner and writers.
+//! `TaggedPacket` messages can't be attributed to their sender and are accepted from any
+//! keyload recipient. Keyloads sent after the permission carry the roles as well.
+//!
+//! ```ddml
+//! message Permission {
+//!     join link msgid;
+//!     absorb u8 ed25519pk[32];
+//!     absorb u8 permission;
+//!     absorb oneof branch {
+//!         0:
+//!         1:
+//!             absorb link branch;
+//!     }
+//!     commit;
+//!     squeeze external u8 hash[64];
+//!     ed25519(hash) sig;
+//! }
+//! ```
+//!
+//! # Fields
+//!
+//! * `msgid` -- link to the base message.
+//!
+//! * `ed25519pk` -- Ed25519 public key of the participant.
+//!
+//! * `permission` -- `0` revokes write permission, `1` grants it.
+//!
+//! * `branch` -- optional link to the message packets must be linked to;
+//! if absent permission applies to all branches.
+//!
+//! * `hash` -- hash value to be signed.
+//!
+//! * `sig` -- signature generated with channel owner's Ed25519 private key.
+//!
+
+use iota_streams_core::{
+    Errors::{
+        InvalidPermission,
+        ValueOutOfRange,
+    },
+    Result,
+};
+use iota_streams_app::message::{
+    self,
+    HasLink,
+};
+use iota_streams_core::sponge::prp::PRP;
+use iota_streams_core_edsig::signature::ed25519;
+use iota_streams_ddml::{
+    command::*,
+    io,
+    link_store::{
+        EmptyLinkStore,
+        LinkStore,
+    },
+    types::*,
+};
+
+/// Participant can only read packets, write permission is revoked.
+pub const READ: u8 = 0;
+/// Participant can publish packets.
+pub const WRITE: u8 = 1;
+
+pub struct ContentWrap<'a, F, Link>
+where
+    Link: HasLink,
+    <Link as HasLink>::Rel: 'a,
+{
+    pub(crate) link: &'a <Link as HasLink>::Rel,
+    pub(crate) pk: &'a ed25519::PublicKey,
+    pub(crate) permission: u8,
+    pub(crate) branch: Option<&'a <Link as HasLink>::Rel>,
+    pub(crate) sig_kp: &'a ed25519::Keypair,
+    pub(crate) _phantom: core::marker::PhantomData<(F, Link)>,
+}
+
+impl<'a, F, Link> message::ContentSizeof<F> for ContentWrap<'a, F, Link>
+where
+    F: PRP,
+    Link: HasLink,
+    <Link as HasLink>::Rel: 'a + Eq + SkipFallback<F> + AbsorbFallback<F>,
+{
+    fn sizeof<'c>(&self, ctx: &'c mut sizeof::Context<F>) -> Result<&'c mut sizeof::Context<F>> {
+        let store = EmptyLinkStore::<F, <Link as HasLink>::Rel, ()>::default();
+        let oneof_branch = Uint8(if self.branch.is_some() { 1 } else { 0 });
+        ctx.join(&store, self.link)?
+            .absorb(self.pk)?
+            .absorb(Uint8(self.permission))?
+            .absorb(&oneof_branch)?;
+        if let Some(branch) = self.branch {
+            ctx.absorb(<&Fallback<<Link as HasLink>::Rel>>::from(branch))?;
+        }
+        ctx.ed25519(self.sig_kp, HashSig)?;
+        Ok(ctx)
+    }
+}
+
+impl<'a, F, Link, Store> message::ContentWrap<F, Store> for ContentWrap<'a, F, Link>
+where
+    F: PRP,
+    Link: HasLink,
+    <Link as HasLink>::Rel: 'a + Eq + SkipFallback<F> + AbsorbFallback<F>,
+    Store: LinkStore<F, <Link as HasLink>::Rel>,
+{
+    fn wrap<'c, OS: io::OStream>(
+        &self,
+        store: &Store,
+        ctx: &'c mut wrap::Context<F, OS>,
+    ) -> Result<&'c mut wrap::Context<F, OS>> {
+        let oneof_branch = Uint8(if self.branch.is_some() { 1 } else { 0 });
+        ctx.join(store, self.link)?
+            .absorb(self.pk)?
+            .absorb(Uint8(self.permission))?
+            .absorb(&oneof_branch)?;
+        if let Some(branch) = self.branch {
+            ctx.absorb(<&Fallback<<Link as HasLink>::Rel>>::from(branch))?;
+        }
+        ctx.ed25519(self.sig_kp, HashSig)?;
+        Ok(ctx)
+    }
+}
+
+pub struct ContentUnwrap<F, Link: HasLink> {
+    pub(crate) link: <Link as HasLink>::Rel,
+    pub(crate) pk: ed25519::PublicKey,
+    pub(crate) permission: Uint8,
+    pub(crate) branch: Option<<Link as HasLink>::Rel>,
+    /// Channel owner's public key, the signature is verified against it.
+    pub(crate) sig_pk: ed25519::PublicKey,
+    pub(crate) _phantom: core::marker::PhantomData<(F, Link)>,
+}
+
+impl<F, Link> ContentUnwrap<F, Link>
+where
+    Link: HasLink,
+    <Link as HasLink>::Rel: Eq + Default + SkipFallback<F>,
+{
+    pub fn new(sig_pk: ed25519::PublicKey) -> Self {
+        Self {
+            link: <<Link as HasLink>::Rel as Default>::default(),
+            pk: ed25519::PublicKey::default(),
+            permission: Uint8(READ),
+            branch: None,
+            sig_pk,
+            _phantom: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<F, Link, Store> message::ContentUnwrap<F, Store> for ContentUnwrap<F, Link>
+where
+    F: PRP,
+    Link: HasLink,
+    <Link as HasLink>::Rel: Eq + Default + SkipFallback<F> + AbsorbFallback<F>,
+    Store: LinkStore<F, <Link as HasLink>::Rel>,
+{
+    fn unwrap<'c, IS: io::IStream>(
+        &mut self,
+        store: &Store,
+        ctx: &'c mut unwrap::Context<F, IS>,
+    ) -> Result<&'c mut unwrap::Context<F, IS>> {
+        let mut oneof_branch = Uint8(0);
+        ctx.join(store, &mut self.link)?
+            .absorb(&mut self.pk)?
+            .absorb(&mut self.permission)?
+            .guard(self.permission.0 == READ || self.permission.0 == WRITE, InvalidPermission(self.permission.0))?
+            .absorb(&mut oneof_branch)?
+            .guard(oneof_branch.0 < 2, ValueOutOfRange(1, oneof_branch.0 as usize))?;
+        self.branch = if oneof_branch.0 == 1 {
+            let mut branch = <<Link as HasLink>::Rel as Default>::default();
+            ctx.absorb(<&mut Fallback<<Link as HasLink>::Rel>>::from(&mut branch))?;
+            Some(branch)
+        } else {
+            None
+        };
+        ctx.ed25519(&self.sig_pk, HashSig)?;
+        Ok(ctx)
+    }
+}
@@ -6,6 +6,9 @@
 //! ```pb3
 //! message SignedPacket {
 //!     join link msgid;
+//!     if (content_version == 1) {
+//!         absorb u64 timestamp;
+//!     }
 //!     absorb bytes public_payload;
 //!     mask bytes masked_payload;
 //!     commit;
@@ -16,7 +19,16 @@
 //!
 //! # Fields
 //!
-//! * `msgid` -- link to the base message.
+//! * `msgid` -- link to the base message. In a ratcheted channel the spongos state of the base
+//! message is advanced with `Spongos::ratchet` up to the sequence number of the packet before it
+//! is joined, so each packet is wrapped with its own one-way derived state. Recipients advance
+//! their stored state of the base message past each handled packet, see `RatchetLinkStore`.
+//!
+//! * `content_version` -- version from the message header, `CONTENT_VERSION_TIMESTAMP` if the packet is
+//! timestamped, packets without timestamp keep the original layout of `CONTENT_VERSION_PLAIN`.
+//!
+//! * `timestamp` -- time of publishing asserted by the sender, milliseconds since Unix epoch.
+//! It's authenticated by the signature.
 //!
 //! * `public_payload` -- public part of payload.
 //!
@@ -27,7 +39,12 @@
 //! * `sig` -- message signature generated with one of channel owner's private key.
 //!
 
-use iota_streams_core::Result;
+use iota_streams_core::{
+    err,
+    Errors::InvalidContentVersion,
+    Result,
+    LOCATION_LOG,
+};
 use iota_streams_app::message::{
     self,
     HasLink,
@@ -40,22 +57,47 @@ use iota_streams_ddml::{
     link_store::{
         EmptyLinkStore,
         LinkStore,
+        RatchetLinkStore,
     },
     types::*,
 };
 
+/// Original packet layout without timestamp.
+pub const CONTENT_VERSION_PLAIN: u8 = 0;
+/// Packet layout with timestamp following the link.
+pub const CONTENT_VERSION_TIMESTAMP: u8 = 1;
+
 pub struct ContentWrap<'a, F, Link>
 where
     Link: HasLink,
     <Link as HasLink>::Rel: 'a,
 {
     pub(crate) link: &'a <Link as HasLink>::Rel,
+    /// Ratchet positions of the stored spongos states of ratcheted links.
+    pub(crate) ratchet_positions: &'a [(<Link as HasLink>::Rel, u64)],
+    /// Ratchet position of the state of `link` the packet is joined with.
+    pub(crate) ratchet: u64,
+    pub(crate) timestamp: Option<u64>,
     pub(crate) public_payload: &'a Bytes,
     pub(crate) masked_payload: &'a Bytes,
     pub(crate) sig_kp: &'a ed25519::Keypair,
     pub(crate) _phantom: core::marker::PhantomData<(F, Link)>,
 }
 
+impl<'a, F, Link> ContentWrap<'a, F, Link>
+where
+    Link: HasLink,
+{
+    /// Content version to be put into the message header.
+    pub fn content_version(&self) -> u8 {
+        if self.timestamp.is_some() {
+            CONTENT_VERSION_TIMESTAMP
+        } else {
+            CONTENT_VERSION_PLAIN
+        }
+    }
+}
+
 impl<'a, F, Link> message::ContentSizeof<F> for ContentWrap<'a, F, Link>
 where
     F: PRP,
@@ -65,8 +107,11 @@ where
     fn sizeof<'c>(&self, ctx: &'c mut sizeof::Context<F>) -> Result<&'c mut sizeof::Context<F>> {
         let store = EmptyLinkStore::<F, <Link as HasLink>::Rel, ()>::default();
         ctx.join(&store, self.link)?
-            .absorb(&self.sig_kp.public)?
-            .absorb(self.public_payload)?
+            .absorb(&self.sig_kp.public)?;
+        if let Some(timestamp) = self.timestamp {
+            ctx.absorb(Uint64(timestamp))?;
+        }
+        ctx.absorb(self.public_payload)?
             .mask(self.masked_payload)?
             .ed25519(self.sig_kp, HashSig)?;
         // TODO: Is both public and masked payloads are ok? Leave public only or masked only?
@@ -86,9 +131,12 @@ where
         store: &Store,
         ctx: &'c mut wrap::Context<F, OS>,
     ) -> Result<&'c mut wrap::Context<F, OS>> {
-        ctx.join(store, self.link)?
-            .absorb(&self.sig_kp.public)?
-            .absorb(self.public_payload)?
+        ctx.join(&RatchetLinkStore::new(store, self.ratchet_positions, self.ratchet), self.link)?
+            .absorb(&self.sig_kp.public)?;
+        if let Some(timestamp) = self.timestamp {
+            ctx.absorb(Uint64(timestamp))?;
+        }
+        ctx.absorb(self.public_payload)?
             .mask(self.masked_payload)?
             .ed25519(self.sig_kp, HashSig)?;
         Ok(ctx)
@@ -97,6 +145,9 @@ where
 
 pub struct ContentUnwrap<F, Link: HasLink> {
     pub(crate) link: <Link as HasLink>::Rel,
+    /// Content version taken from the message header.
+    pub(crate) content_version: u8,
+    pub(crate) timestamp: Option<u64>,
     pub(crate) public_payload: Bytes,
     pub(crate) masked_payload: Bytes,
     pub(crate) sig_pk: ed25519::PublicKey,
@@ -111,6 +162,8 @@ where
     fn default() -> Self {
         Self {
             link: <<Link as HasLink>::Rel as Default>::default(),
+            content_version: CONTENT_VERSION_PLAIN,
+            timestamp: None,
             public_payload: Bytes::default(),
             masked_payload: Bytes::default(),
             sig_pk: ed25519::PublicKey::default(),
@@ -132,8 +185,17 @@ where
         ctx: &'c mut unwrap::Context<F, IS>,
     ) -> Result<&'c mut unwrap::Context<F, IS>> {
         ctx.join(store, &mut self.link)?
-            .absorb(&mut self.sig_pk)?
-            .absorb(&mut self.public_payload)?
+            .absorb(&mut self.sig_pk)?;
+        self.timestamp = match self.content_version {
+            CONTENT_VERSION_PLAIN => None,
+            CONTENT_VERSION_TIMESTAMP => {
+                let mut timestamp = Uint64(0);
+                ctx.absorb(&mut timestamp)?;
+                Some(timestamp.0)
+            }
+            version => return err!(InvalidContentVersion(CONTENT_VERSION_TIMESTAMP, version)),
+        };
+        ctx.absorb(&mut self.public_payload)?
             .mask(&mut self.masked_payload)?
             .ed25519(&self.sig_pk, HashSig)?;
         Ok(ctx)
@@ -1,20 +1,44 @@
 //! `TaggedPacket` message content. The message may be linked to any other message
 //! in the channel. It contains both plain and masked payloads. The message is
-//! authenticated with MAC and can be published by channel owner or by a recipient.
+//! authenticated with MAC and can be published by channel owner or by a recipient with writer role.
 //!
 //! ```pb3
 //! message TaggedPacket {
 //!     join link msgid;
+//!     if (content_version & CONTENT_VERSION_SENDER) {
+//!         absorb ed25519pk sender;
+//!     }
+//!     if (content_version & CONTENT_VERSION_TIMESTAMP) {
+//!         absorb u64 timestamp;
+//!     }
 //!     absorb bytes public_payload;
 //!     mask bytes masked_payload;
 //!     commit;
 //!     squeeze byte mac[81];
+//!     if (content_version & CONTENT_VERSION_SENDER) {
+//!         squeeze external byte hash[78];
+//!         mssig(hash) sig;
+//!     }
 //! }
 //! ```
 //!
 //! # Fields
 //!
-//! * `msgid` -- link to the base message.
+//! * `msgid` -- link to the base message. In a ratcheted channel the spongos state of the base
+//! message is advanced with `Spongos::ratchet` up to the sequence number of the packet before it
+//! is joined, so each packet is wrapped with its own one-way derived state. Recipients advance
+//! their stored state of the base message past each handled packet, see `RatchetLinkStore`.
+//!
+//! * `content_version` -- version from the message header, `CONTENT_VERSION_SENDER` bit is set for the
+//! packets carrying their sender, `CONTENT_VERSION_TIMESTAMP` bit is set if the packet is timestamped.
+//! `CONTENT_VERSION_PLAIN` and `CONTENT_VERSION_TIMESTAMP` are the original layouts without sender.
+//!
+//! * `sender` -- public key of the sender. MAC is keyed with the keyload session key shared by all
+//! the recipients, so any of them could produce it. The sender is authenticated by the signature
+//! instead, which allows recipients to check the writer role of the sender.
+//!
+//! * `timestamp` -- time of publishing asserted by the sender, milliseconds since Unix epoch.
+//! It's authenticated with MAC.
 //!
 //! * `public_payload` -- public part of payload.
 //!
@@ -22,8 +46,17 @@
 //!
 //! * `mac` -- MAC of the message.
 //!
+//! * `hash` -- hash value to be signed.
+//!
+//! * `sig` -- message signature generated with the sender's private key.
+//!
 
-use iota_streams_core::Result;
+use iota_streams_core::{
+    try_or,
+    Errors::InvalidContentVersion,
+    Result,
+    LOCATION_LOG,
+};
 use iota_streams_app::message::{
     self,
     HasLink,
@@ -35,27 +68,56 @@ use iota_streams_core::{
         spongos,
     },
 };
+use iota_streams_core_edsig::signature::ed25519;
 use iota_streams_ddml::{
     command::*,
     io,
     link_store::{
         EmptyLinkStore,
         LinkStore,
+        RatchetLinkStore,
     },
     types::*,
 };
 
+/// Original packet layout without timestamp.
+pub const CONTENT_VERSION_PLAIN: u8 = 0;
+/// Packet layout with timestamp following the link, this bit can be combined with `CONTENT_VERSION_SENDER`.
+pub const CONTENT_VERSION_TIMESTAMP: u8 = 1;
+/// Packet layout with sender public key following the link and signature following the MAC.
+pub const CONTENT_VERSION_SENDER: u8 = 2;
+
 pub struct ContentWrap<'a, F, Link>
 where
     Link: HasLink,
     <Link as HasLink>::Rel: 'a,
 {
     pub(crate) link: &'a <Link as HasLink>::Rel,
+    /// Ratchet positions of the stored spongos states of ratcheted links.
+    pub(crate) ratchet_positions: &'a [(<Link as HasLink>::Rel, u64)],
+    /// Ratchet position of the state of `link` the packet is joined with.
+    pub(crate) ratchet: u64,
+    pub(crate) timestamp: Option<u64>,
     pub(crate) public_payload: &'a Bytes,
     pub(crate) masked_payload: &'a Bytes,
+    pub(crate) sig_kp: &'a ed25519::Keypair,
     pub(crate) _phantom: core::marker::PhantomData<(F, Link)>,
 }
 
+impl<'a, F, Link> ContentWrap<'a, F, Link>
+where
+    Link: HasLink,
+{
+    /// Content version to be put into the message header.
+    pub fn content_version(&self) -> u8 {
+        if self.timestamp.is_some() {
+            CONTENT_VERSION_SENDER | CONTENT_VERSION_TIMESTAMP
+        } else {
+            CONTENT_VERSION_SENDER
+        }
+    }
+}
+
 impl<'a, F, Link> message::ContentSizeof<F> for ContentWrap<'a, F, Link>
 where
     F: PRP,
@@ -66,10 +128,15 @@ where
         let store = EmptyLinkStore::<F, <Link as HasLink>::Rel, ()>::default();
         let mac = Mac(spongos::MacSize::<F>::USIZE);
         ctx.join(&store, self.link)?
-            .absorb(self.public_payload)?
+            .absorb(&self.sig_kp.public)?;
+        if let Some(timestamp) = self.timestamp {
+            ctx.absorb(Uint64(timestamp))?;
+        }
+        ctx.absorb(self.public_payload)?
             .mask(self.masked_payload)?
             .commit()?
-            .squeeze(&mac)?;
+            .squeeze(&mac)?
+            .ed25519(self.sig_kp, HashSig)?;
         // TODO: Is bot public and masked payloads are ok? Leave public only or masked only?
         Ok(ctx)
     }
@@ -88,12 +155,16 @@ where
         ctx: &'c mut wrap::Context<F, OS>,
     ) -> Result<&'c mut wrap::Context<F, OS>> {
         let mac = Mac(spongos::MacSize::<F>::USIZE);
-        ctx
-            .join(store, self.link)?
-            .absorb(self.public_payload)?
+        ctx.join(&RatchetLinkStore::new(store, self.ratchet_positions, self.ratchet), self.link)?
+            .absorb(&self.sig_kp.public)?;
+        if let Some(timestamp) = self.timestamp {
+            ctx.absorb(Uint64(timestamp))?;
+        }
+        ctx.absorb(self.public_payload)?
             .mask(self.masked_payload)?
             .commit()?
-            .squeeze(&mac)?;
+            .squeeze(&mac)?
+            .ed25519(self.sig_kp, HashSig)?;
         Ok(ctx)
     }
 }
@@ -101,6 +172,11 @@ where
 // TODO: factor out `public_payload` and `masked_payload` into `pub struct Content`
 pub struct ContentUnwrap<F, Link: HasLink> {
     pub(crate) link: <Link as HasLink>::Rel,
+    /// Content version taken from the message header.
+    pub(crate) content_version: u8,
+    /// Public key of the sender, `None` for the layouts without sender.
+    pub(crate) sender: Option<ed25519::PublicKey>,
+    pub(crate) timestamp: Option<u64>,
     pub(crate) public_payload: Bytes,
     pub(crate) masked_payload: Bytes,
     pub(crate) _phantom: core::marker::PhantomData<(F, Link)>,
@@ -114,6 +190,9 @@ where
     pub fn new() -> Self {
         Self {
             link: <<Link as HasLink>::Rel as Default>::default(),
+            content_version: CONTENT_VERSION_PLAIN,
+            sender: None,
+            timestamp: None,
             public_payload: Bytes::default(),
             masked_payload: Bytes::default(),
             _phantom: core::marker::PhantomData,
@@ -134,12 +213,33 @@ where
         ctx: &'c mut unwrap::Context<F, IS>,
     ) -> Result<&'c mut unwrap::Context<F, IS>> {
         let mac = Mac(spongos::MacSize::<F>::USIZE);
-        ctx
-            .join(store, &mut self.link)?
-            .absorb(&mut self.public_payload)?
+        let version = self.content_version;
+        try_or!(
+            version & !(CONTENT_VERSION_SENDER | CONTENT_VERSION_TIMESTAMP) == 0,
+            InvalidContentVersion(CONTENT_VERSION_SENDER | CONTENT_VERSION_TIMESTAMP, version)
+        )?;
+        ctx.join(store, &mut self.link)?;
+        self.sender = if version & CONTENT_VERSION_SENDER != 0 {
+            let mut sender = ed25519::PublicKey::default();
+            ctx.absorb(&mut sender)?;
+            Some(sender)
+        } else {
+            None
+        };
+        self.timestamp = if version & CONTENT_VERSION_TIMESTAMP != 0 {
+            let mut timestamp = Uint64(0);
+            ctx.absorb(&mut timestamp)?;
+            Some(timestamp.0)
+        } else {
+            None
+        };
+        ctx.absorb(&mut self.public_payload)?
             .mask(&mut self.masked_payload)?
             .commit()?
             .squeeze(&mac)?;
+        if let Some(sender) = &self.sender {
+            ctx.ed25519(sender, HashSig)?;
+        }
         Ok(ctx)
     }
 }
@@ -0,0 +1,418 @@
+//! Splitting of large messages into a sequence of linked payload frames.
+//!
+//! A wrapped message which doesn't fit into a single frame is published as
+//! `payload_frame_count` frames. The first frame is published at the message link
+//! and carries the beginning of the message body: `HDF` with `payload_frame_count`
+//! set and `PCF` with `frame_type` equal to `INIT_PCF_ID`. Each of the following
+//! frames is published at a uniform link generated from the link of the previous
+//! frame and the frame number, and carries the next chunk of the message body:
+//!
+//! ```ddml
+//! message PayloadFrame {
+//!     absorb external link prev;
+//!     absorb u8 frame_type;
+//!     skip u8 payload_frame_num[3];
+//!     absorb size chunk_size;
+//!     absorb u8 chunk[chunk_size];
+//!     commit;
+//!     squeeze u8 mac[32];
+//! }
+//! ```
+//!
+//! # Fields
+//!
+//! * `prev` -- link of the previous frame.
+//!
+//! * `frame_type` -- `FINAL_PCF_ID` for the last frame, `INTER_PCF_ID` otherwise.
+//!
+//! * `payload_frame_num` -- frame number, the first frame has number 1.
+//!
+//! * `chunk` -- chunk of the message body.
+//!
+//! * `mac` -- integrity check of the frame and its position in the sequence.
+//!
+//! The spongos state a frame is wrapped with is forked from the state of the previous frame,
+//! the state of the first frame is obtained by absorbing its whole body into a key state supplied
+//! by the application. Hence `mac` binds the frame to the message and to all of the frames
+//! preceding it, and only the holders of the key state can produce it. Anyone can publish at the
+//! link of a frame, so a receiver picks among the messages found there the one with the valid `mac`.
+//!
+//! When the key state is public the frames can be forged. Frames are joined into the original
+//! message body before it is unwrapped, so a forged frame then makes the message content fail
+//! its own authenticity check.
+
+use iota_streams_core::{
+    prelude::{
+        typenum::Unsigned as _,
+        Vec,
+    },
+    sponge::{
+        prp::PRP,
+        spongos,
+    },
+    err,
+    try_or,
+    Errors::*,
+    Result,
+    LOCATION_LOG,
+};
+use iota_streams_ddml::{
+    command::*,
+    io,
+    types::*,
+};
+
+use super::*;
+
+/// Chunk of message body carried by a payload frame.
+struct Chunk(Bytes);
+
+impl<F: PRP> ContentSizeof<F> for Chunk {
+    fn sizeof<'c>(&self, ctx: &'c mut sizeof::Context<F>) -> Result<&'c mut sizeof::Context<F>> {
+        let mac = Mac(spongos::MacSize::<F>::USIZE);
+        ctx.absorb(&self.0)?.commit()?.squeeze(&mac)?;
+        Ok(ctx)
+    }
+}
+
+impl<F: PRP, Store> ContentWrap<F, Store> for Chunk {
+    fn wrap<'c, OS: io::OStream>(
+        &self,
+        _store: &Store,
+        ctx: &'c mut wrap::Context<F, OS>,
+    ) -> Result<&'c mut wrap::Context<F, OS>> {
+        let mac = Mac(spongos::MacSize::<F>::USIZE);
+        ctx.absorb(&self.0)?.commit()?.squeeze(&mac)?;
+        Ok(ctx)
+    }
+}
+
+impl<F: PRP, Store> ContentUnwrap<F, Store> for Chunk {
+    fn unwrap<'c, IS: io::IStream>(
+        &mut self,
+        _store: &Store,
+        ctx: &'c mut unwrap::Context<F, IS>,
+    ) -> Result<&'c mut unwrap::Context<F, IS>> {
+        let mac = Mac(spongos::MacSize::<F>::USIZE);
+        ctx.absorb(&mut self.0)?.commit()?.squeeze(&mac)?;
+        Ok(ctx)
+    }
+}
+
+/// Size of the chunks carried by the frames following the first one, so that none of the frames
+/// exceeds `frame_size` bytes. The first frame carries `frame_size` bytes of the message body.
+fn chunk_size<F: PRP>(frame_size: usize) -> Result<usize> {
+    let pcf = PCF::new(FINAL_PCF_ID, 2, Chunk(Bytes(vec![0; frame_size])))?;
+    let mut ctx = sizeof::Context::<F>::new();
+    pcf.sizeof(&mut ctx)?;
+    let overhead = ctx.get_size() - frame_size;
+    try_or!(overhead < frame_size, PayloadFrameSizeTooSmall(overhead + 1, frame_size))?;
+    Ok(frame_size - overhead)
+}
+
+/// Number of frames of at most `frame_size` bytes a message body of `body_size` bytes
+/// is split into.
+pub fn payload_frame_count<F: PRP>(body_size: usize, frame_size: usize) -> Result<usize> {
+    if frame_size == 0 || body_size <= frame_size {
+        return Ok(1);
+    }
+    let chunk_size = chunk_size::<F>(frame_size)?;
+    Ok(1 + (body_size - frame_size + chunk_size - 1) / chunk_size)
+}
+
+/// Smallest message body size not less than `body_size` which fills all of its frames of
+/// `frame_size` bytes.
+pub fn full_frames_size<F: PRP>(body_size: usize, frame_size: usize) -> Result<usize> {
+    if frame_size == 0 || body_size <= frame_size {
+        return Ok(frame_size);
+    }
+    let chunk_size = chunk_size::<F>(frame_size)?;
+    Ok(frame_size + (body_size - frame_size + chunk_size - 1) / chunk_size * chunk_size)
+}
+
+/// Spongos state the frames following the first frame `first` of a message are chained to.
+/// The `key` state is joined, so it works the same whether it's trimmed or not.
+fn first_frame_spongos<F: PRP>(key: &spongos::Spongos<F>, first: &[u8]) -> spongos::Spongos<F> {
+    let mut s = spongos::Spongos::init();
+    s.join(&mut key.fork());
+    s.absorb(first);
+    s.commit();
+    s
+}
+
+/// Link of the frame `payload_frame_num` following the frame published at `prev`.
+pub fn frame_link<Link, LG>(link_gen: &LG, prev: &Link, payload_frame_num: u32) -> Link
+where
+    Link: HasLink,
+    LG: LinkGenerator<Link>,
+{
+    link_gen.uniform_link_from(Cursor::new_at(prev.rel(), 0, payload_frame_num))
+}
+
+impl<F, Link> BinaryMessage<F, Link>
+where
+    F: PRP,
+    Link: HasLink + AbsorbExternalFallback<F>,
+{
+    /// Split message into frames of at most `frame_size` bytes according to `payload_frame_count`
+    /// of its header, the frames are chained to the `key` state. Single-frame messages are
+    /// returned as is.
+    pub fn split_frames<LG: LinkGenerator<Link>>(
+        self,
+        link_gen: &LG,
+        key: &spongos::Spongos<F>,
+        frame_size: usize,
+    ) -> Result<Vec<Self>> {
+        let count = self.parse_header()?.header.get_payload_frame_count() as usize;
+        if count < 2 {
+            return Ok(vec![self]);
+        }
+        let expected = payload_frame_count::<F>(self.body.bytes.len(), frame_size)?;
+        try_or!(expected == count, ValueMismatch(count, expected))?;
+        let (first, rest) = self.body.bytes.split_at(frame_size);
+        let chunks = rest.chunks(chunk_size::<F>(frame_size)?);
+
+        let mut frames = Vec::with_capacity(count);
+        let mut spongos = first_frame_spongos(key, first);
+        frames.push(Self::new(self.link.clone(), first.to_vec().into()));
+        for (num, chunk) in (2..=count as u32).zip(chunks) {
+            let prev = &frames.last().unwrap().link;
+            let link = frame_link(link_gen, prev, num);
+            let frame_type = if num as usize == count { FINAL_PCF_ID } else { INTER_PCF_ID };
+            let pcf = PCF::new(frame_type, num, Chunk(Bytes(chunk.to_vec())))?;
+            let buf_size = {
+                let mut ctx = sizeof::Context::<F>::new();
+                ctx.absorb(External(Fallback(prev)))?;
+                pcf.sizeof(&mut ctx)?;
+                ctx.get_size()
+            };
+            let mut buf = vec![0; buf_size];
+            {
+                let mut ctx = wrap::Context::<F, _>::new(&mut buf[..]);
+                ctx.spongos = spongos.fork();
+                ctx.absorb(External(Fallback(prev)))?;
+                pcf.wrap(&(), &mut ctx)?;
+                try_or!(ctx.stream.is_empty(), OutputStreamNotFullyConsumed(ctx.stream.len()))?;
+                spongos = ctx.spongos;
+            }
+            frames.push(Self::new(link, buf.into()));
+        }
+        Ok(frames)
+    }
+
+    /// Spongos state the rest of the frames chained to the `key` state are chained to,
+    /// the message must consist of its first frame only.
+    pub fn frames_spongos(&self, key: &spongos::Spongos<F>) -> spongos::Spongos<F> {
+        first_frame_spongos(key, &self.body.bytes)
+    }
+
+    /// Check the frame `payload_frame_num` out of `payload_frame_count` following the frame
+    /// published at `prev` and return its chunk together with the spongos state the next frame
+    /// is chained to.
+    fn unwrap_frame(
+        spongos: &spongos::Spongos<F>,
+        prev: &Link,
+        frame: &Self,
+        payload_frame_num: u32,
+        payload_frame_count: u32,
+    ) -> Result<(Bytes, spongos::Spongos<F>)> {
+        let expected_type = if payload_frame_num == payload_frame_count { FINAL_PCF_ID } else { INTER_PCF_ID };
+        let mut pcf = PCF::default_with_content(Chunk(Bytes::default()));
+        let mut ctx = unwrap::Context::<F, _>::new(&frame.body.bytes[..]);
+        ctx.spongos = spongos.fork();
+        ctx.absorb(External(Fallback(prev)))?;
+        pcf.unwrap(&(), &mut ctx)?;
+        try_or!(ctx.stream.is_empty(), InputStreamNotFullyConsumed(ctx.stream.len()))?;
+        try_or!(
+            pcf.frame_type == expected_type,
+            InvalidMsgType(expected_type.0, pcf.frame_type.0)
+        )?;
+        try_or!(
+            pcf.get_payload_frame_num() == payload_frame_num,
+            PayloadFrameMismatch(payload_frame_num, pcf.get_payload_frame_num())
+        )?;
+        Ok((pcf.content.0, ctx.spongos))
+    }
+
+    /// Pick the frame `payload_frame_num` out of `payload_frame_count` following the frame
+    /// published at `prev` among `candidates` published at its link, append its chunk to the
+    /// message body and advance `spongos` to the state the next frame is chained to. The first
+    /// candidate with valid MAC is taken, the error of the last candidate is returned if none is.
+    pub fn join_frame<'a, I>(
+        &mut self,
+        spongos: &mut spongos::Spongos<F>,
+        prev: &Link,
+        candidates: I,
+        payload_frame_num: u32,
+        payload_frame_count: u32,
+    ) -> Result<()>
+    where
+        I: IntoIterator<Item = &'a Self>,
+        Self: 'a,
+    {
+        let mut result = None;
+        for frame in candidates {
+            let unwrapped = Self::unwrap_frame(spongos, prev, frame, payload_frame_num, payload_frame_count);
+            let found = unwrapped.is_ok();
+            result = Some(unwrapped);
+            if found {
+                break;
+            }
+        }
+        let (chunk, next) = match result {
+            Some(unwrapped) => unwrapped?,
+            None => return err!(PayloadFrameMismatch(payload_frame_num, 0)),
+        };
+        self.body.bytes.extend_from_slice(&chunk.0);
+        *spongos = next;
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "tangle"))]
+mod tests {
+    use super::*;
+    use crate::transport::tangle::{
+        DefaultTangleLinkGenerator,
+        TangleAddress,
+    };
+    use iota_streams_core_keccak::sponge::prp::keccak::KeccakF1600;
+
+    type Message = BinaryMessage<KeccakF1600, TangleAddress>;
+
+    const FRAME_SIZE: usize = 96;
+
+    /// Message with header announcing the number of frames of `frame_size` bytes it is split into
+    /// followed by `payload`.
+    fn message(frame_size: usize, payload: &[u8]) -> Result<Message> {
+        let link = TangleAddress::default();
+        let header = HDF::new(link.clone());
+        let header_size = {
+            let mut ctx = sizeof::Context::<KeccakF1600>::new();
+            header.sizeof(&mut ctx)?;
+            ctx.get_size()
+        };
+        let count = payload_frame_count::<KeccakF1600>(header_size + payload.len(), frame_size)?;
+        let header = header.with_payload_frame_count(count as u32)?;
+        let mut buf = vec![0; header_size];
+        {
+            let mut ctx = wrap::Context::<KeccakF1600, _>::new(&mut buf[..]);
+            header.wrap(&(), &mut ctx)?;
+            try_or!(ctx.stream.is_empty(), OutputStreamNotFullyConsumed(ctx.stream.len()))?;
+        }
+        buf.extend_from_slice(payload);
+        Ok(Message::new(link, buf.into()))
+    }
+
+    /// Key state the frames are chained to, derived from `secret`.
+    fn key(secret: &[u8]) -> spongos::Spongos<KeccakF1600> {
+        let mut s = spongos::Spongos::init();
+        s.absorb(secret);
+        s.commit();
+        s
+    }
+
+    /// Candidates for each of the frames following the first one consisting of the frame only.
+    fn candidates(frames: &[Message]) -> Vec<Vec<Message>> {
+        frames[1..].iter().map(|frame| vec![frame.clone()]).collect()
+    }
+
+    /// Join frames chained to `key` picking each frame among the corresponding `candidates`.
+    fn join_frames(
+        key: &spongos::Spongos<KeccakF1600>,
+        first: &Message,
+        candidates: &[Vec<Message>],
+    ) -> Result<Message> {
+        let count = candidates.len() as u32 + 1;
+        let mut msg = first.clone();
+        let mut spongos = msg.frames_spongos(key);
+        let mut prev = first.link.clone();
+        for (num, frames) in (2..=count).zip(candidates) {
+            msg.join_frame(&mut spongos, &prev, frames, num, count)?;
+            prev = frames.last().unwrap().link.clone();
+        }
+        Ok(msg)
+    }
+
+    #[test]
+    fn split_and_join_frames() -> Result<()> {
+        let link_gen = DefaultTangleLinkGenerator::<KeccakF1600>::default();
+        let msg = message(FRAME_SIZE, &[7; 200])?;
+        let frames = msg.clone().split_frames(&link_gen, &key(b"secret"), FRAME_SIZE)?;
+        assert!(2 < frames.len());
+        assert!(frames[0].link == msg.link);
+        for num in 2..=frames.len() {
+            assert!(frames[num - 1].link == frame_link(&link_gen, &frames[num - 2].link, num as u32));
+        }
+
+        assert!(join_frames(&key(b"secret"), &frames[0], &candidates(&frames))? == msg);
+        Ok(())
+    }
+
+    #[test]
+    fn frames_fit_frame_size() -> Result<()> {
+        let link_gen = DefaultTangleLinkGenerator::<KeccakF1600>::default();
+        for &frame_size in &[FRAME_SIZE, 200, 1000] {
+            let msg = message(frame_size, &[7; 3000])?;
+            let frames = msg.clone().split_frames(&link_gen, &key(b"secret"), frame_size)?;
+            assert_eq!(frames.len(), msg.parse_header()?.header.get_payload_frame_count() as usize);
+            assert_eq!(frames[0].body.bytes.len(), frame_size);
+            for frame in &frames {
+                assert!(frame.body.bytes.len() <= frame_size);
+            }
+            assert!(join_frames(&key(b"secret"), &frames[0], &candidates(&frames))? == msg);
+
+            // Frames following the first one are of the same size once the body fills its frames.
+            let size = full_frames_size::<KeccakF1600>(msg.body.bytes.len(), frame_size)?;
+            let payload = vec![7; 3000 + size - msg.body.bytes.len()];
+            let frames = message(frame_size, &payload)?.split_frames(&link_gen, &key(b"secret"), frame_size)?;
+            assert_eq!(frames.len(), msg.parse_header()?.header.get_payload_frame_count() as usize);
+            let last = frames.last().unwrap().body.bytes.len();
+            assert!(frames[1..].iter().all(|frame| frame.body.bytes.len() == last));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn join_frame_picks_candidate_by_mac() -> Result<()> {
+        let link_gen = DefaultTangleLinkGenerator::<KeccakF1600>::default();
+        let msg = message(FRAME_SIZE, &[7; 200])?;
+        let frames = msg.clone().split_frames(&link_gen, &key(b"secret"), FRAME_SIZE)?;
+        // Frames of another message are published at the same links, but their MACs are chained to
+        // a different first frame.
+        let forged = message(FRAME_SIZE, &[8; 200])?.split_frames(&link_gen, &key(b"secret"), FRAME_SIZE)?;
+        assert!(forged[1].link == frames[1].link);
+
+        let mut mixed = candidates(&frames);
+        mixed[0].insert(0, forged[1].clone());
+        mixed[1].push(forged[2].clone());
+        assert!(join_frames(&key(b"secret"), &frames[0], &mixed)? == msg);
+
+        let mut mixed = candidates(&frames);
+        mixed[0] = vec![forged[1].clone()];
+        assert!(join_frames(&key(b"secret"), &frames[0], &mixed).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn join_frame_rejects_frames_without_key() -> Result<()> {
+        let link_gen = DefaultTangleLinkGenerator::<KeccakF1600>::default();
+        let msg = message(FRAME_SIZE, &[7; 200])?;
+        let frames = msg.clone().split_frames(&link_gen, &key(b"secret"), FRAME_SIZE)?;
+        // The public first frame is not enough to chain frames to it.
+        let forged = msg.split_frames(&link_gen, &key(b"guess"), FRAME_SIZE)?;
+        assert!(forged[0] == frames[0]);
+        assert!(join_frames(&key(b"secret"), &frames[0], &candidates(&forged)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn join_frame_rejects_reordered_frames() -> Result<()> {
+        let link_gen = DefaultTangleLinkGenerator::<KeccakF1600>::default();
+        let frames = message(FRAME_SIZE, &[7; 200])?.split_frames(&link_gen, &key(b"secret"), FRAME_SIZE)?;
+        let mut reordered = candidates(&frames);
+        reordered.swap(0, 1);
+        assert!(join_frames(&key(b"secret"), &frames[0], &reordered).is_err());
+        Ok(())
+    }
+}
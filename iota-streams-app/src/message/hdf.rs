@@ -17,6 +17,11 @@ use iota_streams_ddml::{
 use super::*;
 
 pub const FLAG_BRANCHING_MASK: u8 = 1;
+pub const FLAG_RATCHET_MASK: u8 = 2;
+pub const FLAG_PADDING_MASK: u8 = 4;
+
+/// Content layout of the first Streams version, content versions are 2 bits.
+pub const CONTENT_VERSION_0: u8 = 0;
 
 #[derive(Clone)]
 pub struct HDF<Link> {
@@ -24,6 +29,8 @@ pub struct HDF<Link> {
     pub version: Uint8,
     // message type is 4 bits
     pub content_type: u8,
+    // content version is 2 bits
+    pub content_version: u8,
     // payload length is 10 bits
     pub payload_length: usize,
     pub frame_type: Uint8,
@@ -39,6 +46,7 @@ impl<Link> HDF<Link> {
             encoding: UTF8,
             version: STREAMS_1_VER,
             content_type: 0,
+            content_version: CONTENT_VERSION_0,
             payload_length: 0,
             frame_type: HDF_ID,
             payload_frame_count: 0,
@@ -57,6 +65,16 @@ impl<Link> HDF<Link> {
         self.content_type
     }
 
+    pub fn with_content_version(mut self, content_version: u8) -> Result<Self> {
+        try_or!(content_version < 0x04, ValueOutOfRange(0x04 as usize, content_version as usize))?;
+        self.content_version = content_version;
+        Ok(self)
+    }
+
+    pub fn get_content_version(&self) -> u8 {
+        self.content_version
+    }
+
     pub fn with_payload_length(mut self, payload_length: usize) -> Result<Self> {
         try_or!(
             payload_length < 0x0400,
@@ -102,6 +120,7 @@ impl<Link> HDF<Link> {
             encoding: UTF8,
             version: STREAMS_1_VER,
             content_type,
+            content_version: CONTENT_VERSION_0,
             payload_length,
             frame_type: HDF_ID,
             payload_frame_count: 0,
@@ -117,6 +136,7 @@ impl<Link: Default> Default for HDF<Link> {
             encoding: UTF8,
             version: STREAMS_1_VER,
             content_type: 0,
+            content_version: CONTENT_VERSION_0,
             payload_length: 0,
             frame_type: HDF_ID,
             payload_frame_count: 0,
@@ -133,10 +153,11 @@ where
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "{{encoding: {:?}, version: {:?}, content_type: {:?}, payload_length: {:?}}}",
+            "{{encoding: {:?}, version: {:?}, content_type: {:?}, content_version: {:?}, payload_length: {:?}}}",
             self.encoding,
             self.version,
             self.get_content_type(),
+            self.get_content_version(),
             self.get_payload_length()
         )
     }
@@ -153,7 +174,7 @@ where
         ctx.absorb(self.encoding)?
             .absorb(self.version)?
             .skip(&content_type_and_payload_length)?
-            .absorb(External(Uint8(self.content_type << 4)))?
+            .absorb(External(Uint8((self.content_type << 4) | (self.content_version << 2))))?
             .absorb(self.frame_type)?
             .skip(&payload_frame_count)?
             .absorb(External(Fallback(&self.link)))?
@@ -175,7 +196,7 @@ where
         let content_type_and_payload_length = {
             let mut nbytes = NBytes::<U2>::default();
             let v = nbytes.as_mut();
-            v[0] = (self.content_type << 4) | ((self.payload_length >> 8) as u8 & 0x03);
+            v[0] = (self.content_type << 4) | (self.content_version << 2) | ((self.payload_length >> 8) as u8 & 0x03);
             v[1] = self.payload_length as u8;
             nbytes
         };
@@ -192,7 +213,7 @@ where
         ctx.absorb(self.encoding)?
             .absorb(self.version)?
             .skip(&content_type_and_payload_length)?
-            .absorb(External(Uint8(self.content_type << 4)))?
+            .absorb(External(Uint8((self.content_type << 4) | (self.content_version << 2))))?
             .absorb(self.frame_type)?
             .skip(&payload_frame_count)?
             .absorb(External(Fallback(&self.link)))?
@@ -223,12 +244,12 @@ where
             .skip(&mut content_type_and_payload_length)?;
         {
             let v = content_type_and_payload_length.as_ref();
-            try_or!(0 == v[0] & 0x0c, InvalidBitReservation)?;
             self.content_type = v[0] >> 4;
+            self.content_version = (v[0] >> 2) & 0x03;
             self.payload_length = (((v[0] & 0x03) as usize) << 8) | (v[1] as usize);
         }
 
-        ctx.absorb(External(Uint8(self.content_type << 4)))?
+        ctx.absorb(External(Uint8((self.content_type << 4) | (self.content_version << 2))))?
             .absorb(&mut self.frame_type)?
             .guard(
                 self.frame_type == HDF_ID,
@@ -5,7 +5,10 @@ use super::*;
 use iota_streams_core::{
     sponge::prp::PRP,
     try_or,
-    Errors::OutputStreamNotFullyConsumed,
+    Errors::{
+        OutputStreamNotFullyConsumed,
+        PayloadFrameSizeTooSmall,
+    },
     LOCATION_LOG
 };
 use iota_streams_ddml::{
@@ -50,13 +53,38 @@ where
     HDF<Link>: ContentWrap<F, Store>,
     Content: ContentWrap<F, Store>,
 {
-    pub fn wrap(&self) -> Result<WrappedMessage<F, Link>> {
-        let buf_size = {
+    /// Size in bytes of the wrapped message.
+    pub fn sizeof(&self) -> Result<usize> {
+        let mut ctx = sizeof::Context::<F>::new();
+        self.header.sizeof(&mut ctx)?;
+        self.content.sizeof(&mut ctx)?;
+        Ok(ctx.get_size())
+    }
+
+    /// Set `payload_frame_count` so that the wrapped message can be split into frames
+    /// of at most `frame_size` bytes, `0` means the message is never split.
+    pub fn with_frame_size(mut self, frame_size: usize) -> Result<Self> {
+        let header_size = {
             let mut ctx = sizeof::Context::<F>::new();
             self.header.sizeof(&mut ctx)?;
-            self.content.sizeof(&mut ctx)?;
             ctx.get_size()
         };
+        // The first frame carries `frame_size` bytes, it must contain the whole header and the
+        // beginning of the content.
+        try_or!(
+            frame_size == 0 || 2 * header_size < frame_size,
+            PayloadFrameSizeTooSmall(2 * header_size + 1, frame_size)
+        )?;
+        let count = frames::payload_frame_count::<F>(self.sizeof()?, frame_size)?;
+        self.header = self.header.with_payload_frame_count(count as u32)?;
+        if 1 < count {
+            self.content.frame_type = INIT_PCF_ID;
+        }
+        Ok(self)
+    }
+
+    pub fn wrap(&self) -> Result<WrappedMessage<F, Link>> {
+        let buf_size = self.sizeof()?;
 
         let mut buf = vec![0; buf_size];
 
@@ -12,10 +12,28 @@ use iota_streams_core::{
 #[cfg(feature = "async")]
 use iota_streams_core::Errors::MessageNotUnique;
 
-#[cfg(feature = "async")]
-use atomic_refcell::AtomicRefCell;
-#[cfg(feature = "async")]
-use iota_streams_core::prelude::Arc;
+#[cfg(feature = "tangle")]
+use super::tangle::{
+    TangleAddress,
+    TangleMessage,
+};
+#[cfg(feature = "tangle")]
+use crate::message::BinaryMessage;
+#[cfg(feature = "tangle")]
+use iota_streams_core::{
+    sponge::prp::PRP,
+    try_or,
+    Errors::{
+        BucketVersionRecoveryFailure,
+        InputStreamNotFullyConsumed,
+        OutputStreamNotFullyConsumed,
+    },
+};
+#[cfg(feature = "tangle")]
+use iota_streams_ddml::{
+    command::*,
+    types::*,
+};
 
 pub struct BucketTransport<Link, Msg> {
     bucket: HashMap<Link, Vec<Msg>>,
@@ -39,6 +57,134 @@ where
     }
 }
 
+impl<Link, Msg> BucketTransport<Link, Msg>
+where
+    Link: Eq + hash::Hash,
+    Msg: PartialEq,
+{
+    /// Add messages from `other` bucket which are not present in this bucket yet.
+    pub fn merge(&mut self, other: Self) {
+        for (link, msgs) in other.bucket {
+            let bucket_msgs = self.bucket.entry(link).or_insert_with(Vec::new);
+            for msg in msgs {
+                if !bucket_msgs.contains(&msg) {
+                    bucket_msgs.push(msg);
+                }
+            }
+        }
+    }
+}
+
+/// Version of the bucket dump format.
+#[cfg(feature = "tangle")]
+const DUMP_VERSION: u8 = 1;
+
+#[cfg(feature = "tangle")]
+impl<F: PRP> BucketTransport<TangleAddress, TangleMessage<F>> {
+    /// Serialize binary messages together with their timestamps.
+    ///
+    /// Links are dumped in a fixed order, buckets with the same messages produce the same bytes.
+    pub fn dump(&self) -> Result<Vec<u8>> {
+        let mut links: Vec<_> = self.bucket.iter().collect();
+        links.sort_by(|(a, _), (b, _)| {
+            (a.appinst.as_ref(), a.msgid.as_ref()).cmp(&(b.appinst.as_ref(), b.msgid.as_ref()))
+        });
+
+        let buf_size = {
+            let mut ctx = sizeof::Context::<F>::new();
+            ctx
+                .absorb(Uint8(DUMP_VERSION))?
+                .absorb(Size(links.len()))?
+                .repeated(links.iter(), |ctx, (link, msgs)| {
+                    ctx
+                        .absorb(<&Fallback<TangleAddress>>::from(*link))?
+                        .absorb(Size(msgs.len()))?
+                        .repeated(msgs.iter(), |ctx, msg| {
+                            ctx
+                                .absorb(Uint64(msg.timestamp))?
+                                .absorb(<&Bytes>::from(&msg.binary.body.bytes))
+                        })
+                })?
+                .commit()?
+                .squeeze(Mac(32))?
+            ;
+            ctx.get_size()
+        };
+
+        let mut buf = vec![0; buf_size];
+
+        {
+            let mut ctx = wrap::Context::<F, _>::new(&mut buf[..]);
+            ctx
+                .absorb(Uint8(DUMP_VERSION))?
+                .absorb(Size(links.len()))?
+                .repeated(links.iter(), |ctx, (link, msgs)| {
+                    ctx
+                        .absorb(<&Fallback<TangleAddress>>::from(*link))?
+                        .absorb(Size(msgs.len()))?
+                        .repeated(msgs.iter(), |ctx, msg| {
+                            ctx
+                                .absorb(Uint64(msg.timestamp))?
+                                .absorb(<&Bytes>::from(&msg.binary.body.bytes))
+                        })
+                })?
+                .commit()?
+                .squeeze(Mac(32))?
+            ;
+            try_or!(
+                ctx.stream.is_empty(),
+                OutputStreamNotFullyConsumed(ctx.stream.len())
+            )?;
+        }
+
+        Ok(buf)
+    }
+
+    /// Restore bucket from bytes produced by `dump`.
+    pub fn load(bytes: &[u8]) -> Result<Self> {
+        let mut bucket = Self::new();
+        let mut ctx = unwrap::Context::<F, _>::new(bytes);
+        let mut version = Uint8(0);
+        let mut repeated_links = Size(0);
+        ctx
+            .absorb(&mut version)?
+            .guard(version.0 == DUMP_VERSION,
+                   BucketVersionRecoveryFailure(DUMP_VERSION, version.0)
+            )?
+            .absorb(&mut repeated_links)?
+            .repeated(repeated_links, |ctx| {
+                let mut link = Fallback(TangleAddress::default());
+                let mut repeated_msgs = Size(0);
+                let mut msgs = Vec::new();
+                ctx
+                    .absorb(&mut link)?
+                    .absorb(&mut repeated_msgs)?
+                    .repeated(repeated_msgs, |ctx| {
+                        let mut timestamp = Uint64(0);
+                        let mut body = Bytes::new();
+                        ctx
+                            .absorb(&mut timestamp)?
+                            .absorb(&mut body)?
+                        ;
+                        let binary = BinaryMessage::new(link.0.clone(), body.0.into());
+                        msgs.push(TangleMessage::with_timestamp(binary, timestamp.0));
+                        Ok(ctx)
+                    })?
+                ;
+                bucket.bucket.insert(link.0, msgs);
+                Ok(ctx)
+            })?
+            .commit()?
+            .squeeze(Mac(32))?
+        ;
+        try_or!(
+            ctx.stream.is_empty(),
+            InputStreamNotFullyConsumed(ctx.stream.len())
+        )?;
+        Ok(bucket)
+    }
+}
+
 impl<Link, Msg> TransportOptions for BucketTransport<Link, Msg> {
     type SendOptions = ();
     fn get_send_options(&self) -> () {}
@@ -75,7 +221,8 @@ where
 }
 
 #[cfg(feature = "async")]
-#[async_trait(?Send)]
+#[cfg_attr(feature = "async-send", async_trait)]
+#[cfg_attr(not(feature = "async-send"), async_trait(?Send))]
 impl<Link, Msg> Transport<Link, Msg> for BucketTransport<Link, Msg>
 where
     Link: Eq + hash::Hash + Clone + core::marker::Send + core::marker::Sync + core::fmt::Display,
@@ -110,3 +257,61 @@ where
         }
     }
 }
+
+#[cfg(all(test, feature = "tangle"))]
+mod tests {
+    use super::*;
+    use crate::transport::tangle::MsgId;
+    use iota_streams_core::Errors;
+    use iota_streams_core_keccak::sponge::prp::keccak::KeccakF1600;
+
+    type Message = TangleMessage<KeccakF1600>;
+    type Bucket = BucketTransport<TangleAddress, Message>;
+
+    fn message(msgid: u8, body: &[u8], timestamp: u64) -> Message {
+        let link = TangleAddress::new(Default::default(), MsgId::from(&[msgid; 12][..]));
+        Message::with_timestamp(BinaryMessage::new(link, body.to_vec().into()), timestamp)
+    }
+
+    fn bucket(msgs: &[Message]) -> Bucket {
+        let mut bucket = Bucket::new();
+        for msg in msgs {
+            bucket.bucket.entry(msg.link().clone()).or_insert_with(Vec::new).push(msg.clone());
+        }
+        bucket
+    }
+
+    #[test]
+    fn dump_and_load() -> Result<()> {
+        let msgs = [message(1, b"first", 1), message(1, b"second", 2), message(2, b"other", 3)];
+        let dump = bucket(&msgs).dump()?;
+        let loaded = Bucket::load(&dump)?;
+        assert!(loaded.bucket == bucket(&msgs).bucket);
+
+        // Links are dumped in a fixed order.
+        let reordered = [msgs[2].clone(), msgs[0].clone(), msgs[1].clone()];
+        assert_eq!(bucket(&reordered).dump()?, dump);
+        Ok(())
+    }
+
+    #[test]
+    fn load_rejects_corrupted_dump() -> Result<()> {
+        let mut dump = bucket(&[message(1, b"body", 1)]).dump()?;
+        let last = dump.len() - 1;
+        dump[last] ^= 1;
+        assert!(Bucket::load(&dump).is_err());
+
+        dump[0] = DUMP_VERSION + 1;
+        let err = Bucket::load(&dump).err().unwrap();
+        assert!(matches!(err.downcast_ref::<Errors>(), Some(BucketVersionRecoveryFailure(_, _))));
+        Ok(())
+    }
+
+    #[test]
+    fn merge_skips_present_messages() {
+        let msgs = [message(1, b"first", 1), message(1, b"second", 2), message(2, b"other", 3)];
+        let mut merged = bucket(&msgs[..2]);
+        merged.merge(bucket(&msgs[1..]));
+        assert!(merged.bucket == bucket(&msgs).bucket);
+    }
+}
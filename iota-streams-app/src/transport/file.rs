@@ -0,0 +1,356 @@
+//! Filesystem-backed transport.
+//!
+//! Messages are stored in `<root>/<appinst>/<msgid>/<n>.msg` files, where `<n>` is the index of
+//! the message among the messages published at the same link. Each file contains the message
+//! timestamp (8 bytes, big-endian) followed by the binary encoded message body.
+//!
+//! A message file is first written under a temporary name and then hard-linked to its final
+//! name, so readers never observe partially written messages and concurrent writers (possibly
+//! in different processes) never overwrite each other's messages. Temporary files left behind
+//! by crashed writers are removed once they are older than `STALE_TMP_AGE`.
+//!
+//! The transport performs blocking filesystem calls, the `async` implementation included: its
+//! futures complete without yielding and block the executor thread for the duration of the
+//! call. Use a dedicated thread if this is not acceptable.
+
+use std::{
+    fs,
+    io::{
+        self,
+        Write,
+    },
+    path::{
+        Path,
+        PathBuf,
+    },
+    process,
+    sync::atomic::{
+        AtomicUsize,
+        Ordering,
+    },
+    time::{
+        Duration,
+        SystemTime,
+    },
+};
+
+use super::*;
+use crate::message::{
+    BinaryMessage,
+    LinkedMessage,
+};
+use super::tangle::{
+    TangleAddress,
+    TangleMessage,
+};
+
+use iota_streams_core::{
+    err,
+    prelude::string::ToString,
+    try_or,
+    wrapped_err,
+    Errors::{
+        MalformedStoredMessage,
+        MessageLinkNotFound,
+        TransportStorageFailure,
+    },
+    WrappedError,
+    LOCATION_LOG,
+};
+
+#[cfg(feature = "async")]
+use iota_streams_core::Errors::MessageNotUnique;
+
+/// Extension of the files containing published messages.
+const MSG_EXT: &str = "msg";
+
+/// Size of the timestamp prefix of a message file.
+const TIMESTAMP_SIZE: usize = 8;
+
+/// Extension of the temporary files messages are written to before being published.
+const TMP_EXT: &str = "tmp";
+
+/// Age after which a temporary file is considered to be left behind by a crashed writer.
+pub const STALE_TMP_AGE: Duration = Duration::from_secs(10 * 60);
+
+/// Counter used to generate unique temporary file names within the process.
+static TMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+fn handle_io_result<T>(result: io::Result<T>) -> Result<T> {
+    result.map_err(|err| wrapped_err!(TransportStorageFailure, WrappedError(err)))
+}
+
+/// Index of the message stored in file `path`, `None` if `path` is not a message file.
+fn message_index(path: &Path) -> Option<u64> {
+    if path.extension()? == MSG_EXT {
+        path.file_stem()?.to_str()?.parse().ok()
+    } else {
+        None
+    }
+}
+
+/// Whether `path` is a temporary file which has not been modified for `STALE_TMP_AGE`.
+fn is_stale_tmp(path: &Path, now: SystemTime) -> bool {
+    path.extension().map_or(false, |ext| ext == TMP_EXT)
+        && fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .map_or(false, |age| STALE_TMP_AGE <= age)
+}
+
+/// Remove stale temporary files from the link directory `dir`, returns the number of removed files.
+fn remove_stale_tmp(dir: &Path) -> Result<usize> {
+    let now = SystemTime::now();
+    let mut removed = 0;
+    for entry in handle_io_result(fs::read_dir(dir))? {
+        let path = handle_io_result(entry)?.path();
+        // The file may have been removed by a concurrent cleanup
+        if is_stale_tmp(&path, now) && fs::remove_file(&path).is_ok() {
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+fn read_message<F>(path: &Path, link: &TangleAddress) -> Result<TangleMessage<F>> {
+    let bytes = handle_io_result(fs::read(path))?;
+    try_or!(
+        TIMESTAMP_SIZE <= bytes.len(),
+        MalformedStoredMessage(path.display().to_string())
+    )?;
+    let mut timestamp = [0_u8; TIMESTAMP_SIZE];
+    timestamp.copy_from_slice(&bytes[..TIMESTAMP_SIZE]);
+    let binary = BinaryMessage::new(link.clone(), bytes[TIMESTAMP_SIZE..].to_vec().into());
+    Ok(TangleMessage::with_timestamp(binary, u64::from_be_bytes(timestamp)))
+}
+
+/// Transport persisting Tangle messages in a local directory.
+///
+/// Several `FileTransport` instances, possibly in different processes, can share the same
+/// directory: messages sent by one instance are received by the others.
+#[derive(Clone)]
+pub struct FileTransport {
+    root: PathBuf,
+}
+
+impl FileTransport {
+    /// Create transport storing messages in `root` directory, the directory is created if needed.
+    pub fn new<P: AsRef<Path>>(root: P) -> Result<Self> {
+        let root = root.as_ref().to_path_buf();
+        handle_io_result(fs::create_dir_all(&root))?;
+        Ok(Self { root })
+    }
+
+    /// Directory where messages are stored.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Remove temporary files left behind by crashed writers in the whole transport directory,
+    /// returns the number of removed files. Stale files in a link directory are also removed
+    /// whenever a message is sent to the link.
+    pub fn remove_stale_files(&self) -> Result<usize> {
+        let mut removed = 0;
+        for appinst in handle_io_result(fs::read_dir(&self.root))? {
+            let appinst = handle_io_result(appinst)?.path();
+            if !appinst.is_dir() {
+                continue;
+            }
+            for msgid in handle_io_result(fs::read_dir(&appinst))? {
+                let msgid = handle_io_result(msgid)?.path();
+                if msgid.is_dir() {
+                    removed += remove_stale_tmp(&msgid)?;
+                }
+            }
+        }
+        Ok(removed)
+    }
+
+    fn link_dir(&self, link: &TangleAddress) -> PathBuf {
+        self.root.join(link.appinst.to_string()).join(link.msgid.to_string())
+    }
+
+    fn write_message<F>(&self, msg: &TangleMessage<F>) -> Result<()> {
+        let dir = self.link_dir(msg.link());
+        handle_io_result(fs::create_dir_all(&dir))?;
+        remove_stale_tmp(&dir)?;
+
+        let tmp = dir.join(format!(
+            ".{}-{}.{}",
+            process::id(),
+            TMP_COUNTER.fetch_add(1, Ordering::Relaxed),
+            TMP_EXT
+        ));
+        let written = fs::File::create(&tmp).and_then(|mut file| {
+            file.write_all(&msg.timestamp.to_be_bytes())?;
+            file.write_all(&msg.binary.body.bytes)?;
+            file.sync_all()
+        });
+        if let Err(err) = written {
+            let _ = fs::remove_file(&tmp);
+            return handle_io_result(Err(err));
+        }
+
+        // `hard_link` fails if the target exists, so the message takes the first free index.
+        let mut idx = handle_io_result(fs::read_dir(&dir))?
+            .filter_map(|entry| entry.ok().and_then(|entry| message_index(&entry.path())))
+            .count() as u64;
+        let published = loop {
+            match fs::hard_link(&tmp, dir.join(format!("{}.{}", idx, MSG_EXT))) {
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => idx += 1,
+                result => break result,
+            }
+        };
+        let _ = fs::remove_file(&tmp);
+        handle_io_result(published)
+    }
+
+    fn read_messages<F>(&self, link: &TangleAddress) -> Result<Vec<TangleMessage<F>>> {
+        let entries = match fs::read_dir(self.link_dir(link)) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                return err!(MessageLinkNotFound(link.to_string()));
+            }
+            Err(err) => return handle_io_result(Err(err)),
+        };
+
+        let mut files = Vec::new();
+        for entry in entries {
+            let path = handle_io_result(entry)?.path();
+            if let Some(idx) = message_index(&path) {
+                files.push((idx, path));
+            }
+        }
+        try_or!(!files.is_empty(), MessageLinkNotFound(link.to_string()))?;
+
+        files.sort_by_key(|(idx, _)| *idx);
+        files.iter().map(|(_, path)| read_message(path, link)).collect()
+    }
+}
+
+impl TransportOptions for FileTransport {
+    type SendOptions = ();
+    fn get_send_options(&self) -> () {}
+    fn set_send_options(&mut self, _opt: ()) {}
+
+    type RecvOptions = ();
+    fn get_recv_options(&self) -> () {}
+    fn set_recv_options(&mut self, _opt: ()) {}
+}
+
+#[cfg(not(feature = "async"))]
+impl<F> Transport<TangleAddress, TangleMessage<F>> for FileTransport {
+    /// Store a message in the transport directory.
+    fn send_message(&mut self, msg: &TangleMessage<F>) -> Result<()> {
+        self.write_message(msg)
+    }
+
+    /// Read messages stored at the link in the order they have been sent.
+    fn recv_messages(&mut self, link: &TangleAddress) -> Result<Vec<TangleMessage<F>>> {
+        self.read_messages(link)
+    }
+}
+
+// Filesystem calls are blocking, see the module documentation.
+#[cfg(feature = "async")]
+#[cfg_attr(feature = "async-send", async_trait)]
+#[cfg_attr(not(feature = "async-send"), async_trait(?Send))]
+impl<F> Transport<TangleAddress, TangleMessage<F>> for FileTransport
+where
+    F: 'static + core::marker::Send + core::marker::Sync,
+{
+    /// Store a message in the transport directory.
+    async fn send_message(&mut self, msg: &TangleMessage<F>) -> Result<()> {
+        self.write_message(msg)
+    }
+
+    /// Read messages stored at the link in the order they have been sent.
+    async fn recv_messages(&mut self, link: &TangleAddress) -> Result<Vec<TangleMessage<F>>> {
+        self.read_messages(link)
+    }
+
+    async fn recv_message(&mut self, link: &TangleAddress) -> Result<TangleMessage<F>> {
+        let mut msgs = self.read_messages(link)?;
+        if let Some(msg) = msgs.pop() {
+            try_or!(msgs.is_empty(), MessageNotUnique(link.to_string()))?;
+            Ok(msg)
+        } else {
+            err!(MessageLinkNotFound(link.to_string()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::tangle::MsgId;
+    use iota_streams_core::Errors;
+    use iota_streams_core_keccak::sponge::prp::keccak::KeccakF1600;
+
+    type Message = TangleMessage<KeccakF1600>;
+
+    /// Run `test` with a transport in a fresh directory which is removed afterwards.
+    fn with_transport<T>(name: &str, test: impl FnOnce(&FileTransport) -> T) -> T {
+        let root = std::env::temp_dir().join(format!("streams-file-{}-{}", name, process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let result = FileTransport::new(&root).map(|transport| test(&transport));
+        let _ = fs::remove_dir_all(&root);
+        result.unwrap()
+    }
+
+    fn message(msgid: u8, body: &[u8], timestamp: u64) -> Message {
+        let link = TangleAddress::new(Default::default(), MsgId::from(&[msgid; 12][..]));
+        Message::with_timestamp(BinaryMessage::new(link, body.to_vec().into()), timestamp)
+    }
+
+    #[test]
+    fn messages_are_received_in_send_order() {
+        with_transport("order", |transport| {
+            let msgs = vec![message(1, b"first", 1), message(1, b"second", 2)];
+            for msg in &msgs {
+                transport.write_message(msg).unwrap();
+            }
+            transport.write_message(&message(2, b"other", 3)).unwrap();
+
+            let other = FileTransport::new(transport.root()).unwrap();
+            let received = other.read_messages::<KeccakF1600>(msgs[0].link()).unwrap();
+            assert!(received == msgs);
+        })
+    }
+
+    #[test]
+    fn missing_and_malformed_messages_are_reported() {
+        with_transport("malformed", |transport| {
+            let msg = message(1, b"body", 1);
+            let err = transport.read_messages::<KeccakF1600>(msg.link()).err().unwrap();
+            assert!(matches!(err.downcast_ref::<Errors>(), Some(MessageLinkNotFound(_))));
+
+            let dir = transport.link_dir(msg.link());
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join(format!("0.{}", MSG_EXT)), [0_u8; TIMESTAMP_SIZE - 1]).unwrap();
+            let err = transport.read_messages::<KeccakF1600>(msg.link()).err().unwrap();
+            assert!(matches!(err.downcast_ref::<Errors>(), Some(MalformedStoredMessage(_))));
+        })
+    }
+
+    #[test]
+    fn only_stale_temporary_files_are_removed() {
+        with_transport("stale", |transport| {
+            let msg = message(1, b"body", 1);
+            transport.write_message(&msg).unwrap();
+            let dir = transport.link_dir(msg.link());
+            let tmp = dir.join(format!(".crashed.{}", TMP_EXT));
+            fs::write(&tmp, b"partial").unwrap();
+
+            let now = SystemTime::now();
+            assert!(!is_stale_tmp(&tmp, now));
+            assert!(is_stale_tmp(&tmp, now + STALE_TMP_AGE));
+            assert!(!is_stale_tmp(&dir.join(format!("0.{}", MSG_EXT)), now + STALE_TMP_AGE));
+
+            assert_eq!(transport.remove_stale_files().unwrap(), 0);
+            assert!(tmp.exists());
+            assert_eq!(transport.read_messages::<KeccakF1600>(msg.link()).unwrap().len(), 1);
+        })
+    }
+}
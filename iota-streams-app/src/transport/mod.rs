@@ -10,7 +10,10 @@ use core::marker::{
 #[cfg(feature = "async")]
 use async_trait::async_trait;
 #[cfg(feature = "async")]
-use atomic_refcell::AtomicRefCell;
+use futures::lock::{
+    Mutex,
+    MutexLockFuture,
+};
 #[cfg(feature = "async")]
 use iota_streams_core::prelude::{Arc, Box, };
 
@@ -50,8 +53,10 @@ pub trait Transport<Link: Debug + Display, Msg>: TransportOptions {
     }
 }
 
+/// Futures are `Send` if `async-send` feature is enabled.
 #[cfg(feature = "async")]
-#[async_trait(?Send)]
+#[cfg_attr(feature = "async-send", async_trait)]
+#[cfg_attr(not(feature = "async-send"), async_trait(?Send))]
 pub trait Transport<Link, Msg>: TransportOptions
 where
     Link: Send + Sync,
@@ -130,64 +135,287 @@ pub fn new_shared_transport<T>(tsp: T) -> Rc<RefCell<T>> {
     Rc::new(RefCell::new(tsp))
 }
 
+/// Transport shared by the users of an async process, operations on it wait for each other.
+/// Each handle keeps its own copy of the options outside of the lock, so options are available
+/// while an operation is pending. They are passed to the transport with every operation of the handle.
+#[cfg(feature = "async")]
+pub struct SharedTransport<T: TransportOptions> {
+    transport: Arc<Mutex<T>>,
+    send_options: T::SendOptions,
+    recv_options: T::RecvOptions,
+}
+
+#[cfg(feature = "async")]
+pub fn new_shared_transport<T: TransportOptions>(tsp: T) -> SharedTransport<T> {
+    SharedTransport {
+        send_options: tsp.get_send_options(),
+        recv_options: tsp.get_recv_options(),
+        transport: Arc::new(Mutex::new(tsp)),
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: TransportOptions> SharedTransport<T> {
+    /// Acquire the lock of the shared transport, eg. to access it directly.
+    pub fn lock(&self) -> MutexLockFuture<'_, T> {
+        self.transport.lock()
+    }
+}
+
 #[cfg(feature = "async")]
-impl<Tsp: TransportOptions> TransportOptions for Arc<AtomicRefCell<Tsp>> {
+impl<T> Clone for SharedTransport<T>
+where
+    T: TransportOptions,
+    T::SendOptions: Clone,
+    T::RecvOptions: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            transport: self.transport.clone(),
+            send_options: self.send_options.clone(),
+            recv_options: self.recv_options.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<Tsp> TransportOptions for SharedTransport<Tsp>
+where
+    Tsp: TransportOptions,
+    Tsp::SendOptions: Clone,
+    Tsp::RecvOptions: Clone,
+{
     type SendOptions = <Tsp as TransportOptions>::SendOptions;
     fn get_send_options(&self) -> Self::SendOptions {
-        (&*self).borrow().get_send_options()
+        self.send_options.clone()
     }
     fn set_send_options(&mut self, opt: Self::SendOptions) {
-        (&*self).borrow_mut().set_send_options(opt)
+        self.send_options = opt;
     }
 
     type RecvOptions = <Tsp as TransportOptions>::RecvOptions;
     fn get_recv_options(&self) -> Self::RecvOptions {
-        (&*self).borrow().get_recv_options()
+        self.recv_options.clone()
     }
     fn set_recv_options(&mut self, opt: Self::RecvOptions) {
-        (&*self).borrow_mut().set_recv_options(opt)
+        self.recv_options = opt;
     }
 }
 
-/*
-// The impl below is too restrictive: Link and Msg require 'async_trait life-time, Tsp is Sync + Send.
-#[cfg(feature = "async")]
-#[async_trait]
-impl<Link, Msg, Tsp: Transport<Link, Msg>> Transport<Link, Msg> for Arc<AtomicRefCell<Tsp>> where
+// Futures of `Transport` are not required to be `Send`, so `Tsp` doesn't have to be `Send`.
+// Concurrent operations on the same transport wait for the lock instead of failing.
+#[cfg(all(feature = "async", not(feature = "async-send")))]
+#[async_trait(?Send)]
+impl<Link, Msg, Tsp: Transport<Link, Msg>> Transport<Link, Msg> for SharedTransport<Tsp>
+where
     Link: 'static + core::marker::Send + core::marker::Sync,
     Msg: 'static + core::marker::Send + core::marker::Sync,
-    Tsp: core::marker::Send + core::marker::Sync,
+    Tsp::SendOptions: Clone,
+    Tsp::RecvOptions: Clone,
 {
     /// Send a message.
     async fn send_message(&mut self, msg: &Msg) -> Result<()> {
-        (&*self).borrow_mut().send_message(msg).await
+        let mut tsp = self.transport.lock().await;
+        tsp.set_send_options(self.send_options.clone());
+        tsp.send_message(msg).await
     }
 
     /// Receive messages with default options.
     async fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>> {
-        (&*self).borrow_mut().recv_messages(link).await
+        let mut tsp = self.transport.lock().await;
+        tsp.set_recv_options(self.recv_options.clone());
+        tsp.recv_messages(link).await
     }
 
     /// Receive a message with default options.
     async fn recv_message(&mut self, link: &Link) -> Result<Msg> {
-        (&*self).borrow_mut().recv_message(link).await
+        let mut tsp = self.transport.lock().await;
+        tsp.set_recv_options(self.recv_options.clone());
+        tsp.recv_message(link).await
     }
 }
- */
 
-#[cfg(feature = "async")]
-pub type SharedTransport<T> = Arc<AtomicRefCell<T>>;
+// The lock is held across `.await`, so the transport must be `Send` for the future to be `Send`.
+#[cfg(feature = "async-send")]
+#[async_trait]
+impl<Link, Msg, Tsp: Transport<Link, Msg>> Transport<Link, Msg> for SharedTransport<Tsp>
+where
+    Link: 'static + core::marker::Send + core::marker::Sync,
+    Msg: 'static + core::marker::Send + core::marker::Sync,
+    Tsp: core::marker::Send,
+    Tsp::SendOptions: Clone + core::marker::Send,
+    Tsp::RecvOptions: Clone + core::marker::Send,
+{
+    /// Send a message.
+    async fn send_message(&mut self, msg: &Msg) -> Result<()> {
+        let mut tsp = self.transport.lock().await;
+        tsp.set_send_options(self.send_options.clone());
+        tsp.send_message(msg).await
+    }
 
-#[cfg(feature = "async")]
-pub fn new_shared_transport<T>(tsp: T) -> Arc<AtomicRefCell<T>> {
-    Arc::new(AtomicRefCell::new(tsp))
+    /// Receive messages with default options.
+    async fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>> {
+        let mut tsp = self.transport.lock().await;
+        tsp.set_recv_options(self.recv_options.clone());
+        tsp.recv_messages(link).await
+    }
+
+    /// Receive a message with default options.
+    async fn recv_message(&mut self, link: &Link) -> Result<Msg> {
+        let mut tsp = self.transport.lock().await;
+        tsp.set_recv_options(self.recv_options.clone());
+        tsp.recv_message(link).await
+    }
 }
 
 mod bucket;
 pub use bucket::BucketTransport;
+
+#[cfg(all(feature = "std", feature = "tangle"))]
+mod file;
+#[cfg(all(feature = "std", feature = "tangle"))]
+pub use file::FileTransport;
+
+#[cfg(feature = "std")]
+mod retry;
+#[cfg(feature = "std")]
+pub use retry::{
+    is_transient,
+    is_transient_or_missing,
+    RetryOptions,
+    RetryTransport,
+};
 use core::fmt::{Debug, Display};
 use iota_streams_core::{try_or, err, wrapped_err, WrappedError, LOCATION_LOG};
 use iota_streams_core::Errors::{MessageNotUnique, MessageLinkNotFound, TransportNotAvailable};
 
 #[cfg(feature = "tangle")]
 pub mod tangle;
+
+#[cfg(all(test, feature = "async"))]
+mod tests {
+    use super::*;
+    use crate::message::LinkedMessage;
+    use core::{
+        future::Future,
+        pin::Pin,
+        task::{
+            Context,
+            Poll,
+        },
+    };
+    use futures::{
+        executor::block_on,
+        future::join_all,
+    };
+
+    #[derive(Clone, PartialEq, Debug)]
+    struct TestMessage(u64);
+
+    impl LinkedMessage<u64> for TestMessage {
+        fn link(&self) -> &u64 {
+            &self.0
+        }
+    }
+
+    /// Future which is pending on the first poll, giving other futures a chance to run.
+    struct YieldNow(bool);
+
+    impl Future for YieldNow {
+        type Output = ();
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            if self.0 {
+                Poll::Ready(())
+            } else {
+                self.0 = true;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+    }
+
+    /// Bucket transport yielding in the middle of every operation.
+    #[derive(Default)]
+    struct YieldingTransport {
+        bucket: BucketTransport<u64, TestMessage>,
+        send_options: u8,
+        recv_options: u8,
+    }
+
+    impl TransportOptions for YieldingTransport {
+        type SendOptions = u8;
+        fn get_send_options(&self) -> u8 {
+            self.send_options
+        }
+        fn set_send_options(&mut self, opt: u8) {
+            self.send_options = opt;
+        }
+
+        type RecvOptions = u8;
+        fn get_recv_options(&self) -> u8 {
+            self.recv_options
+        }
+        fn set_recv_options(&mut self, opt: u8) {
+            self.recv_options = opt;
+        }
+    }
+
+    #[cfg_attr(feature = "async-send", async_trait)]
+    #[cfg_attr(not(feature = "async-send"), async_trait(?Send))]
+    impl Transport<u64, TestMessage> for YieldingTransport {
+        async fn send_message(&mut self, msg: &TestMessage) -> Result<()> {
+            YieldNow(false).await;
+            self.bucket.send_message(msg).await
+        }
+
+        async fn recv_messages(&mut self, link: &u64) -> Result<Vec<TestMessage>> {
+            YieldNow(false).await;
+            self.bucket.recv_messages(link).await
+        }
+
+        async fn recv_message(&mut self, link: &u64) -> Result<TestMessage> {
+            YieldNow(false).await;
+            self.bucket.recv_message(link).await
+        }
+    }
+
+    #[test]
+    fn shared_transport_waits_for_pending_operations() {
+        let transport = new_shared_transport(YieldingTransport::default());
+
+        let sends = (0..4).map(|i| {
+            let mut tsp = transport.clone();
+            async move { tsp.send_message(&TestMessage(i)).await }
+        });
+        for result in block_on(join_all(sends)) {
+            assert!(dbg!(result).is_ok());
+        }
+
+        let recvs = (0..4).map(|i| {
+            let mut tsp = transport.clone();
+            async move { tsp.recv_message(&i).await }
+        });
+        for (i, result) in block_on(join_all(recvs)).into_iter().enumerate() {
+            assert_eq!(dbg!(result).ok(), Some(TestMessage(i as u64)));
+        }
+    }
+
+    #[test]
+    fn shared_transport_options_are_available_while_locked() {
+        let transport = new_shared_transport(YieldingTransport::default());
+        let mut other = transport.clone();
+        {
+            let _pending = block_on(transport.lock());
+            other.set_send_options(1);
+            other.set_recv_options(2);
+            assert_eq!((other.get_send_options(), other.get_recv_options()), (1, 2));
+            assert_eq!((transport.get_send_options(), transport.get_recv_options()), (0, 0));
+        }
+
+        // Options of a handle are passed to the transport with the operations of the handle.
+        assert!(dbg!(block_on(other.send_message(&TestMessage(0)))).is_ok());
+        assert_eq!(block_on(transport.lock()).send_options, 1);
+        assert!(dbg!(block_on(other.recv_message(&0))).is_ok());
+        assert_eq!(block_on(transport.lock()).recv_options, 2);
+    }
+}
@@ -0,0 +1,475 @@
+//! Transport middleware retrying failed operations with exponential backoff.
+
+use core::time::Duration;
+
+use super::*;
+use iota_streams_core::{
+    Error,
+    Errors::{
+        self,
+        ClientOperationFailure,
+        MessageLinkNotFound,
+        MessageLinkNotFoundInTangle,
+    },
+};
+
+/// Default retry predicate: the node failed to perform the operation.
+///
+/// Missing messages are not retried by default: fetching new messages probes links most of which
+/// are never going to be published, retrying them would stall every fetch.
+pub fn is_transient(err: &Error) -> bool {
+    match err.downcast_ref::<Errors>() {
+        Some(ClientOperationFailure) => true,
+        _ => false,
+    }
+}
+
+/// Retry predicate for receivers waiting for a message known to be published: the node failed to
+/// perform the operation or the message has not reached it yet.
+///
+/// Use it as `retry_if` around `tangle::client::Client` when reading a specific link, eg. the announcement
+/// or a keyload shared out of band, which may lag behind on the node the receiver is connected to.
+pub fn is_transient_or_missing(err: &Error) -> bool {
+    match err.downcast_ref::<Errors>() {
+        Some(ClientOperationFailure) | Some(MessageLinkNotFound(_)) | Some(MessageLinkNotFoundInTangle(_)) => true,
+        _ => false,
+    }
+}
+
+/// Retry policy of `RetryTransport`.
+#[derive(Clone)]
+pub struct RetryOptions {
+    /// Maximum number of attempts including the first one.
+    pub max_attempts: usize,
+    /// Delay before the first retry.
+    pub initial_delay: Duration,
+    /// Factor the delay is multiplied by after each retry.
+    pub multiplier: u32,
+    /// Upper bound for the delay between attempts.
+    pub max_delay: Duration,
+    /// Whether the operation failed with the error should be retried.
+    pub retry_if: fn(&Error) -> bool,
+}
+
+impl Default for RetryOptions {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_delay: Duration::from_millis(100),
+            multiplier: 2,
+            max_delay: Duration::from_secs(10),
+            retry_if: is_transient,
+        }
+    }
+}
+
+impl RetryOptions {
+    fn should_retry(&self, attempt: usize, err: &Error) -> bool {
+        attempt < self.max_attempts && (self.retry_if)(err)
+    }
+
+    fn first_delay(&self) -> Duration {
+        self.initial_delay.min(self.max_delay)
+    }
+
+    fn next_delay(&self, delay: Duration) -> Duration {
+        delay
+            .checked_mul(self.multiplier)
+            .map_or(self.max_delay, |delay| delay.min(self.max_delay))
+    }
+}
+
+/// Transport wrapper retrying `send_message` and `recv_messages` of the inner transport.
+pub struct RetryTransport<T> {
+    transport: T,
+    options: RetryOptions,
+}
+
+impl<T> RetryTransport<T> {
+    /// Wrap transport with the default retry policy.
+    pub fn new(transport: T) -> Self {
+        Self::with_options(transport, RetryOptions::default())
+    }
+
+    /// Wrap transport with the retry policy.
+    pub fn with_options(transport: T, options: RetryOptions) -> Self {
+        Self { transport, options }
+    }
+
+    /// Current retry policy.
+    pub fn get_retry_options(&self) -> &RetryOptions {
+        &self.options
+    }
+
+    /// Replace retry policy.
+    pub fn set_retry_options(&mut self, options: RetryOptions) {
+        self.options = options;
+    }
+
+    /// Inner transport.
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Mutable inner transport.
+    pub fn transport_mut(&mut self) -> &mut T {
+        &mut self.transport
+    }
+
+    /// Unwrap inner transport.
+    pub fn into_inner(self) -> T {
+        self.transport
+    }
+}
+
+impl<T: Clone> Clone for RetryTransport<T> {
+    fn clone(&self) -> Self {
+        Self {
+            transport: self.transport.clone(),
+            options: self.options.clone(),
+        }
+    }
+}
+
+impl<T: TransportOptions> TransportOptions for RetryTransport<T> {
+    type SendOptions = <T as TransportOptions>::SendOptions;
+    fn get_send_options(&self) -> Self::SendOptions {
+        self.transport.get_send_options()
+    }
+    fn set_send_options(&mut self, opt: Self::SendOptions) {
+        self.transport.set_send_options(opt)
+    }
+
+    type RecvOptions = <T as TransportOptions>::RecvOptions;
+    fn get_recv_options(&self) -> Self::RecvOptions {
+        self.transport.get_recv_options()
+    }
+    fn set_recv_options(&mut self, opt: Self::RecvOptions) {
+        self.transport.set_recv_options(opt)
+    }
+}
+
+#[cfg(not(feature = "async"))]
+impl<Link: Debug + Display, Msg, T: Transport<Link, Msg>> Transport<Link, Msg> for RetryTransport<T> {
+    /// Send a message, retrying if the inner transport fails.
+    fn send_message(&mut self, msg: &Msg) -> Result<()> {
+        let mut delay = self.options.first_delay();
+        let mut attempt = 1;
+        loop {
+            match self.transport.send_message(msg) {
+                Err(err) if self.options.should_retry(attempt, &err) => {
+                    std::thread::sleep(delay);
+                    delay = self.options.next_delay(delay);
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Receive messages, retrying if the inner transport fails.
+    fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>> {
+        let mut delay = self.options.first_delay();
+        let mut attempt = 1;
+        loop {
+            match self.transport.recv_messages(link) {
+                Err(err) if self.options.should_retry(attempt, &err) => {
+                    std::thread::sleep(delay);
+                    delay = self.options.next_delay(delay);
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+// Retry loops are shared by `Send` and non-`Send` flavours of the async trait impl below.
+#[cfg(feature = "async")]
+impl<T> RetryTransport<T> {
+    async fn retry_send_message<Link, Msg>(&mut self, msg: &Msg) -> Result<()>
+    where
+        T: Transport<Link, Msg>,
+        Link: Send + Sync,
+        Msg: Send + Sync,
+    {
+        let mut delay = self.options.first_delay();
+        let mut attempt = 1;
+        loop {
+            match self.transport.send_message(msg).await {
+                Err(err) if self.options.should_retry(attempt, &err) => {
+                    if wasm_timer::Delay::new(delay).await.is_err() {
+                        return Err(err);
+                    }
+                    delay = self.options.next_delay(delay);
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn retry_recv_messages<Link, Msg>(&mut self, link: &Link) -> Result<Vec<Msg>>
+    where
+        T: Transport<Link, Msg>,
+        Link: Send + Sync,
+        Msg: Send + Sync,
+    {
+        let mut delay = self.options.first_delay();
+        let mut attempt = 1;
+        loop {
+            match self.transport.recv_messages(link).await {
+                Err(err) if self.options.should_retry(attempt, &err) => {
+                    if wasm_timer::Delay::new(delay).await.is_err() {
+                        return Err(err);
+                    }
+                    delay = self.options.next_delay(delay);
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn retry_recv_message<Link, Msg>(&mut self, link: &Link) -> Result<Msg>
+    where
+        T: Transport<Link, Msg>,
+        Link: Send + Sync,
+        Msg: Send + Sync,
+    {
+        let mut delay = self.options.first_delay();
+        let mut attempt = 1;
+        loop {
+            match self.transport.recv_message(link).await {
+                Err(err) if self.options.should_retry(attempt, &err) => {
+                    if wasm_timer::Delay::new(delay).await.is_err() {
+                        return Err(err);
+                    }
+                    delay = self.options.next_delay(delay);
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[cfg(all(feature = "async", not(feature = "async-send")))]
+#[async_trait(?Send)]
+impl<Link, Msg, T: Transport<Link, Msg>> Transport<Link, Msg> for RetryTransport<T>
+where
+    Link: 'static + core::marker::Send + core::marker::Sync,
+    Msg: 'static + core::marker::Send + core::marker::Sync,
+{
+    /// Send a message, retrying if the inner transport fails.
+    async fn send_message(&mut self, msg: &Msg) -> Result<()> {
+        self.retry_send_message::<Link, Msg>(msg).await
+    }
+
+    /// Receive messages, retrying if the inner transport fails.
+    async fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>> {
+        self.retry_recv_messages::<Link, Msg>(link).await
+    }
+
+    /// Receive a message, retrying if the inner transport fails.
+    async fn recv_message(&mut self, link: &Link) -> Result<Msg> {
+        self.retry_recv_message::<Link, Msg>(link).await
+    }
+}
+
+#[cfg(feature = "async-send")]
+#[async_trait]
+impl<Link, Msg, T: Transport<Link, Msg>> Transport<Link, Msg> for RetryTransport<T>
+where
+    Link: 'static + core::marker::Send + core::marker::Sync,
+    Msg: 'static + core::marker::Send + core::marker::Sync,
+    T: core::marker::Send,
+{
+    /// Send a message, retrying if the inner transport fails.
+    async fn send_message(&mut self, msg: &Msg) -> Result<()> {
+        self.retry_send_message::<Link, Msg>(msg).await
+    }
+
+    /// Receive messages, retrying if the inner transport fails.
+    async fn recv_messages(&mut self, link: &Link) -> Result<Vec<Msg>> {
+        self.retry_recv_messages::<Link, Msg>(link).await
+    }
+
+    /// Receive a message, retrying if the inner transport fails.
+    async fn recv_message(&mut self, link: &Link) -> Result<Msg> {
+        self.retry_recv_message::<Link, Msg>(link).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::LinkedMessage;
+    use iota_streams_core::{
+        anyhow,
+        err,
+        LOCATION_LOG,
+    };
+
+    #[derive(Clone, PartialEq, Debug)]
+    struct TestMessage(u64);
+
+    impl LinkedMessage<u64> for TestMessage {
+        fn link(&self) -> &u64 {
+            &self.0
+        }
+    }
+
+    /// Bucket transport failing the first `failures` operations with a transient error.
+    struct FlakyTransport {
+        bucket: BucketTransport<u64, TestMessage>,
+        failures: usize,
+        attempts: usize,
+    }
+
+    impl FlakyTransport {
+        fn new(failures: usize) -> Self {
+            Self {
+                bucket: BucketTransport::new(),
+                failures,
+                attempts: 0,
+            }
+        }
+
+        fn attempt(&mut self) -> Result<()> {
+            self.attempts += 1;
+            if self.attempts <= self.failures {
+                err!(ClientOperationFailure)
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    impl TransportOptions for FlakyTransport {
+        type SendOptions = ();
+        fn get_send_options(&self) -> () {}
+        fn set_send_options(&mut self, _opt: ()) {}
+
+        type RecvOptions = ();
+        fn get_recv_options(&self) -> () {}
+        fn set_recv_options(&mut self, _opt: ()) {}
+    }
+
+    #[cfg(not(feature = "async"))]
+    impl Transport<u64, TestMessage> for FlakyTransport {
+        fn send_message(&mut self, msg: &TestMessage) -> Result<()> {
+            self.attempt()?;
+            self.bucket.send_message(msg)
+        }
+
+        fn recv_messages(&mut self, link: &u64) -> Result<Vec<TestMessage>> {
+            self.attempt()?;
+            self.bucket.recv_messages(link)
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[cfg_attr(feature = "async-send", async_trait)]
+    #[cfg_attr(not(feature = "async-send"), async_trait(?Send))]
+    impl Transport<u64, TestMessage> for FlakyTransport {
+        async fn send_message(&mut self, msg: &TestMessage) -> Result<()> {
+            self.attempt()?;
+            self.bucket.send_message(msg).await
+        }
+
+        async fn recv_messages(&mut self, link: &u64) -> Result<Vec<TestMessage>> {
+            self.attempt()?;
+            self.bucket.recv_messages(link).await
+        }
+
+        async fn recv_message(&mut self, link: &u64) -> Result<TestMessage> {
+            self.attempt()?;
+            self.bucket.recv_message(link).await
+        }
+    }
+
+    fn retry_transport(failures: usize, max_attempts: usize) -> RetryTransport<FlakyTransport> {
+        retry_transport_if(failures, max_attempts, is_transient)
+    }
+
+    fn retry_transport_if(
+        failures: usize,
+        max_attempts: usize,
+        retry_if: fn(&Error) -> bool,
+    ) -> RetryTransport<FlakyTransport> {
+        RetryTransport::with_options(
+            FlakyTransport::new(failures),
+            RetryOptions {
+                max_attempts,
+                initial_delay: Duration::from_millis(1),
+                multiplier: 2,
+                max_delay: Duration::from_millis(4),
+                retry_if,
+            },
+        )
+    }
+
+    #[cfg(not(feature = "async"))]
+    fn send(transport: &mut RetryTransport<FlakyTransport>, msg: &TestMessage) -> Result<()> {
+        transport.send_message(msg)
+    }
+
+    #[cfg(feature = "async")]
+    fn send(transport: &mut RetryTransport<FlakyTransport>, msg: &TestMessage) -> Result<()> {
+        futures::executor::block_on(transport.send_message(msg))
+    }
+
+    #[cfg(not(feature = "async"))]
+    fn recv(transport: &mut RetryTransport<FlakyTransport>, link: &u64) -> Result<TestMessage> {
+        transport.recv_message(link)
+    }
+
+    #[cfg(feature = "async")]
+    fn recv(transport: &mut RetryTransport<FlakyTransport>, link: &u64) -> Result<TestMessage> {
+        futures::executor::block_on(transport.recv_message(link))
+    }
+
+    #[test]
+    fn retries_until_success() {
+        let mut transport = retry_transport(2, 3);
+        assert!(dbg!(send(&mut transport, &TestMessage(1))).is_ok());
+        assert_eq!(transport.transport().attempts, 3);
+
+        transport.transport_mut().failures = 5;
+        assert_eq!(recv(&mut transport, &1).ok(), Some(TestMessage(1)));
+        assert_eq!(transport.transport().attempts, 6);
+    }
+
+    #[test]
+    fn gives_up_after_max_attempts() {
+        let mut transport = retry_transport(3, 3);
+        let err = send(&mut transport, &TestMessage(1)).unwrap_err();
+        assert!(matches!(err.downcast_ref::<Errors>(), Some(ClientOperationFailure)));
+        assert_eq!(transport.transport().attempts, 3);
+    }
+
+    #[test]
+    fn does_not_retry_missing_messages() {
+        let mut transport = retry_transport(0, 3);
+        assert!(recv(&mut transport, &1).is_err());
+        assert_eq!(transport.transport().attempts, 1);
+        assert!(!is_transient(&anyhow!(MessageLinkNotFoundInTangle("1".to_string()))));
+    }
+
+    #[test]
+    fn retries_missing_messages_if_requested() {
+        let mut transport = retry_transport_if(0, 3, is_transient_or_missing);
+        assert!(recv(&mut transport, &1).is_err());
+        assert_eq!(transport.transport().attempts, 3);
+
+        assert!(send(&mut transport, &TestMessage(1)).is_ok());
+        assert_eq!(recv(&mut transport, &1).ok(), Some(TestMessage(1)));
+        assert_eq!(transport.transport().attempts, 5);
+
+        assert!(is_transient_or_missing(&anyhow!(ClientOperationFailure)));
+        assert!(is_transient_or_missing(&anyhow!(MessageLinkNotFound("1".to_string()))));
+        assert!(is_transient_or_missing(&anyhow!(MessageLinkNotFoundInTangle("1".to_string()))));
+        assert!(!is_transient_or_missing(&anyhow!(Errors::MessageNotUnique("1".to_string()))));
+    }
+}
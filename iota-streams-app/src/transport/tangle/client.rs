@@ -1,9 +1,9 @@
 //#[cfg(not(feature = "async"))]
 use smol::block_on;
 
-#[cfg(feature = "async")]
+#[cfg(all(feature = "async", not(feature = "async-send")))]
 use iota_streams_core::prelude::Rc;
-#[cfg(feature = "async")]
+#[cfg(all(feature = "async", not(feature = "async-send")))]
 use core::cell::RefCell;
 
 use iota::{
@@ -64,7 +64,7 @@ fn get_hash(tx_address: &[u8], tx_tag: &[u8]) ->  Result<String>  {
 /// Reconstruct Streams Message from bundle. The input bundle is not checked (for validity of
 /// the hash, consistency of indices, etc.). Checked bundles are returned by `(client.get_message().index`.
 pub fn msg_from_tangle_message<F>(message: &Message, link: &TangleAddress) -> Result<TangleMessage<F>> {
-    if let Payload::Indexation(i) = message.payload().as_ref().unwrap() {
+    if let Some(Payload::Indexation(i)) = message.payload() {
 
         let mut bytes = Vec::<u8>::new();
         for b in i.data() {
@@ -81,13 +81,13 @@ pub fn msg_from_tangle_message<F>(message: &Message, link: &TangleAddress) -> Re
     }
 }
 
-async fn get_messages(client: &iota_client::Client, tx_address: &[u8], tx_tag: &[u8]) -> Result<Vec<Message>> {
-    let hash = get_hash(tx_address, tx_tag)?;
+async fn get_messages(client: &iota_client::Client, link: &TangleAddress) -> Result<Vec<Message>> {
+    let hash = get_hash(link.appinst.as_ref(), link.msgid.as_ref())?;
     let msg_ids = handle_client_result(client.get_message()
             .index(&hash.to_string())
             .await
-        ).unwrap();
-    try_or!(!msg_ids.is_empty(), IndexNotFound)?;
+        )?;
+    try_or!(!msg_ids.is_empty(), MessageLinkNotFoundInTangle(link.to_string()))?;
 
     let msgs = join_all(
         msg_ids.iter().map(|msg| {
@@ -96,10 +96,11 @@ async fn get_messages(client: &iota_client::Client, tx_address: &[u8], tx_tag: &
                     .get_message()
                     .data(msg)
                     .await
-                ).unwrap()
+                )
             }
         }
     )).await;
+    let msgs = msgs.into_iter().collect::<Result<Vec<_>>>()?;
     try_or!(!msgs.is_empty(), MessageContentsNotFound)?;
     Ok(msgs)
 }
@@ -114,24 +115,24 @@ pub async fn async_send_message_with_options<F>(client: &iota_client::Client, ms
     }
 
     //TODO: Get rid of copy caused by to_owned
-    client
+    handle_client_result(client
         .message()
         .with_index(&hash.to_string())
         .with_data(bytes)
         .finish()
-        .await?;
+        .await
+    )?;
     Ok(())
 }
 
+/// Receive messages published at `link`. Failures of the node are reported as `ClientOperationFailure`,
+/// absence of messages as `MessageLinkNotFoundInTangle`.
 pub async fn async_recv_messages<F>(client: &iota_client::Client, link: &TangleAddress) -> Result<Vec<TangleMessage<F>>> {
-    let tx_address = link.appinst.as_ref();
-    let tx_tag = link.msgid.as_ref();
-    match get_messages(client, tx_address, tx_tag).await {
-        Ok(txs) => Ok(txs.iter()
-            .map(|b| msg_from_tangle_message(b, link).unwrap())
-            .collect()),
-        Err(_) => Ok(Vec::new()), // Just ignore the error?
-    }
+    get_messages(client, link)
+        .await?
+        .iter()
+        .map(|msg| msg_from_tangle_message(msg, link))
+        .collect()
 }
 
 #[cfg(not(feature = "async"))]
@@ -209,7 +210,8 @@ impl<F> Transport<TangleAddress, TangleMessage<F>> for Client {
 }
 
 #[cfg(feature = "async")]
-#[async_trait(?Send)]
+#[cfg_attr(feature = "async-send", async_trait)]
+#[cfg_attr(not(feature = "async-send"), async_trait(?Send))]
 impl<F> Transport<TangleAddress, TangleMessage<F>> for Client
 where
     F: 'static + core::marker::Send + core::marker::Sync,
@@ -236,7 +238,7 @@ where
 }
 
 // It's safe to impl async trait for Rc<RefCell<T>> targeting wasm as it's single-threaded.
-#[cfg(feature = "async")]
+#[cfg(all(feature = "async", not(feature = "async-send")))]
 #[async_trait(?Send)]
 impl<F> Transport<TangleAddress, TangleMessage<F>> for Rc<RefCell<Client>>
 where
@@ -263,7 +265,7 @@ where
             Ok(mut tsp) => {
                 let mut msgs = async_recv_messages(&tsp.client, link).await?;
                 if let Some(msg) = msgs.pop() {
-                    try_or!(msgs.is_empty(), MessageNotUnique(link.msgid.to_string()));
+                    try_or!(msgs.is_empty(), MessageNotUnique(link.msgid.to_string()))?;
                     Ok(msg)
                 } else {
                     err!(MessageLinkNotFound(link.msgid.to_string()))
@@ -273,3 +275,60 @@ where
         }
     }
 }
+
+#[cfg(all(test, not(feature = "async")))]
+mod tests {
+    use super::*;
+    use crate::transport::{
+        is_transient,
+        RetryOptions,
+        RetryTransport,
+    };
+    use core::time::Duration;
+    use iota_streams_core::Errors;
+    use iota_streams_core_keccak::sponge::prp::keccak::KeccakF1600;
+
+    type Message = TangleMessage<KeccakF1600>;
+
+    fn message() -> Message {
+        let link = TangleAddress::new(Default::default(), MsgId::from(&[1; 12][..]));
+        Message::with_timestamp(BinaryMessage::new(link, Vec::new().into()), 0)
+    }
+
+    fn unreachable_node() -> Client {
+        // Nothing listens on port 1, requests are refused right away.
+        Client::new_from_url("http://127.0.0.1:1")
+    }
+
+    fn assert_client_failure<T>(result: Result<T>) {
+        let err = result.err().expect("operation on unreachable node must fail");
+        assert!(matches!(err.downcast_ref::<Errors>(), Some(ClientOperationFailure)));
+        assert!(is_transient(&err));
+    }
+
+    #[test]
+    fn client_failures_are_reported() {
+        let mut client = unreachable_node();
+        let msg = message();
+        assert_client_failure(Transport::<_, Message>::send_message(&mut client, &msg));
+        assert_client_failure(Transport::<_, Message>::recv_messages(&mut client, &msg.binary.link));
+        assert_client_failure(Transport::<_, Message>::recv_message(&mut client, &msg.binary.link));
+    }
+
+    #[test]
+    fn client_failures_are_retried() {
+        let mut transport = RetryTransport::with_options(
+            unreachable_node(),
+            RetryOptions {
+                max_attempts: 2,
+                initial_delay: Duration::from_millis(1),
+                multiplier: 2,
+                max_delay: Duration::from_millis(2),
+                retry_if: is_transient,
+            },
+        );
+        let msg = message();
+        assert_client_failure(Transport::<_, Message>::send_message(&mut transport, &msg));
+        assert_client_failure(Transport::<_, Message>::recv_message(&mut transport, &msg.binary.link));
+    }
+}
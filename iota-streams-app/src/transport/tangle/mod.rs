@@ -57,12 +57,24 @@ pub struct TangleMessage<F> {
     pub timestamp: u64,
 }
 
+impl<F> PartialEq for TangleMessage<F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.binary == other.binary && self.timestamp == other.timestamp
+    }
+}
+
 impl<F> LinkedMessage<TangleAddress> for TangleMessage<F> {
     fn link(&self) -> &TangleAddress {
         self.binary.link()
     }
 }
 
+/// Current time in milliseconds since Unix epoch.
+#[cfg(feature = "std")]
+pub fn timestamp_now() -> u64 {
+    chrono::Utc::now().timestamp_millis() as u64
+}
+
 // TODO: Use better feature to detect `chrono::Utc::new()`.
 #[cfg(all(feature = "std"))]//, not(feature = "wasmbind")
 //#[cfg(all(feature = "std"))]
@@ -71,7 +83,7 @@ impl<F> TangleMessage<F> {
     pub fn new(msg: BinaryMessage<F, TangleAddress>) -> Self {
         Self {
             binary: msg,
-            timestamp: chrono::Utc::now().timestamp_millis() as u64,
+            timestamp: timestamp_now(),
         }
     }
 }
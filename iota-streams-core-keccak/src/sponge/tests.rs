@@ -1,10 +1,14 @@
 use super::prp::keccak::KeccakF1600;
 use iota_streams_core::{
+    psk,
     sponge::{
         prp::PRP,
         tests::*,
     },
-    prelude::typenum::Unsigned,
+    prelude::{
+        typenum::Unsigned,
+        Vec,
+    },
 };
 
 #[test]
@@ -26,3 +30,42 @@ fn encrypt_decrypt_keccak_byte() {
     encrypt_decrypt_n::<KeccakF1600>(rate + 28);
     encrypt_decrypt_n::<KeccakF1600>(2 * rate);
 }
+
+#[test]
+fn ratchet_keccak_byte() {
+    ratchet::<KeccakF1600>();
+}
+
+#[test]
+fn psk_derivation_keccak_byte() {
+    let seed = "A sufficiently long shared secret".as_bytes();
+    let psk = psk::new_psk::<KeccakF1600>(seed);
+    assert_eq!(
+        psk.as_slice(),
+        &[
+            0x68, 0xb7, 0x0f, 0x9e, 0x78, 0x8c, 0x1d, 0x18, 0x2c, 0x21, 0x7a, 0x92, 0xed, 0x74, 0x20, 0xf7,
+            0xe3, 0xa2, 0xf7, 0x31, 0x53, 0x55, 0xe4, 0x97, 0xee, 0x01, 0xef, 0x94, 0x56, 0x9e, 0x13, 0x05,
+        ][..]
+    );
+    let pskid = psk::pskid_from_psk::<KeccakF1600>(&psk);
+    assert_eq!(
+        pskid.as_slice(),
+        &[0xc9, 0xc1, 0xda, 0xc5, 0xf1, 0x57, 0x73, 0x58, 0xa3, 0xad, 0x38, 0xeb, 0x8f, 0xb1, 0x53, 0x61][..]
+    );
+
+    // Seed longer than the Keccak rate.
+    let seed: Vec<u8> = (0..200).collect();
+    let psk = psk::new_psk::<KeccakF1600>(&seed);
+    assert_eq!(
+        psk.as_slice(),
+        &[
+            0xdc, 0x6b, 0xeb, 0x76, 0xc7, 0x15, 0x8e, 0xbf, 0xe0, 0x09, 0x64, 0xb9, 0x5d, 0x12, 0x40, 0xe1,
+            0x3b, 0x42, 0x49, 0xa4, 0xb3, 0x8b, 0x03, 0x98, 0x0d, 0xef, 0xba, 0x25, 0x69, 0x41, 0xef, 0xb2,
+        ][..]
+    );
+    let pskid = psk::pskid_from_psk::<KeccakF1600>(&psk);
+    assert_eq!(
+        pskid.as_slice(),
+        &[0x80, 0x9d, 0x46, 0x31, 0xf5, 0xa9, 0x17, 0x14, 0xd1, 0xa2, 0x3e, 0xac, 0xc4, 0x7f, 0x73, 0x44][..]
+    );
+}
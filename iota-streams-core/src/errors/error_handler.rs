@@ -65,8 +65,24 @@ pub fn panic_if_not(cond: bool) {
     assert!(cond)
 }
 
+/// Wrapped error can still be downcast to `Errors` in order to find out the kind of the error.
 pub fn wrapped_err<T: Debug>(err: Errors, src: WrappedError<T>) -> anyhow::Error {
-    anyhow!("\n\tStreams Error: {}\n\t\tCause: {:?}", err, src.0)
+    let msg = format!("\n\tStreams Error: {}\n\t\tCause: {:?}", err, src.0);
+    anyhow!(err).context(msg)
 }
 
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrapped_err_keeps_message_and_kind() {
+        let err = wrapped_err(ClientOperationFailure, WrappedError("node unavailable"));
+        assert_eq!(
+            err.to_string(),
+            "\n\tStreams Error: Iota Client failed to perform operation.\n\t\tCause: \"node unavailable\""
+        );
+        assert!(matches!(err.downcast_ref::<Errors>(), Some(ClientOperationFailure)));
+    }
+}
@@ -68,6 +68,12 @@ pub enum Errors {
     MessageLinkNotFoundInTangle(String),
     #[error("Transport object is already borrowed")]
     TransportNotAvailable,
+    #[error("Transport storage operation failed")]
+    TransportStorageFailure,
+    #[error("Stored message {0} is malformed")]
+    MalformedStoredMessage(String),
+    #[error("Bucket dump version does not match (expected: {0}, found: {1})")]
+    BucketVersionRecoveryFailure(u8, u8),
 
 
     //////////
@@ -102,6 +108,8 @@ pub enum Errors {
     InvalidMsgVersion(u8, u8),
     #[error("Message frame type not supported (expected: {0}, found: {1})")]
     InvalidMsgType(u8, u8),
+    #[error("Message content version not supported (max: {0}, found: {1})")]
+    InvalidContentVersion(u8, u8),
     #[error("Message type is not known (found: {0})")]
     UnknownMsgType(u8),
     #[error("Reserved bits are improperly formatted")]
@@ -112,6 +120,16 @@ pub enum Errors {
     BadMessageInfo(u8),
     #[error("Failed to make message")]
     MessageCreationFailure,
+    #[error("Keyload is not signed by the channel author")]
+    KeyloadNotSigned,
+    #[error("Permission is not known (found: {0})")]
+    InvalidPermission(u8),
+    #[error("Payload frame size is too small (min: {0}, found: {1})")]
+    PayloadFrameSizeTooSmall(usize, usize),
+    #[error("Payload frame is out of order (expected: {0}, found: {1})")]
+    PayloadFrameMismatch(u32, u32),
+    #[error("Masked payload padding is malformed")]
+    InvalidPadding,
 
 
     //////////
@@ -133,6 +151,26 @@ pub enum Errors {
     SeqNumRetrievalFailure,
     #[error("State store has failed to retrieve")]
     StateStoreFailure,
+    #[error("Packet timestamp {0} is outside of the clock skew window (local time: {1})")]
+    TimestampOutOfRange(u64, u64),
+    #[error("Packet is not timestamped")]
+    MissingTimestamp,
+    #[error("Clock is not available to check packet timestamp")]
+    ClockNotAvailable,
+    #[error("Subscriber public key {0} is not registered in the channel")]
+    SubscriberNotFound(String),
+    #[error("Pre-shared key with id {0} is not stored")]
+    PskNotFound(String),
+    #[error("Channel {0} has already been announced")]
+    ChannelAlreadyAnnounced(String),
+    #[error("No keyload has been sent in the channel")]
+    KeyloadNotFound,
+    #[error("Packet sender {0} does not have writer role in the channel")]
+    NotAWriter(String),
+    #[error("Message {0} is not linked to a previous message")]
+    PreviousMessageNotFound(String),
+    #[error("Ratchet position {0} of packet is outside of the window (stored position: {1}, window: {2})")]
+    RatchetPositionOutOfRange(u64, u64, u64),
 
 
     //////////
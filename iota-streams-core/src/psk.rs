@@ -1,16 +1,22 @@
 //! Pre-shared key is a secret symmetric key shared between two parties and is used for
 //! (session) key exchange.
 
-use crate::prelude::{
-    generic_array::{
-        typenum::{
-            U16,
-            U32,
+use crate::{
+    prelude::{
+        generic_array::{
+            typenum::{
+                U16,
+                U32,
+            },
+            GenericArray,
         },
-        GenericArray,
+        HashMap,
+        Vec,
+    },
+    sponge::{
+        prp::PRP,
+        spongos::Spongos,
     },
-    HashMap,
-    Vec,
 };
 
 /// Size of pre-shared key identifier.
@@ -44,3 +50,44 @@ pub fn filter_psks<'a>(psks: &'a Psks, psk_ids: &'_ PskIds) -> Vec<IPsk<'a>> {
         .filter_map(|psk_id| psks.get_key_value(psk_id))
         .collect::<Vec<(&PskId, &Psk)>>()
 }
+
+/// Number of absorb/commit rounds applied to the seed by `new_psk`.
+pub const PSK_KDF_ROUNDS: u32 = 1 << 14;
+
+/// Derive pre-shared key from a secret seed, eg. a passphrase shared between the parties.
+///
+/// The seed and the domain string `IOTA Streams psk` are absorbed and committed, then
+/// `PSK_KDF_ROUNDS` rounds absorb the round number and commit, and `PSK_SIZE` bytes are squeezed.
+/// Each round costs a PRP transform, so every guess of an attacker costs `PSK_KDF_ROUNDS`
+/// transforms (about 25 ms with Keccak-f[1600] in a release build on a desktop CPU).
+///
+/// The rounds only slow brute-force down, they don't add entropy and no salt is used: equal
+/// seeds give equal keys in all channels. The seed should carry at least 128 bits of entropy,
+/// eg. 10 random diceware words or 22 random alphanumeric characters. Low-entropy passphrases
+/// can be recovered from any message encrypted with the key.
+pub fn new_psk<F: PRP>(seed: &[u8]) -> Psk {
+    let mut s = Spongos::<F>::init();
+    s.absorb(seed);
+    s.commit();
+    s.absorb("IOTA Streams psk".as_bytes());
+    s.commit();
+    for round in 0..PSK_KDF_ROUNDS {
+        s.absorb(round.to_be_bytes());
+        s.commit();
+    }
+    s.squeeze_arr()
+}
+
+/// Derive pre-shared key identifier from the pre-shared key.
+///
+/// The pre-shared key is absorbed and committed, then the domain string `IOTA Streams pskid`
+/// is absorbed and committed, and `PSKID_SIZE` bytes are squeezed. Both parties holding
+/// the same key derive the same identifier, so it doesn't need to be exchanged separately.
+pub fn pskid_from_psk<F: PRP>(psk: &Psk) -> PskId {
+    let mut s = Spongos::<F>::init();
+    s.absorb(psk);
+    s.commit();
+    s.absorb("IOTA Streams pskid".as_bytes());
+    s.commit();
+    s.squeeze_arr()
+}
@@ -290,6 +290,16 @@ impl<F: PRP> Spongos<F> {
         self.absorb(x.as_ref());
     }
 
+    /// Derive a new committed Spongos object from self in a one-way fashion.
+    /// A fresh object joins a fork of self and is committed, thus neither self nor
+    /// its inner state can be recovered from the result, even if it is not trimmed.
+    pub fn ratchet(&self) -> Self {
+        let mut s = Self::init();
+        s.join(&mut self.fork());
+        s.commit();
+        s
+    }
+
     /// Fork Spongos object into another.
     /// Essentially this just creates a clone of self.
     pub fn fork_at(&self, fork: &mut Self) {
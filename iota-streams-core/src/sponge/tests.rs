@@ -170,3 +170,21 @@ pub fn encrypt_decrypt_n<F: PRP>(n: usize)
         assert_eq!(tag, s4.squeeze_n(rate));
     }
 }
+
+pub fn ratchet<F: PRP>()
+{
+    let mut s = Spongos::<F>::init();
+    s.absorb(&vec![2; 32]);
+    s.commit();
+
+    let r1 = s.ratchet();
+    assert!(r1.is_committed());
+    // Ratchet depends on the inner state only, so it works the same for trimmed spongos.
+    let trimmed = Spongos::<F>::from(s.to_inner().unwrap());
+    assert!(r1.to_inner().unwrap() == trimmed.ratchet().to_inner().unwrap());
+
+    let r2 = r1.ratchet();
+    assert!(r1.to_inner().unwrap() != s.to_inner().unwrap());
+    assert!(r2.to_inner().unwrap() != r1.to_inner().unwrap());
+    assert!(r2.to_inner().unwrap() != s.to_inner().unwrap());
+}
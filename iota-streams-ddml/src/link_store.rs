@@ -17,7 +17,7 @@ use iota_streams_core::{
     try_or,
     err,
     LOCATION_LOG,
-    Errors::{GenericLinkNotFound, MessageLinkNotFoundInTangle}
+    Errors::{GenericLinkNotFound, MessageLinkNotFoundInTangle, RatchetPositionOutOfRange}
 };
 use core::fmt::Display;
 
@@ -125,6 +125,66 @@ where
     }
 }
 
+/// Maximal number of `Spongos::ratchet` steps a ratcheted spongos state can be advanced by at once.
+pub const RATCHET_WINDOW: u64 = 1024;
+
+/// Read-only view of a link store deriving spongos states of ratcheted links at ratchet `position`.
+///
+/// The stored state of a ratcheted link has already been advanced to the position recorded for
+/// the link in `positions`, links without a recorded position are at position 0. The state is
+/// advanced with `Spongos::ratchet` from the stored position up to `position`. Earlier positions
+/// can't be derived anymore, and positions more than `RATCHET_WINDOW` steps ahead of the stored
+/// one are rejected without ratcheting, so the work of a lookup is bounded whatever the position.
+/// The underlying states are left intact, updates and inserts are ignored.
+pub struct RatchetLinkStore<'a, Store, Link> {
+    store: &'a Store,
+    positions: &'a [(Link, u64)],
+    position: u64,
+}
+
+impl<'a, Store, Link> RatchetLinkStore<'a, Store, Link> {
+    pub fn new(store: &'a Store, positions: &'a [(Link, u64)], position: u64) -> Self {
+        Self {
+            store,
+            positions,
+            position,
+        }
+    }
+}
+
+impl<'a, F: PRP, Link, Store> LinkStore<F, Link> for RatchetLinkStore<'a, Store, Link>
+where
+    Link: Eq,
+    Store: LinkStore<F, Link>,
+{
+    type Info = <Store as LinkStore<F, Link>>::Info;
+    fn lookup(&self, link: &Link) -> Result<(Spongos<F>, Self::Info)> {
+        let stored = self
+            .positions
+            .iter()
+            .find(|(l, _)| l == link)
+            .map_or(0, |(_, position)| *position);
+        try_or!(
+            stored <= self.position && self.position - stored <= RATCHET_WINDOW,
+            RatchetPositionOutOfRange(self.position, stored, RATCHET_WINDOW)
+        )?;
+        let (mut spongos, info) = self.store.lookup(link)?;
+        for _ in stored..self.position {
+            spongos = spongos.ratchet();
+        }
+        Ok((spongos, info))
+    }
+    fn update(&mut self, _link: &Link, _spongos: Spongos<F>, _info: Self::Info) -> Result<()> {
+        Ok(())
+    }
+    fn insert(&mut self, _link: &Link, _spongos: Inner<F>, _info: Self::Info) -> Result<()> {
+        Ok(())
+    }
+    fn iter(&self) -> Vec<(&Link, &(Inner<F>, Self::Info))> {
+        self.store.iter()
+    }
+}
+
 pub struct DefaultLinkStore<F: PRP, Link, Info> {
     map: HashMap<Link, (Inner<F>, Info)>,
     _phantom: core::marker::PhantomData<F>,
@@ -178,3 +238,41 @@ where
         self.map.iter().collect()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use iota_streams_core::{
+        prelude::typenum::U32,
+        Errors::ValueMismatch,
+    };
+    use iota_streams_core_keccak::sponge::prp::keccak::KeccakF1600;
+
+    fn squeeze(mut spongos: Spongos<KeccakF1600>) -> Vec<u8> {
+        spongos.squeeze_arr::<U32>().to_vec()
+    }
+
+    #[test]
+    fn ratchet_window() -> Result<()> {
+        let mut base = Spongos::<KeccakF1600>::init();
+        base.absorb("ratchet base".as_bytes());
+        base.commit();
+        let expected = squeeze(base.ratchet().ratchet().ratchet());
+
+        // Stored state of link 1 has been advanced to position 2.
+        let mut store = DefaultLinkStore::<KeccakF1600, u32, ()>::default();
+        store.update(&1, base.ratchet().ratchet(), ())?;
+        let positions = [(1_u32, 2_u64)];
+
+        let (spongos, ()) = RatchetLinkStore::new(&store, &positions, 3).lookup(&1)?;
+        let ratcheted = squeeze(spongos);
+        try_or!(ratcheted == expected, ValueMismatch(3, 2))?;
+        try_or!(RatchetLinkStore::new(&store, &positions, 2 + RATCHET_WINDOW).lookup(&1).is_ok(), ValueMismatch(1, 0))?;
+
+        // Positions behind the stored one and beyond the window are rejected.
+        try_or!(RatchetLinkStore::new(&store, &positions, 1).lookup(&1).is_err(), ValueMismatch(0, 1))?;
+        try_or!(RatchetLinkStore::new(&store, &positions, 3 + RATCHET_WINDOW).lookup(&1).is_err(), ValueMismatch(0, 1))?;
+        try_or!(RatchetLinkStore::new(&store, &positions, u64::MAX).lookup(&1).is_err(), ValueMismatch(0, 1))?;
+        Ok(())
+    }
+}